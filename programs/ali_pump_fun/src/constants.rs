@@ -1,14 +1,37 @@
 use std::str::FromStr;
 use anchor_lang::solana_program::pubkey::Pubkey;
 
-pub const QUOTE_MINT_STR: &'static str = "9wvorGtBJ8gyLorFTmwXWcymPoGVUBn6MRzHwFpCdCeC"; // Quote (ALI) mint address
-
 pub const DEFAULT_OWNER: &'static str = "4Gstv5b2EhxrF5b11E8QiJ4oPa5dgRRrQCQ9tQKT4TZT"; // Default owner address
+pub const METADATA_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bWg2YKB4s6s"; // Metaplex Token Metadata program
+pub const METADATA_SEED: &[u8] = b"metadata"; // Seed of Metaplex metadata account
+
+pub const MAX_NAME_LENGTH: usize = 32; // Metaplex limits of token metadata
+pub const MAX_SYMBOL_LENGTH: usize = 10;
+pub const MAX_URI_LENGTH: usize = 200;
 
 pub const FEE_PER_DIV: u128 = 1000; // 1000 for 1%
 pub const MAX_TRADING_FEE: u64 = 5 * FEE_PER_DIV as u64; // 5%
+pub const MAX_FEE_SHARE: u64 = 100 * FEE_PER_DIV as u64; // 100% of trading fee
+
+pub const DEFAULT_UPDATE_DELAY: i64 = 2 * 24 * 60 * 60; // 2 days before queued MainState update can be executed
+pub const MAX_UPDATE_DELAY: i64 = 30 * 24 * 60 * 60; // 30 days
+
+pub const MAX_ANTI_SNIPE_SLOTS: u64 = 9_000; // ~1 hour
+pub const MAX_ANTI_SNIPE_SECONDS: u64 = 60 * 60; // 1 hour
+
+pub const DEFAULT_VESTING_DURATION: i64 = 365 * 24 * 60 * 60; // 1 year of linear release of locked unsold base tokens
+pub const MAX_VESTING_DURATION: i64 = 4 * 365 * 24 * 60 * 60; // 4 years
 
 pub const TOTAL_SUPPLY: u64 = 1_000_000_000_000_000; // 1 billion token
 pub const INIT_VIRT_BASE_RESERVE: u64 = 66_666_666_000_000; // ~6.6666666% of total supply
 pub const INIT_VIRT_QUOTE_RESERVE: u64 = 100_000_00_000_000; // 100k ALI
 pub const REAL_QUOTE_THRESHOLD: u64 = 300_000_00_000_000; // +300k ALI
+
+pub const PRICE_FRACTIONAL_BITS: u32 = 64; // Prices are Q64.64 fixed point amounts of quote per base token
+pub const OBSERVATION_COUNT: usize = 256; // Number of trade samples kept in PoolObservations ring buffer
+
+pub const STEPPED_CURVE_STEPS: u64 = 10; // Number of price tranches of the stepped curve
+pub const EXP_CURVE_DOUBLINGS: u64 = 4; // Number of price doublings of the exponential curve until completion
+
+pub const MERKLE_LEAF_PREFIX: &[u8] = &[0x00]; // Presale Merkle leaves and nodes are hashed with different prefixes
+pub const MERKLE_NODE_PREFIX: &[u8] = &[0x01]; //   so that an internal node can't be passed off as a leaf
@@ -50,5 +50,123 @@ pub enum PumpFunError {
     #[msg("BondingCurve complete")]
     BondingCurveComplete,
     #[msg("BondingCurve already withdrawn")]
-    BondingCurveAlreadyWithdrawn
+    BondingCurveAlreadyWithdrawn,
+
+    #[msg("Invalid allowed curve types")]
+    InvalidAllowedCurves,
+    #[msg("Curve type is not allowed")]
+    CurveTypeNotAllowed,
+
+    #[msg("Too much input base")]
+    TooMuchInputBase,
+    #[msg("Too much output quote")]
+    TooMuchOutputQuote,
+
+    #[msg("Invalid fee share")]
+    InvalidFeeShare,
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+
+    #[msg("Invalid referrer")]
+    InvalidReferrer,
+    #[msg("Referrer accounts are missing")]
+    MissingReferrerAccounts,
+
+    #[msg("Paused")]
+    Paused,
+    #[msg("Pause state unchanged")]
+    PauseStateUnchanged,
+
+    #[msg("Invalid pending owner")]
+    InvalidPendingOwner,
+    #[msg("No pending owner")]
+    NoPendingOwner,
+
+    #[msg("Invalid update delay")]
+    InvalidUpdateDelay,
+    #[msg("Queued update is not ready to execute")]
+    UpdateNotReady,
+
+    #[msg("Initial buy accounts are missing")]
+    MissingInitialBuyAccounts,
+
+    #[msg("Invalid token metadata")]
+    InvalidTokenMetadata,
+
+    #[msg("Base token mint extension is not supported")]
+    UnsupportedMintExtension,
+
+    #[msg("Invalid anti-snipe settings")]
+    InvalidAntiSnipeSettings,
+    #[msg("Max buy per wallet exceeded during anti-snipe window")]
+    MaxBuyPerWalletExceeded,
+    #[msg("User position account is missing")]
+    MissingUserPosition,
+
+    #[msg("Trading has not started yet")]
+    TradingNotStarted,
+
+    #[msg("Presale can't be set on this pool")]
+    InvalidPresale,
+    #[msg("Only allowlisted wallets can buy during presale")]
+    PresaleActive,
+    #[msg("Presale is not active")]
+    PresaleNotActive,
+    #[msg("Invalid Merkle proof")]
+    InvalidMerkleProof,
+    #[msg("Presale allocation exceeded")]
+    PresaleAllocationExceeded,
+    #[msg("Presale claim account is missing")]
+    MissingPresaleClaim,
+
+    #[msg("Deadline must be in the future and after trading start time")]
+    InvalidDeadline,
+    #[msg("Pool deadline has passed")]
+    DeadlinePassed,
+    #[msg("Pool is not in refund state")]
+    RefundNotActive,
+
+    #[msg("BondingCurve is not withdrawn or migrated yet")]
+    BondingCurveNotWithdrawn,
+
+    #[msg("Withdrawal shares must not exceed 100%")]
+    InvalidWithdrawalPolicy,
+    #[msg("Withdraw amount must be greater than 0 and not exceed reserves")]
+    InvalidWithdrawAmount,
+
+    #[msg("Invalid vesting duration")]
+    InvalidVestingDuration,
+    #[msg("Vesting accounts are missing")]
+    MissingVestingAccounts,
+    #[msg("Nothing to release")]
+    NothingToRelease,
+
+    #[msg("Completion reward share must not exceed 100%")]
+    InvalidCompletionReward,
+
+    #[msg("User position can't be closed during anti-snipe window")]
+    UserPositionLocked,
+
+    #[msg("PoolObservations account is missing")]
+    MissingPoolObservations,
+
+    #[msg("Base token transfer fee authorities must be revoked")]
+    BaseTokenTransferFeeMustNotBeManaged,
+    #[msg("AMM only supports SPL Token base tokens")]
+    UnsupportedAmmBaseToken,
+
+    #[msg("Math overflow")]
+    Overflow,
+
+    #[msg("Scheduled trading start requires anti-snipe window in seconds")]
+    ScheduledStartWithSlotWindow,
+
+    #[msg("Quote reserves are not fully refunded yet")]
+    RefundIncomplete,
+
+    #[msg("Unsold base tokens must be reclaimed first")]
+    UnsoldNotReclaimed,
+
+    #[msg("UserPosition doesn't belong to the pool")]
+    InvalidUserPosition
 }
@@ -22,17 +22,49 @@ pub mod ali_pump_fun {
         main_state::init_main_state(ctx)
     }
 
-    pub fn transfer_ownership(ctx: Context<ATransferOwnership>, new_owner: Pubkey) -> Result<()> {
-        main_state::transfer_ownership(ctx, new_owner)
+    pub fn propose_owner(ctx: Context<AProposeOwner>, new_owner: Pubkey) -> Result<()> {
+        main_state::propose_owner(ctx, new_owner)
+    }
+
+    pub fn accept_ownership(ctx: Context<AAcceptOwnership>) -> Result<()> {
+        main_state::accept_ownership(ctx)
+    }
+
+    pub fn cancel_ownership_proposal(ctx: Context<AProposeOwner>) -> Result<()> {
+        main_state::cancel_ownership_proposal(ctx)
     }
     
-    pub fn update_main_state(ctx: Context<AUpdateMainState>, input: UpdateMainStateInput) -> Result<()> {
-        main_state::update_main_state(ctx, input)
+    pub fn queue_main_state_update(ctx: Context<AQueueMainStateUpdate>, input: UpdateMainStateInput) -> Result<()> {
+        main_state::queue_main_state_update(ctx, input)
+    }
+
+    pub fn execute_main_state_update(ctx: Context<AExecuteMainStateUpdate>) -> Result<()> {
+        main_state::execute_main_state_update(ctx)
+    }
+
+    pub fn cancel_main_state_update(ctx: Context<ACancelMainStateUpdate>) -> Result<()> {
+        main_state::cancel_main_state_update(ctx)
+    }
+
+    pub fn add_quote_mint(ctx: Context<AAddQuoteMint>, input: QuoteConfigInput) -> Result<()> {
+        main_state::add_quote_mint(ctx, input)
+    }
+
+    pub fn remove_quote_mint(ctx: Context<ARemoveQuoteMint>) -> Result<()> {
+        main_state::remove_quote_mint(ctx)
+    }
+
+    pub fn set_global_pause(ctx: Context<ASetGlobalPause>, paused: bool) -> Result<()> {
+        main_state::set_global_pause(ctx, paused)
     }
 
     
-    pub fn create_pool(ctx: Context<ACreatePool>) -> Result<()> {
-        pool::create_pool(ctx)
+    pub fn create_pool(ctx: Context<ACreatePool>, curve_type: CurveType, initial_buy_quote_amount: Option<u64>, trading_start_ts: Option<i64>, deadline: Option<i64>) -> Result<()> {
+        pool::create_pool(ctx, curve_type, initial_buy_quote_amount, trading_start_ts, deadline)
+    }
+
+    pub fn launch_token(ctx: Context<ALaunchToken>, name: String, symbol: String, uri: String, decimals: u8, curve_type: CurveType) -> Result<()> {
+        pool::launch_token(ctx, name, symbol, uri, decimals, curve_type)
     }
 
     pub fn buy_tokens_from_exact_quote(ctx: Context<ABuy>, quote_amount: u64, min_base_amount: u64) -> Result<()> {
@@ -43,11 +75,83 @@ pub mod ali_pump_fun {
         pool::buy_exact_tokens_from_quote(ctx, base_amount, max_quote_amount)
     }
 
+    pub fn presale_buy(ctx: Context<ABuy>, quote_amount: u64, min_base_amount: u64, allocation: u64, proof: Vec<[u8; 32]>) -> Result<()> {
+        pool::presale_buy(ctx, quote_amount, min_base_amount, allocation, proof)
+    }
+
     pub fn sell(ctx: Context<ASell>, amount: u64, min_quote_output: u64) -> Result<()> {
         pool::sell(ctx, amount, min_quote_output)
     }
+
+    pub fn sell_tokens_for_exact_quote(ctx: Context<ASell>, quote_amount_out: u64, max_base_in: u64) -> Result<()> {
+        pool::sell_tokens_for_exact_quote(ctx, quote_amount_out, max_base_in)
+    }
+
+    pub fn close_user_position(ctx: Context<ACloseUserPosition>) -> Result<()> {
+        pool::close_user_position(ctx)
+    }
+    
+    pub fn quote_buy_exact_quote(ctx: Context<AQuote>, quote_amount: u64) -> Result<TradeQuote> {
+        pool::quote_buy_exact_quote(ctx, quote_amount)
+    }
+
+    pub fn quote_buy_exact_base(ctx: Context<AQuote>, base_amount: u64) -> Result<TradeQuote> {
+        pool::quote_buy_exact_base(ctx, base_amount)
+    }
+
+    pub fn quote_sell(ctx: Context<AQuote>, base_amount: u64) -> Result<TradeQuote> {
+        pool::quote_sell(ctx, base_amount)
+    }
+
+    pub fn observe_price(ctx: Context<AQuote>) -> Result<PriceObservation> {
+        pool::observe_price(ctx)
+    }
     
-    pub fn withdraw(ctx: Context<AWithdraw>) -> Result<()> {
-        pool::withdraw(ctx)
+    pub fn claim_creator_fees(ctx: Context<AClaimCreatorFees>) -> Result<()> {
+        pool::claim_creator_fees(ctx)
+    }
+
+    pub fn claim_completion_reward(ctx: Context<AClaimCompletionReward>) -> Result<()> {
+        pool::claim_completion_reward(ctx)
+    }
+
+    pub fn set_pool_pause(ctx: Context<ASetPoolPause>, paused: bool) -> Result<()> {
+        pool::set_pool_pause(ctx, paused)
+    }
+
+    pub fn set_presale(ctx: Context<ASetPresale>, merkle_root: [u8; 32], presale_end_ts: i64) -> Result<()> {
+        pool::set_presale(ctx, merkle_root, presale_end_ts)
+    }
+
+    pub fn init_pool_observations(ctx: Context<AInitPoolObservations>) -> Result<()> {
+        pool::init_pool_observations(ctx)
+    }
+
+    pub fn refund(ctx: Context<ARefund>, base_amount: u64) -> Result<()> {
+        pool::refund(ctx, base_amount)
+    }
+
+    pub fn reclaim_unsold(ctx: Context<AReclaimUnsold>) -> Result<()> {
+        pool::reclaim_unsold(ctx)
+    }
+
+    pub fn withdraw(ctx: Context<AWithdraw>, base_amount: u64, quote_amount: u64) -> Result<()> {
+        pool::withdraw(ctx, base_amount, quote_amount)
+    }
+
+    pub fn release_vested_base(ctx: Context<AReleaseVestedBase>) -> Result<()> {
+        pool::release_vested_base(ctx)
+    }
+
+    pub fn migrate_pool(ctx: Context<AMigratePool>) -> Result<()> {
+        pool::migrate_pool(ctx)
+    }
+
+    pub fn close_pool(ctx: Context<AClosePool>) -> Result<()> {
+        pool::close_pool(ctx)
+    }
+
+    pub fn close_refunded_pool(ctx: Context<ACloseRefundedPool>) -> Result<()> {
+        pool::close_refunded_pool(ctx)
     }
 }
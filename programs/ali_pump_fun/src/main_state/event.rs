@@ -1,19 +1,47 @@
 use anchor_lang::prelude::*;
+use crate::{UpdateMainStateInput, WindowUnit, WithdrawalPolicy, UnsoldBaseAction, CompletionReward};
 
 // MainState initialization event
 #[event]
 pub struct MainStateInitialized {
     pub owner: Pubkey,
     pub withdrawer: Pubkey,
+    pub pauser: Pubkey,
     pub fee_recipient: Pubkey,
     pub trading_fee: u64,
     pub total_supply: u64,
     pub init_virt_base_reserves: u64,
     pub init_virt_quote_reserves: u64,
     pub real_quote_threshold: u64,
+    pub update_delay: i64,
+    pub allowed_curves: u8,
+    pub creator_fee_share: u64,
+    pub referral_fee_share: u64,
+    pub anti_snipe_window: u64,
+    pub anti_snipe_window_unit: WindowUnit,
+    pub max_buy_per_wallet: u64,
+    pub withdrawal_policy: WithdrawalPolicy,
+    pub unsold_base_action: UnsoldBaseAction,
+    pub vesting_duration: i64,
+    pub creator_completion_reward: CompletionReward,
+    pub track_positions: bool,
 }
 
-// Transfer ownership event
+// Ownership proposal event
+#[event]
+pub struct OwnershipProposed {
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+}
+
+// Ownership proposal cancel event
+#[event]
+pub struct OwnershipProposalCancelled {
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+}
+
+// Transfer ownership event (emitted when the proposed owner accepts)
 #[event]
 pub struct OwnershipTransferred {
     pub previous_owner: Pubkey,
@@ -24,10 +52,68 @@ pub struct OwnershipTransferred {
 #[event]
 pub struct MainStateUpdated {
     pub withdrawer: Pubkey,
+    pub pauser: Pubkey,
     pub fee_recipient: Pubkey,
     pub trading_fee: u64,
     pub total_supply: u64,
     pub init_virt_base_reserves: u64,
     pub init_virt_quote_reserves: u64,
     pub real_quote_threshold: u64,
+    pub update_delay: i64,
+    pub allowed_curves: u8,
+    pub creator_fee_share: u64,
+    pub referral_fee_share: u64,
+    pub anti_snipe_window: u64,
+    pub anti_snipe_window_unit: WindowUnit,
+    pub max_buy_per_wallet: u64,
+    pub withdrawal_policy: WithdrawalPolicy,
+    pub unsold_base_action: UnsoldBaseAction,
+    pub vesting_duration: i64,
+    pub creator_completion_reward: CompletionReward,
+    pub track_positions: bool,
+}
+
+// MainState update queued event
+#[event]
+pub struct MainStateUpdateQueued {
+    pub input: UpdateMainStateInput,    // Queued MainState update parameters
+    pub eta: i64,                       // Earliest execution time
+}
+
+// MainState update cancelled event
+#[event]
+pub struct MainStateUpdateCancelled {
+    pub input: UpdateMainStateInput,    // Cancelled MainState update parameters
+    pub eta: i64,                       // Earliest execution time it had
+}
+
+// Quote token whitelisted event
+#[event]
+pub struct QuoteMintAdded {
+    pub quote_mint: Pubkey,
+    pub init_virt_quote_reserves: u64,
+    pub real_quote_threshold: u64,
+    pub trading_fee: Option<u64>,
+}
+
+// Quote token removed from whitelist event
+#[event]
+pub struct QuoteMintRemoved {
+    pub quote_mint: Pubkey,
+}
+
+// Pause event (pool is None when the whole program is paused)
+#[event]
+pub struct Paused {
+    pub pauser: Pubkey,             // Pauser wallet address
+    pub pool: Option<Pubkey>,       // Paused PoolState address
+    pub timestamp: i64,             // Pause time
+}
+
+// Unpause event (pool is None when the whole program is unpaused)
+#[event]
+pub struct Unpaused {
+    pub pauser: Pubkey,             // Pauser wallet address
+    pub pool: Option<Pubkey>,       // Unpaused PoolState address
+    pub timestamp: i64,             // Unpause time
 }
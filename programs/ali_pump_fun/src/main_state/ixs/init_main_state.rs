@@ -1,6 +1,6 @@
 use crate::{
-    constants::{QUOTE_MINT_STR, DEFAULT_OWNER, TOTAL_SUPPLY, INIT_VIRT_BASE_RESERVE, INIT_VIRT_QUOTE_RESERVE, REAL_QUOTE_THRESHOLD, FEE_PER_DIV},
-    MainState,
+    constants::{DEFAULT_OWNER, TOTAL_SUPPLY, INIT_VIRT_BASE_RESERVE, INIT_VIRT_QUOTE_RESERVE, REAL_QUOTE_THRESHOLD, FEE_PER_DIV, DEFAULT_UPDATE_DELAY, DEFAULT_VESTING_DURATION},
+    MainState, CurveType, WindowUnit, WithdrawalPolicy, UnsoldBaseAction, CompletionReward,
     MainStateInitialized,
     error::PumpFunError
 };
@@ -18,7 +18,10 @@ pub fn init_main_state(ctx: Context<AInitMainState>) -> Result<()> {
 
     // Initialize all members
     state.owner = ctx.accounts.owner.key();
+    state.pending_owner = Pubkey::default();
     state.withdrawer = ctx.accounts.owner.key();
+    state.pauser = ctx.accounts.owner.key();
+    state.paused = false;
     
     state.fee_recipient = ctx.accounts.owner.key();
     state.trading_fee = 1 * FEE_PER_DIV as u64; // 1%
@@ -27,16 +30,46 @@ pub fn init_main_state(ctx: Context<AInitMainState>) -> Result<()> {
     state.init_virt_base_reserves = INIT_VIRT_BASE_RESERVE;
     state.init_virt_quote_reserves = INIT_VIRT_QUOTE_RESERVE;
     state.real_quote_threshold = REAL_QUOTE_THRESHOLD;
+    state.update_delay = DEFAULT_UPDATE_DELAY;
+    state.allowed_curves = CurveType::ConstantProduct.mask(); // Only constant product curve
+    state.creator_fee_share = 0; // Whole trading fee goes to fee recipient
+    state.referral_fee_share = 0;
+    state.anti_snipe_window = 0; // Anti-snipe window disabled
+    state.anti_snipe_window_unit = WindowUnit::Slots;
+    state.max_buy_per_wallet = 0;
+    state.withdrawal_policy = WithdrawalPolicy {
+        treasury: ctx.accounts.owner.key(),
+        creator_share: 0, // Whole proceeds go to treasury
+        migration_escrow: ctx.accounts.owner.key(),
+        migration_escrow_share: 0,
+    };
+    state.unsold_base_action = UnsoldBaseAction::Burn; // Unsold base tokens are burnt
+    state.vesting_duration = DEFAULT_VESTING_DURATION;
+    state.creator_completion_reward = CompletionReward::Fixed(0); // No completion reward
+    state.track_positions = true;
 
     emit!(MainStateInitialized {
         owner: state.owner,
         withdrawer: state.withdrawer,
+        pauser: state.pauser,
         fee_recipient: state.fee_recipient,
         trading_fee: state.trading_fee,
         total_supply: state.total_supply,
         init_virt_base_reserves: state.init_virt_base_reserves,
         init_virt_quote_reserves: state.init_virt_quote_reserves,
-        real_quote_threshold: state.real_quote_threshold
+        real_quote_threshold: state.real_quote_threshold,
+        update_delay: state.update_delay,
+        allowed_curves: state.allowed_curves,
+        creator_fee_share: state.creator_fee_share,
+        referral_fee_share: state.referral_fee_share,
+        anti_snipe_window: state.anti_snipe_window,
+        anti_snipe_window_unit: state.anti_snipe_window_unit,
+        max_buy_per_wallet: state.max_buy_per_wallet,
+        withdrawal_policy: state.withdrawal_policy,
+        unsold_base_action: state.unsold_base_action,
+        vesting_duration: state.vesting_duration,
+        creator_completion_reward: state.creator_completion_reward,
+        track_positions: state.track_positions
     });
 
     Ok(())
@@ -59,10 +92,7 @@ pub struct AInitMainState<'info> {
     )]
     pub main_state: Box<Account<'info, MainState>>, // MainState account
 
-    #[account(
-        constraint = quote_mint.key().to_string() == QUOTE_MINT_STR @ PumpFunError::UnknownQuoteMint
-    )]
-    pub quote_mint: Box<Account<'info, Mint>>,
+    pub quote_mint: Box<Account<'info, Mint>>, // Quote token mint of the fee recipient's ATA (whitelisted later by add_quote_mint)
     #[account(
         init,
         payer = owner,
@@ -6,3 +6,9 @@ pub use transfer_ownership::*;
 
 pub mod update_main_state;
 pub use update_main_state::*;
+
+pub mod quote_config;
+pub use quote_config::*;
+
+pub mod set_global_pause;
+pub use set_global_pause::*;
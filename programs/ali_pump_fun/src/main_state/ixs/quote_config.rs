@@ -0,0 +1,110 @@
+use crate::{
+    constants::MAX_TRADING_FEE,
+    error::PumpFunError,
+    MainState, QuoteConfig,
+    QuoteMintAdded, QuoteMintRemoved,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+// QuoteConfig parameters
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, Copy)]
+pub struct QuoteConfigInput {
+    init_virt_quote_reserves: Option<u64>,  // Initial virtual quote token reserves (optional, MainState's if None)
+    real_quote_threshold: Option<u64>,      // Real quote token threshold (optional, MainState's if None)
+    trading_fee: Option<u64>,               // Trading fee override (optional, MainState's applies if None)
+}
+
+// This function whitelists a quote token, pools can be created against it afterwards
+// Params
+//   ctx - AddQuoteMint context
+//   input - QuoteConfig parameters
+// Return
+//   Ok on success, ErrorCode on failure
+//     QuoteMintAdded is emitted on success
+pub fn add_quote_mint(ctx: Context<AAddQuoteMint>, input: QuoteConfigInput) -> Result<()> {
+    let main_state = &ctx.accounts.main_state;
+    let quote_config = &mut ctx.accounts.quote_config;
+
+    let init_virt_quote_reserves = input.init_virt_quote_reserves.unwrap_or(main_state.init_virt_quote_reserves);
+    let real_quote_threshold = input.real_quote_threshold.unwrap_or(main_state.real_quote_threshold);
+    require!(init_virt_quote_reserves > 0, PumpFunError::InvalidInitVirtQuoteReserves);
+    require!(real_quote_threshold > 0, PumpFunError::InvalidRealQuoteThreshold);
+    if let Some(trading_fee) = input.trading_fee {
+        require!(trading_fee.le(&MAX_TRADING_FEE), PumpFunError::InvalidFee);
+    }
+
+    quote_config.quote_mint = ctx.accounts.quote_mint.key();
+    quote_config.init_virt_quote_reserves = init_virt_quote_reserves;
+    quote_config.real_quote_threshold = real_quote_threshold;
+    quote_config.trading_fee = input.trading_fee;
+
+    emit!(QuoteMintAdded {
+        quote_mint: quote_config.quote_mint,
+        init_virt_quote_reserves,
+        real_quote_threshold,
+        trading_fee: quote_config.trading_fee,
+    });
+
+    Ok(())
+}
+
+// This function removes a quote token from whitelist (existing pools keep trading)
+// Params
+//   ctx - RemoveQuoteMint context
+// Return
+//   Ok on success, ErrorCode on failure
+//     QuoteMintRemoved is emitted on success
+pub fn remove_quote_mint(ctx: Context<ARemoveQuoteMint>) -> Result<()> {
+    emit!(QuoteMintRemoved {
+        quote_mint: ctx.accounts.quote_config.quote_mint,
+    });
+
+    Ok(())
+}
+
+// AddQuoteMint context - passed with accounts
+#[derive(Accounts)]
+pub struct AAddQuoteMint<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>, // Current owner
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+        has_one = owner,
+    )]
+    pub main_state: Box<Account<'info, MainState>>, // MainState account
+
+    pub quote_mint: Box<Account<'info, Mint>>, // Quote token mint account
+    #[account(
+        init,
+        payer = owner,
+        seeds = [QuoteConfig::PREFIX_SEED, quote_mint.key().as_ref()],
+        bump,
+        space = 8 + QuoteConfig::MAX_SIZE
+    )]
+    pub quote_config: Box<Account<'info, QuoteConfig>>, // (New) QuoteConfig account
+
+    pub system_program: Program<'info, System>,
+}
+
+// RemoveQuoteMint context - passed with accounts
+#[derive(Accounts)]
+pub struct ARemoveQuoteMint<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>, // Current owner
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+        has_one = owner,
+    )]
+    pub main_state: Box<Account<'info, MainState>>, // MainState account
+
+    #[account(
+        mut,
+        seeds = [QuoteConfig::PREFIX_SEED, quote_config.quote_mint.as_ref()],
+        bump,
+        close = owner,
+    )]
+    pub quote_config: Box<Account<'info, QuoteConfig>>, // QuoteConfig account (closed)
+}
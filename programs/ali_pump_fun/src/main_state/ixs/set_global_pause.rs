@@ -0,0 +1,43 @@
+use crate::{
+    error::PumpFunError,
+    MainState,
+    Paused, Unpaused,
+};
+use anchor_lang::prelude::*;
+
+// This function pauses/unpauses the whole program
+// Params
+//   ctx - SetGlobalPause context
+//   paused - true to pause, false to unpause
+// Return
+//   Ok on success, ErrorCode on failure
+//     Paused or Unpaused is emitted on success
+pub fn set_global_pause(ctx: Context<ASetGlobalPause>, paused: bool) -> Result<()> {
+    let main_state = &mut ctx.accounts.main_state;
+    require!(main_state.paused.ne(&paused), PumpFunError::PauseStateUnchanged);
+
+    main_state.paused = paused;
+
+    let pauser = ctx.accounts.pauser.key();
+    let timestamp = Clock::get()?.unix_timestamp;
+    if paused {
+        emit!(Paused { pauser, pool: None, timestamp });
+    } else {
+        emit!(Unpaused { pauser, pool: None, timestamp });
+    }
+
+    Ok(())
+}
+
+// SetGlobalPause context - passed with accounts
+#[derive(Accounts)]
+pub struct ASetGlobalPause<'info> {
+    pub pauser: Signer<'info>, // Current pauser
+    #[account(
+        mut,
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+        has_one = pauser,
+    )]
+    pub main_state: Box<Account<'info, MainState>>, // MainState account
+}
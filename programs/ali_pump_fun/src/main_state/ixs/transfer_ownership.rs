@@ -1,27 +1,51 @@
 use crate::{
     error::PumpFunError,
     MainState,
-    OwnershipTransferred,
+    OwnershipProposed, OwnershipProposalCancelled, OwnershipTransferred,
 };
 use anchor_lang::prelude::*;
 
-// This function transfers ownership to other user
+// This function proposes a new owner (ownership moves only when the new owner accepts)
 // Params
-//   ctx - Owner update context
-//   new_owner - Address of new owner
+//   ctx - Owner proposal context
+//   new_owner - Address of proposed owner
 // Return
 //   Ok on success, ErrorCode on failure
-pub fn transfer_ownership(
-    ctx: Context<ATransferOwnership>,
+//     OwnershipProposed is emitted on success
+pub fn propose_owner(
+    ctx: Context<AProposeOwner>,
     new_owner: Pubkey
 ) -> Result<()> {
     let main_state = &mut ctx.accounts.main_state;
     require!(main_state.owner.ne(&new_owner), PumpFunError::AlreadyBecameOwner); // Don't need to transfer ownership to the same user
+    require!(new_owner.ne(&Pubkey::default()), PumpFunError::InvalidPendingOwner);
+
+    // Update pending owner (replaces any previous proposal)
+    main_state.pending_owner = new_owner;
+
+    emit!(OwnershipProposed {
+        owner: main_state.owner,
+        pending_owner: new_owner
+    });
+
+    Ok(())
+}
+
+// This function is called by the proposed owner to accept ownership
+// Params
+//   ctx - Ownership acceptance context
+// Return
+//   Ok on success, ErrorCode on failure
+//     OwnershipTransferred is emitted on success
+pub fn accept_ownership(ctx: Context<AAcceptOwnership>) -> Result<()> {
+    let main_state = &mut ctx.accounts.main_state;
 
     let previous_owner = main_state.owner;
+    let new_owner = ctx.accounts.pending_owner.key();
 
     // Update owner
     main_state.owner = new_owner;
+    main_state.pending_owner = Pubkey::default();
 
     emit!(OwnershipTransferred {
         previous_owner,
@@ -31,9 +55,32 @@ pub fn transfer_ownership(
     Ok(())
 }
 
-// Transfer owner context - passed with accounts
+// This function cancels the pending ownership proposal
+// Params
+//   ctx - Owner proposal context
+// Return
+//   Ok on success, ErrorCode on failure
+//     OwnershipProposalCancelled is emitted on success
+pub fn cancel_ownership_proposal(ctx: Context<AProposeOwner>) -> Result<()> {
+    let main_state = &mut ctx.accounts.main_state;
+    require!(main_state.pending_owner.ne(&Pubkey::default()), PumpFunError::NoPendingOwner);
+
+    let pending_owner = main_state.pending_owner;
+
+    // Clear pending owner
+    main_state.pending_owner = Pubkey::default();
+
+    emit!(OwnershipProposalCancelled {
+        owner: main_state.owner,
+        pending_owner
+    });
+
+    Ok(())
+}
+
+// Owner proposal context - passed with accounts
 #[derive(Accounts)]
-pub struct ATransferOwnership<'info> {
+pub struct AProposeOwner<'info> {
     #[account(mut)]
     pub owner: Signer<'info>, // Current owner
     #[account(
@@ -44,3 +91,17 @@ pub struct ATransferOwnership<'info> {
     )]
     pub main_state: Box<Account<'info, MainState>>, // MainState account with new values
 }
+
+// Ownership acceptance context - passed with accounts
+#[derive(Accounts)]
+pub struct AAcceptOwnership<'info> {
+    #[account(mut)]
+    pub pending_owner: Signer<'info>, // Proposed owner
+    #[account(
+        mut,
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+        has_one = pending_owner @ PumpFunError::Unauthorised,
+    )]
+    pub main_state: Box<Account<'info, MainState>>, // MainState account with new values
+}
@@ -1,8 +1,9 @@
 use crate::{
-    constants::{QUOTE_MINT_STR, MAX_TRADING_FEE},
+    constants::{MAX_TRADING_FEE, MAX_FEE_SHARE, MAX_UPDATE_DELAY, MAX_ANTI_SNIPE_SLOTS, MAX_ANTI_SNIPE_SECONDS, MAX_VESTING_DURATION},
     error::PumpFunError,
-    MainState,
-    MainStateUpdated
+    MainState, PendingMainStateUpdate, QuoteConfig, WindowUnit, WithdrawalPolicy, UnsoldBaseAction, CompletionReward,
+    MainStateUpdated, MainStateUpdateQueued, MainStateUpdateCancelled,
+    CurveType
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{associated_token::AssociatedToken, token::{Mint, Token, TokenAccount}};
@@ -12,36 +13,47 @@ use anchor_spl::{associated_token::AssociatedToken, token::{Mint, Token, TokenAc
 #[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, Copy)]
 pub struct UpdateMainStateInput {
     withdrawer: Pubkey,                     // New withdrawer
+    pauser: Option<Pubkey>,                 // New pauser (optional)
     fee_recipient: Pubkey,                  // New fee recipient
     trading_fee: u64,                       // New trading fee
     total_supply: Option<u64>,              // New token supply (optional)
     init_virt_base_reserves: Option<u64>,   // New initial virtual base token reserves (optional)
     init_virt_quote_reserves: Option<u64>,  // New initial virtual quote token reserves (optinoal)
-    real_quote_threshold: Option<u64>       // New complete real quote token threshold (optional)
+    real_quote_threshold: Option<u64>,      // New complete real quote token threshold (optional)
+    update_delay: Option<i64>,              // New delay of queued MainState updates in seconds (optional)
+    allowed_curves: Option<u8>,             // New bitmask of allowed curve types (optional)
+    creator_fee_share: Option<u64>,         // New creator share of trading fee (optional)
+    referral_fee_share: Option<u64>,        // New referrer share of trading fee (optional)
+    anti_snipe_window: Option<u64>,         // New anti-snipe window length, 0 to disable (optional)
+    anti_snipe_window_unit: Option<WindowUnit>, // New unit of anti-snipe window (optional)
+    max_buy_per_wallet: Option<u64>,        // New maximum base tokens per wallet during anti-snipe window (optional)
+    withdrawal_policy: Option<WithdrawalPolicy>, // New split of completed pool proceeds (optional)
+    unsold_base_action: Option<UnsoldBaseAction>, // New action on unsold base tokens of new pools (optional)
+    vesting_duration: Option<i64>,          // New duration of locked unsold base tokens release (optional)
+    creator_completion_reward: Option<CompletionReward>, // New creator completion reward (optional)
+    track_positions: Option<bool>           // Enable/disable UserPosition trade statistics (optional)
 }
 
-// This function updates main state
+// This function validates update parameters and applies them to main state
 // Params
-//   ctx - MainStatate update context
+//   main_state - MainState account (or its copy)
 //   input - MainState update parameters
 // Return
 //   Ok on success, ErrorCode on failure
-pub fn update_main_state(
-    ctx: Context<AUpdateMainState>,
-    input: UpdateMainStateInput,
-) -> Result<()> {
+fn apply_main_state_update(main_state: &mut MainState, input: &UpdateMainStateInput) -> Result<()> {
     // input parameters check
     require!(
         input.trading_fee.ge(&(0 as u64)) && input.trading_fee.le(&MAX_TRADING_FEE),
         PumpFunError::InvalidFee
     );
 
-    let main_state = &mut ctx.accounts.main_state;
-
     // Update new members
     main_state.withdrawer = input.withdrawer;
     main_state.fee_recipient = input.fee_recipient;
     main_state.trading_fee = input.trading_fee;
+    if let Some(pauser) = input.pauser {
+        main_state.pauser = pauser;
+    }
     
     if let Some(total_supply) = input.total_supply {
         require!(total_supply > 0, PumpFunError::InvalidTotalSupply);
@@ -59,24 +71,187 @@ pub fn update_main_state(
         require!(real_quote_threshold > 0, PumpFunError::InvalidRealQuoteThreshold);
         main_state.real_quote_threshold = real_quote_threshold;
     }
+    if let Some(update_delay) = input.update_delay {
+        require!((0..=MAX_UPDATE_DELAY).contains(&update_delay), PumpFunError::InvalidUpdateDelay);
+        main_state.update_delay = update_delay;
+    }
+    if let Some(allowed_curves) = input.allowed_curves {
+        require!(allowed_curves > 0 && allowed_curves & !CurveType::ALL_MASK == 0, PumpFunError::InvalidAllowedCurves);
+        main_state.allowed_curves = allowed_curves;
+    }
+    if let Some(creator_fee_share) = input.creator_fee_share {
+        require!(creator_fee_share.le(&MAX_FEE_SHARE), PumpFunError::InvalidFeeShare);
+        main_state.creator_fee_share = creator_fee_share;
+    }
+    if let Some(referral_fee_share) = input.referral_fee_share {
+        require!(referral_fee_share.le(&MAX_FEE_SHARE), PumpFunError::InvalidFeeShare);
+        main_state.referral_fee_share = referral_fee_share;
+    }
+    // Creator and referrer shares must leave nothing negative for the protocol
+    require!(
+        (main_state.creator_fee_share + main_state.referral_fee_share).le(&MAX_FEE_SHARE),
+        PumpFunError::InvalidFeeShare
+    );
+
+    if let Some(anti_snipe_window) = input.anti_snipe_window {
+        main_state.anti_snipe_window = anti_snipe_window;
+    }
+    if let Some(anti_snipe_window_unit) = input.anti_snipe_window_unit {
+        main_state.anti_snipe_window_unit = anti_snipe_window_unit;
+    }
+    if let Some(max_buy_per_wallet) = input.max_buy_per_wallet {
+        main_state.max_buy_per_wallet = max_buy_per_wallet;
+    }
+    // Anti-snipe window must be bounded and have a buy limit while enabled
+    let max_anti_snipe_window = match main_state.anti_snipe_window_unit {
+        WindowUnit::Slots => MAX_ANTI_SNIPE_SLOTS,
+        WindowUnit::Seconds => MAX_ANTI_SNIPE_SECONDS,
+    };
+    require!(
+        main_state.anti_snipe_window.le(&max_anti_snipe_window) && (main_state.anti_snipe_window == 0 || main_state.max_buy_per_wallet > 0),
+        PumpFunError::InvalidAntiSnipeSettings
+    );
+
+    if let Some(withdrawal_policy) = input.withdrawal_policy {
+        // Creator and escrow shares must leave nothing negative for the treasury
+        require!(
+            (withdrawal_policy.creator_share + withdrawal_policy.migration_escrow_share).le(&MAX_FEE_SHARE),
+            PumpFunError::InvalidWithdrawalPolicy
+        );
+        main_state.withdrawal_policy = withdrawal_policy;
+    }
+
+    if let Some(unsold_base_action) = input.unsold_base_action {
+        main_state.unsold_base_action = unsold_base_action;
+    }
+    if let Some(vesting_duration) = input.vesting_duration {
+        require!((1..=MAX_VESTING_DURATION).contains(&vesting_duration), PumpFunError::InvalidVestingDuration);
+        main_state.vesting_duration = vesting_duration;
+    }
+
+    if let Some(creator_completion_reward) = input.creator_completion_reward {
+        // Reward can't take more than the whole threshold
+        if let CompletionReward::ThresholdShare(share) = creator_completion_reward {
+            require!(share.le(&MAX_FEE_SHARE), PumpFunError::InvalidCompletionReward);
+        }
+        main_state.creator_completion_reward = creator_completion_reward;
+    }
+
+    if let Some(track_positions) = input.track_positions {
+        main_state.track_positions = track_positions;
+    }
+
+    Ok(())
+}
+
+// This function queues main state update, which can be executed once update_delay has passed
+// Params
+//   ctx - MainState update queue context
+//   input - MainState update parameters
+// Return
+//   Ok on success, ErrorCode on failure
+//     MainStateUpdateQueued is emitted on success
+pub fn queue_main_state_update(
+    ctx: Context<AQueueMainStateUpdate>,
+    input: UpdateMainStateInput,
+) -> Result<()> {
+    // Reject invalid parameters now rather than on execution
+    let mut main_state = ctx.accounts.main_state.as_ref().clone();
+    apply_main_state_update(&mut main_state, &input)?;
+
+    let pending_update = &mut ctx.accounts.pending_update;
+    pending_update.input = input;
+    pending_update.eta = Clock::get()?.unix_timestamp + ctx.accounts.main_state.update_delay;
+
+    emit!(MainStateUpdateQueued {
+        input,
+        eta: pending_update.eta,
+    });
+
+    Ok(())
+}
+
+// This function executes the queued main state update
+// Params
+//   ctx - MainState update execution context
+// Return
+//   Ok on success, ErrorCode on failure
+//     MainStateUpdated is emitted on success
+pub fn execute_main_state_update(ctx: Context<AExecuteMainStateUpdate>) -> Result<()> {
+    let pending_update = &ctx.accounts.pending_update;
+    require!(Clock::get()?.unix_timestamp >= pending_update.eta, PumpFunError::UpdateNotReady);
+
+    let main_state = &mut ctx.accounts.main_state;
+    apply_main_state_update(main_state, &pending_update.input)?;
 
     emit!(MainStateUpdated {
-        withdrawer: input.withdrawer,
-        fee_recipient: input.fee_recipient,
-        trading_fee: input.trading_fee,
-        total_supply: input.total_supply.unwrap_or(main_state.total_supply),
-        init_virt_base_reserves: input.init_virt_base_reserves.unwrap_or(main_state.init_virt_base_reserves),
-        init_virt_quote_reserves: input.init_virt_quote_reserves.unwrap_or(main_state.init_virt_quote_reserves),
-        real_quote_threshold: input.real_quote_threshold.unwrap_or(main_state.real_quote_threshold),
+        withdrawer: main_state.withdrawer,
+        pauser: main_state.pauser,
+        fee_recipient: main_state.fee_recipient,
+        trading_fee: main_state.trading_fee,
+        total_supply: main_state.total_supply,
+        init_virt_base_reserves: main_state.init_virt_base_reserves,
+        init_virt_quote_reserves: main_state.init_virt_quote_reserves,
+        real_quote_threshold: main_state.real_quote_threshold,
+        update_delay: main_state.update_delay,
+        allowed_curves: main_state.allowed_curves,
+        creator_fee_share: main_state.creator_fee_share,
+        referral_fee_share: main_state.referral_fee_share,
+        anti_snipe_window: main_state.anti_snipe_window,
+        anti_snipe_window_unit: main_state.anti_snipe_window_unit,
+        max_buy_per_wallet: main_state.max_buy_per_wallet,
+        withdrawal_policy: main_state.withdrawal_policy,
+        unsold_base_action: main_state.unsold_base_action,
+        vesting_duration: main_state.vesting_duration,
+        creator_completion_reward: main_state.creator_completion_reward,
+        track_positions: main_state.track_positions,
     });
     
     Ok(())
 }
 
-// MainState update context - passed with accounts
+// This function cancels the queued main state update
+// Params
+//   ctx - MainState update cancel context
+// Return
+//   Ok on success, ErrorCode on failure
+//     MainStateUpdateCancelled is emitted on success
+pub fn cancel_main_state_update(ctx: Context<ACancelMainStateUpdate>) -> Result<()> {
+    emit!(MainStateUpdateCancelled {
+        input: ctx.accounts.pending_update.input,
+        eta: ctx.accounts.pending_update.eta,
+    });
+
+    Ok(())
+}
+
+// MainState update queue context - passed with accounts
+#[derive(Accounts)]
+pub struct AQueueMainStateUpdate<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>, // Current owner
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+        has_one = owner,
+    )]
+    pub main_state: Box<Account<'info, MainState>>, // MainState account
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [PendingMainStateUpdate::PREFIX_SEED],
+        bump,
+        space = 8 + PendingMainStateUpdate::MAX_SIZE
+    )]
+    pub pending_update: Box<Account<'info, PendingMainStateUpdate>>, // (New) queued update, only one at a time
+
+    pub system_program: Program<'info, System>
+}
+
+// MainState update execution context - passed with accounts
 #[derive(Accounts)]
-#[instruction(input: UpdateMainStateInput)]
-pub struct AUpdateMainState<'info> {
+pub struct AExecuteMainStateUpdate<'info> {
     #[account(mut)]
     pub owner: Signer<'info>, // Current owner
     #[account(
@@ -86,12 +261,21 @@ pub struct AUpdateMainState<'info> {
         has_one = owner,
     )]
     pub main_state: Box<Account<'info, MainState>>, // MainState account with new values
+    #[account(
+        mut,
+        seeds = [PendingMainStateUpdate::PREFIX_SEED],
+        bump,
+        close = owner,
+    )]
+    pub pending_update: Box<Account<'info, PendingMainStateUpdate>>, // Queued update (closed on execution)
 
     #[account(
-        constraint = quote_mint.key().to_string() == QUOTE_MINT_STR @ PumpFunError::UnknownQuoteMint
+        seeds = [QuoteConfig::PREFIX_SEED, quote_mint.key().as_ref()],
+        bump,
     )]
+    pub quote_config: Box<Account<'info, QuoteConfig>>, // QuoteConfig of quote_mint (must be whitelisted)
     pub quote_mint: Box<Account<'info, Mint>>,
-    #[account(address = input.fee_recipient)]
+    #[account(address = pending_update.input.fee_recipient)]
     /// CHECK: this should be set by admin
     pub fee_recipient: AccountInfo<'info>,
     #[account(
@@ -107,3 +291,23 @@ pub struct AUpdateMainState<'info> {
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>
 }
+
+// MainState update cancel context - passed with accounts
+#[derive(Accounts)]
+pub struct ACancelMainStateUpdate<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>, // Current owner
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+        has_one = owner,
+    )]
+    pub main_state: Box<Account<'info, MainState>>, // MainState account
+    #[account(
+        mut,
+        seeds = [PendingMainStateUpdate::PREFIX_SEED],
+        bump,
+        close = owner,
+    )]
+    pub pending_update: Box<Account<'info, PendingMainStateUpdate>>, // Queued update (closed on cancel)
+}
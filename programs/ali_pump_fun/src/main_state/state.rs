@@ -1,19 +1,151 @@
 use anchor_lang::prelude::*;
+use crate::{UpdateMainStateInput, error::PumpFunError, utils::{calculate_trading_fee, split_trading_fee}};
 
 // Main state of Program
 #[account]
 pub struct MainState {
     pub owner: Pubkey,                  // Address of the Program owner (The initializer becomes the initial program owner)
+    pub pending_owner: Pubkey,          // Address of the proposed owner waiting for acceptance (default pubkey if none)
     pub withdrawer: Pubkey,             // Address of withdrawer
+    pub pauser: Pubkey,                 // Address allowed to pause/unpause the program and pools (Owner becomes the initial pauser)
     pub fee_recipient: Pubkey,          // Address of the fee recipient (Owner becomes the initial fee recipient)
     pub trading_fee: u64,               // Trading fee applied on buying/selling tokens (default: 1%)
     pub total_supply: u64,              // Total supply of tokens (default: 1 billion)
     pub init_virt_base_reserves: u64,   // Initial virtual base token reserves
     pub init_virt_quote_reserves: u64,  // Initial virtual quote token reserves
     pub real_quote_threshold: u64,      // Real quote token threshold
+    pub allowed_curves: u8,             // Bitmask of curve types allowed on pool creation (see CurveType::mask)
+    pub creator_fee_share: u64,         // Share of trading fee sent to the pool creator's fee vault (in FEE_PER_DIV units, default: 0%)
+    pub referral_fee_share: u64,        // Share of trading fee sent to the trade's referrer (in FEE_PER_DIV units, default: 0%)
+    pub update_delay: i64,              // Delay in seconds between queueing and executing MainState update (default: 2 days)
+    pub paused: bool,                   // Flag indicating whether the whole program is paused
+    pub anti_snipe_window: u64,         // Length of anti-snipe window since public trading opens (0: disabled, default)
+    pub anti_snipe_window_unit: WindowUnit, // Unit of anti_snipe_window
+    pub max_buy_per_wallet: u64,        // Maximum amount of base tokens a wallet can buy during anti-snipe window
+    pub withdrawal_policy: WithdrawalPolicy, // Split of completed pool proceeds on withdrawal
+    pub unsold_base_action: UnsoldBaseAction, // Action taken on unsold base tokens of new pools (default: burn)
+    pub vesting_duration: i64,          // Duration in seconds of linear release of locked unsold base tokens (default: 1 year)
+    pub creator_completion_reward: CompletionReward, // Reward paid to the pool creator from real quote reserves on completion (default: none)
+    pub track_positions: bool,          // Flag indicating whether passed UserPosition accounts record trade statistics (default: true, buys are always recorded during anti-snipe window)
+}
+
+// Creator completion reward - reserved from real quote reserves when the bonding curve becomes complete
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionReward {
+    Fixed(u64),             // Fixed amount of quote tokens
+    ThresholdShare(u64),    // Share of real_quote_threshold (in FEE_PER_DIV units)
+}
+
+impl CompletionReward {
+    // This function calculates creator completion reward of a pool
+    // Params
+    //   self - CompletionReward itself
+    //   real_quote_threshold - Real quote token threshold of the pool
+    // Return
+    //   reward in quote
+    pub fn amount(&self, real_quote_threshold: u64) -> u64 {
+        match *self {
+            CompletionReward::Fixed(amount) => amount,
+            CompletionReward::ThresholdShare(share) => calculate_trading_fee(share, real_quote_threshold),
+        }
+    }
+}
+
+// Withdrawal policy - splits withdrawn quote tokens of completed pools
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WithdrawalPolicy {
+    pub treasury: Pubkey,               // Address receiving the remainder (Owner becomes the initial treasury)
+    pub creator_share: u64,             // Share sent to the pool creator as reward (in FEE_PER_DIV units, default: 0%)
+    pub migration_escrow: Pubkey,       // Address of liquidity migration escrow (Owner becomes the initial escrow)
+    pub migration_escrow_share: u64,    // Share sent to the migration escrow (in FEE_PER_DIV units, default: 0%)
+}
+
+impl WithdrawalPolicy {
+    // This function splits withdrawn amount between treasury, creator and migration escrow
+    // Params
+    //   self - WithdrawalPolicy struct itself
+    //   amount - Withdrawn amount of base or quote tokens
+    // Return
+    //   (treasury amount, creator amount, migration escrow amount)
+    pub fn split(&self, amount: u64) -> (u64, u64, u64) {
+        split_trading_fee(self.creator_share, self.migration_escrow_share, amount)
+    }
+}
+
+// Action taken on unsold base tokens (real_base_reserves) of complete pools on withdrawal
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsoldBaseAction {
+    Burn,   // Burnt
+    Lock,   // Locked in BaseVesting PDA, released linearly to treasury
+    Pair,   // Sent to migration escrow to be paired into migration liquidity
+}
+
+// Recipient of withdrawn tokens
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WithdrawRecipient {
+    Treasury,           // WithdrawalPolicy.treasury
+    Creator,            // Pool creator
+    MigrationEscrow,    // WithdrawalPolicy.migration_escrow
+}
+
+// Unit of anti-snipe window length
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowUnit {
+    Slots,      // Window is measured in slots
+    Seconds,    // Window is measured in seconds
 }
 
 impl MainState {
     pub const MAX_SIZE: usize = std::mem::size_of::<Self>();    // Size of MainState
     pub const PREFIX_SEED: &'static [u8] = b"main";             // Seed of MainState
+
+    // This function checks whether pools can schedule trading start (trading_start_ts or presale)
+    //   Slot at which scheduled trading opens is unknown, so anti-snipe window in slots can only run from creation
+    // Params
+    //   self - MainState struct itself
+    // Return
+    //   true if anti-snipe window is disabled or measured in seconds
+    pub fn allows_scheduled_start(&self) -> bool {
+        self.anti_snipe_window == 0 || self.anti_snipe_window_unit == WindowUnit::Seconds
+    }
+
+    // This function checks buy limit per wallet of anti-snipe window, shared by buys and their quotes
+    // Params
+    //   self - MainState struct itself
+    //   in_anti_snipe_window - Flag indicating whether the pool is in anti-snipe window
+    //   base_bought - Amount of base tokens bought by the wallet, including the buy being checked
+    // Return
+    //   Ok unless base_bought exceeds max_buy_per_wallet during anti-snipe window
+    pub fn check_buy_limit(&self, in_anti_snipe_window: bool, base_bought: u64) -> Result<()> {
+        if in_anti_snipe_window {
+            require!(base_bought.le(&self.max_buy_per_wallet), PumpFunError::MaxBuyPerWalletExceeded);
+        }
+        Ok(())
+    }
+}
+
+// Queued MainState update
+#[account]
+pub struct PendingMainStateUpdate {
+    pub input: UpdateMainStateInput,    // MainState update parameters
+    pub eta: i64,                       // Earliest time the update can be executed
+}
+
+impl PendingMainStateUpdate {
+    pub const MAX_SIZE: usize = std::mem::size_of::<Self>();        // Size of PendingMainStateUpdate
+    pub const PREFIX_SEED: &'static [u8] = b"pending_main_update";  // Seed of PendingMainStateUpdate
+}
+
+// Whitelisted quote token config
+#[account]
+pub struct QuoteConfig {
+    pub quote_mint: Pubkey,             // Quote token mint address
+    pub init_virt_quote_reserves: u64,  // Initial virtual quote token reserves of new pools
+    pub real_quote_threshold: u64,      // Real quote token threshold of new pools
+    pub trading_fee: Option<u64>,       // Trading fee of new pools overriding MainState.trading_fee (optional)
+}
+
+impl QuoteConfig {
+    pub const MAX_SIZE: usize = std::mem::size_of::<Self>();    // Size of QuoteConfig
+    pub const PREFIX_SEED: &'static [u8] = b"quote_config";     // Seed of QuoteConfig
 }
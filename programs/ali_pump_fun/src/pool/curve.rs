@@ -0,0 +1,551 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{EXP_CURVE_DOUBLINGS, STEPPED_CURVE_STEPS, PRICE_FRACTIONAL_BITS},
+    error::PumpFunError,
+    PoolState,
+};
+
+// Bonding curve shapes
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveType {
+    ConstantProduct,    // Constant product with virtual reserves
+    Linear,             // Price grows linearly with sold amount
+    Exponential,        // Price doubles EXP_CURVE_DOUBLINGS times until completion
+    Stepped,            // Price is constant inside each of STEPPED_CURVE_STEPS tranches
+}
+
+impl CurveType {
+    pub const ALL_MASK: u8 = 0b1111; // Bitmask with every curve type allowed
+
+    // This function returns bit of the curve type in MainState.allowed_curves
+    pub fn mask(&self) -> u8 {
+        1 << (*self as u8)
+    }
+
+    // This function returns curve implementation of the curve type
+    pub fn curve(&self) -> &'static dyn BondingCurve {
+        match self {
+            CurveType::ConstantProduct => &ConstantProductCurve,
+            CurveType::Linear => &LinearCurve,
+            CurveType::Exponential => &ExponentialCurve,
+            CurveType::Stepped => &SteppedCurve,
+        }
+    }
+}
+
+// Common interface of bonding curves
+//   All amounts exclude trading fee, Overflow error is returned if the curve can't be evaluated
+pub trait BondingCurve {
+    // Receivable amount of base tokens on buying with quote_amount
+    fn compute_receivable_amount_on_buy(&self, pool_state: &PoolState, quote_amount: u64) -> Result<u64>;
+    // Required amount of quote tokens on buying base_amount
+    fn compute_required_amount_on_buy(&self, pool_state: &PoolState, base_amount: u64) -> Result<u64>;
+    // Receivable amount of quote tokens on selling base_amount
+    fn compute_receivable_amount_on_sell(&self, pool_state: &PoolState, base_amount: u64) -> Result<u64>;
+    // Required amount of base tokens on selling for quote_amount (None if the curve can't pay it out)
+    fn compute_required_amount_on_sell(&self, pool_state: &PoolState, quote_amount: u64) -> Result<Option<u64>>;
+    // Amount of base tokens matching quote_amount at the current marginal price
+    fn compute_base_amount_at_price(&self, pool_state: &PoolState, quote_amount: u64) -> Result<u64>;
+    // Current marginal price of base token in quote (Q64.64 fixed point)
+    fn spot_price(&self, pool_state: &PoolState) -> Result<u128>;
+}
+
+// Constant product curve with virtual reserves
+pub struct ConstantProductCurve;
+
+impl ConstantProductCurve {
+    // This function calculates quote reserves the curve prices against
+    //   Creator completion reward reserved on completion still counts, so completion doesn't move the price
+    // Params
+    //   pool_state - PoolState account
+    // Return
+    //   virtual + real + reserved quote reserves
+    fn quote_reserves(pool_state: &PoolState) -> u64 {
+        pool_state.virt_quote_reserves + pool_state.real_quote_reserves + pool_state.reserved_creator_reward
+    }
+}
+
+impl BondingCurve for ConstantProductCurve {
+    fn compute_receivable_amount_on_buy(&self, pool_state: &PoolState, quote_amount: u64) -> Result<u64> {
+        Ok(calculate_output_amount(quote_amount, Self::quote_reserves(pool_state), pool_state.virt_base_reserves + pool_state.real_base_reserves))
+    }
+
+    fn compute_required_amount_on_buy(&self, pool_state: &PoolState, base_amount: u64) -> Result<u64> {
+        Ok(calculate_input_amount(base_amount, Self::quote_reserves(pool_state), pool_state.virt_base_reserves + pool_state.real_base_reserves))
+    }
+
+    fn compute_receivable_amount_on_sell(&self, pool_state: &PoolState, base_amount: u64) -> Result<u64> {
+        Ok(calculate_output_amount(base_amount, pool_state.virt_base_reserves + pool_state.real_base_reserves, Self::quote_reserves(pool_state)))
+    }
+
+    //   base_amount = ceil(quote_amount * (virt_base + real_base) / (virt_quote + real_quote + reserved_reward - quote_amount))
+    fn compute_required_amount_on_sell(&self, pool_state: &PoolState, quote_amount: u64) -> Result<Option<u64>> {
+        let quote_reserve = (Self::quote_reserves(pool_state)) as u128;
+        if quote_amount as u128 >= quote_reserve {
+            return Ok(None);
+        }
+        let divider = quote_reserve - quote_amount as u128;
+        let base_amount = (quote_amount as u128)
+            .checked_mul((pool_state.virt_base_reserves + pool_state.real_base_reserves) as u128)
+            .unwrap()
+            .checked_add(divider - 1)
+            .unwrap()
+            .checked_div(divider)
+            .unwrap();
+        Ok(u64::try_from(base_amount).ok())
+    }
+
+    //   base_amount = quote_amount * (virt_base + real_base) / (virt_quote + real_quote + reserved_reward)
+    fn compute_base_amount_at_price(&self, pool_state: &PoolState, quote_amount: u64) -> Result<u64> {
+        Ok((quote_amount as u128)
+            .checked_mul((pool_state.virt_base_reserves + pool_state.real_base_reserves) as u128)
+            .unwrap()
+            .checked_div((Self::quote_reserves(pool_state)) as u128)
+            .unwrap() as u64)
+    }
+
+    //   price = (virt_quote + real_quote + reserved_reward) / (virt_base + real_base)
+    fn spot_price(&self, pool_state: &PoolState) -> Result<u128> {
+        Ok((((Self::quote_reserves(pool_state)) as u128) << PRICE_FRACTIONAL_BITS)
+            .checked_div((pool_state.virt_base_reserves + pool_state.real_base_reserves) as u128)
+            .unwrap())
+    }
+}
+
+// Curves defined by cost(sold) - total quote tokens paid to buy `sold` base tokens from a fresh pool
+//   Every curve starts from zero, is non-decreasing and reaches ~real_quote_threshold at completion_amount
+//   real_quote_reserves never drops below cost(sold), so sells are always covered
+pub trait CostCurve {
+    fn cost(&self, pool_state: &PoolState, sold: u64) -> Result<u128>;
+}
+
+impl<T: CostCurve> BondingCurve for T {
+    // Largest base amount whose cost doesn't exceed quote_amount (rounded in favour of the pool)
+    fn compute_receivable_amount_on_buy(&self, pool_state: &PoolState, quote_amount: u64) -> Result<u64> {
+        let sold = sold_amount(pool_state);
+        let target = self.cost(pool_state, sold)? + quote_amount as u128;
+        let mut low: u64 = 0;
+        let mut high: u64 = pool_state.real_base_reserves;
+        while low < high {
+            let mid = high - (high - low) / 2;
+            if self.cost(pool_state, sold + mid)? <= target {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        Ok(low)
+    }
+
+    fn compute_required_amount_on_buy(&self, pool_state: &PoolState, base_amount: u64) -> Result<u64> {
+        let sold = sold_amount(pool_state);
+        Ok((self.cost(pool_state, sold + base_amount)? - self.cost(pool_state, sold)?) as u64)
+    }
+
+    fn compute_receivable_amount_on_sell(&self, pool_state: &PoolState, base_amount: u64) -> Result<u64> {
+        let sold = sold_amount(pool_state);
+        Ok(self.cost(pool_state, sold)?
+            .saturating_sub(self.cost(pool_state, sold.saturating_sub(base_amount))?) as u64)
+    }
+
+    // Smallest base amount whose sale returns at least quote_amount (rounded in favour of the pool)
+    fn compute_required_amount_on_sell(&self, pool_state: &PoolState, quote_amount: u64) -> Result<Option<u64>> {
+        let sold = sold_amount(pool_state);
+        let current_cost = self.cost(pool_state, sold)?;
+        if current_cost < quote_amount as u128 {
+            return Ok(None);
+        }
+        let mut low: u64 = 0;
+        let mut high: u64 = sold;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if current_cost - self.cost(pool_state, sold - mid)? >= quote_amount as u128 {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        Ok(Some(low))
+    }
+
+    // Marginal price is sampled over the next 0.1% of completion_amount
+    fn compute_base_amount_at_price(&self, pool_state: &PoolState, quote_amount: u64) -> Result<u64> {
+        let sold = sold_amount(pool_state);
+        let sample = (completion_amount(pool_state) / 1000).max(1) as u64;
+        let sample_cost = self.cost(pool_state, sold + sample)? - self.cost(pool_state, sold)?;
+        Ok((quote_amount as u128)
+            .checked_mul(sample as u128)
+            .unwrap()
+            .checked_div(sample_cost.max(1))
+            .unwrap() as u64)
+    }
+
+    // Marginal price is sampled over the next 0.1% of completion_amount
+    fn spot_price(&self, pool_state: &PoolState) -> Result<u128> {
+        let sold = sold_amount(pool_state);
+        let sample = (completion_amount(pool_state) / 1000).max(1) as u64;
+        let sample_cost = self.cost(pool_state, sold + sample)? - self.cost(pool_state, sold)?;
+        Ok((sample_cost << PRICE_FRACTIONAL_BITS)
+            .checked_div(sample as u128)
+            .unwrap())
+    }
+}
+
+// Linear curve: price(sold) = start_price + slope * sold
+//   start_price matches the constant product curve and slope is calibrated to raise real_quote_threshold at completion_amount
+//   cost(sold) = start_price * sold + extra * (sold / completion_amount)^2
+pub struct LinearCurve;
+
+impl CostCurve for LinearCurve {
+    fn cost(&self, pool_state: &PoolState, sold: u64) -> Result<u128> {
+        let completion = completion_amount(pool_state);
+        let extra = extra_cost(pool_state);
+        Ok(start_price_cost(pool_state, sold) + extra
+            .checked_mul(sold as u128)
+            .unwrap()
+            .checked_div(completion)
+            .unwrap()
+            .checked_mul(sold as u128)
+            .unwrap()
+            .checked_div(completion)
+            .unwrap())
+    }
+}
+
+// Stepped curve: completion_amount is split into STEPPED_CURVE_STEPS tranches with evenly increasing prices
+//   price(tranche) = start_price + step * tranche
+//   cost(sold) = start_price * sold + extra * (width * j * (j - 1) + 2 * rest * j) / (completion_amount * (steps - 1))
+//     where j is the number of full tranches sold and rest is the amount sold inside the current tranche
+pub struct SteppedCurve;
+
+impl CostCurve for SteppedCurve {
+    fn cost(&self, pool_state: &PoolState, sold: u64) -> Result<u128> {
+        let completion = completion_amount(pool_state);
+        let width = (completion / STEPPED_CURVE_STEPS as u128).max(1);
+        let tranche = sold as u128 / width;
+        let rest = sold as u128 % width;
+        let weight = width * tranche * tranche.saturating_sub(1) + 2 * rest * tranche;
+        Ok(start_price_cost(pool_state, sold) + extra_cost(pool_state)
+            .checked_mul(weight)
+            .unwrap()
+            .checked_div(completion * (STEPPED_CURVE_STEPS as u128 - 1))
+            .unwrap())
+    }
+}
+
+// Exponential curve: price doubles EXP_CURVE_DOUBLINGS times between launch and completion_amount
+//   cost(sold) = real_quote_threshold * (2^(doublings * sold / completion_amount) - 1) / (2^doublings - 1)
+pub struct ExponentialCurve;
+
+impl CostCurve for ExponentialCurve {
+    fn cost(&self, pool_state: &PoolState, sold: u64) -> Result<u128> {
+        let exponent = ((EXP_CURVE_DOUBLINGS as u128 * sold as u128) << FIXED_POINT_BITS)
+            .checked_div(completion_amount(pool_state))
+            .unwrap();
+        Ok((exp2(exponent)? - FIXED_POINT_ONE)
+            .checked_mul(pool_state.real_quote_threshold as u128)
+            .ok_or(PumpFunError::Overflow)?
+            .checked_div(((1u128 << EXP_CURVE_DOUBLINGS) - 1) << FIXED_POINT_BITS)
+            .unwrap())
+    }
+}
+
+// Amount of base tokens sold from the bonding curve
+fn sold_amount(pool_state: &PoolState) -> u64 {
+    pool_state.initial_base_reserves - pool_state.real_base_reserves
+}
+
+// Amount of base tokens the constant product curve sells until real_quote_threshold is raised
+//   completion_amount = (virt_base + initial_base) * threshold / (virt_quote + threshold)
+fn completion_amount(pool_state: &PoolState) -> u128 {
+    ((pool_state.virt_base_reserves + pool_state.initial_base_reserves) as u128)
+        .checked_mul(pool_state.real_quote_threshold as u128)
+        .unwrap()
+        .checked_div((pool_state.virt_quote_reserves + pool_state.real_quote_threshold) as u128)
+        .unwrap()
+        .max(1)
+}
+
+// Cost of sold base tokens at the constant product start price
+//   start_price_cost = virt_quote * sold / (virt_base + initial_base)
+fn start_price_cost(pool_state: &PoolState, sold: u64) -> u128 {
+    (pool_state.virt_quote_reserves as u128)
+        .checked_mul(sold as u128)
+        .unwrap()
+        .checked_div((pool_state.virt_base_reserves + pool_state.initial_base_reserves) as u128)
+        .unwrap()
+}
+
+// Part of real_quote_threshold raised above the start price until completion
+fn extra_cost(pool_state: &PoolState) -> u128 {
+    let completion = completion_amount(pool_state);
+    (pool_state.real_quote_threshold as u128).saturating_sub(start_price_cost(pool_state, completion as u64))
+}
+
+const FIXED_POINT_BITS: u32 = 63;
+const FIXED_POINT_ONE: u128 = 1 << FIXED_POINT_BITS;
+
+// 2^(2^-i) for i = 1..=32 in 63-bit fixed point
+const EXP2_TABLE: [u128; 32] = [
+    13043817825332782212,
+    10968499650544839023,
+    10058158527438640871,
+    9631725603661576981,
+    9425337585438007767,
+    9323807973325342579,
+    9273454034941487980,
+    9248379135337035441,
+    9235867122425417553,
+    9229617465154500136,
+    9226494222562136017,
+    9224932997620185949,
+    9224152484218207415,
+    9223762252282022473,
+    9223567142504825508,
+    9223469589163912706,
+    9223420812880372951,
+    9223396424835331638,
+    9223384230836993049,
+    9223378133843869261,
+    9223375085348818743,
+    9223373561101671328,
+    9223372798978192081,
+    9223372417916476073,
+    9223372227385623972,
+    9223372132120199398,
+    9223372084487487480,
+    9223372060671131613,
+    9223372048762953703,
+    9223372042808864754,
+    9223372039831820280,
+    9223372038343298044,
+];
+
+// This function calculates 2^exponent in 63-bit fixed point
+// Params
+//   exponent - Exponent in 63-bit fixed point
+// Return
+//   2^exponent in 63-bit fixed point, Overflow error if it doesn't fit in u128
+fn exp2(exponent: u128) -> Result<u128> {
+    let integer = exponent >> FIXED_POINT_BITS;
+    let fraction = exponent & (FIXED_POINT_ONE - 1);
+    let mut result = FIXED_POINT_ONE;
+    for (i, factor) in EXP2_TABLE.iter().enumerate() {
+        if fraction & (1 << (FIXED_POINT_BITS - 1 - i as u32)) != 0 {
+            result = (result * factor) >> FIXED_POINT_BITS;
+        }
+    }
+    require!(integer < result.leading_zeros() as u128, PumpFunError::Overflow); // checked_shl doesn't catch bits shifted out
+    Ok(result << integer)
+}
+
+// This function calculates output amount by using AMM formula
+// Params
+//   input_amount - Input amount
+//   input_reserve - Input reserve
+//   output_reserve - Output reserve
+// Return
+//   output_amount - Output amount
+//     output_amount = output_reserve * input_amount / (input_reserve + input_amount)
+fn calculate_output_amount(input_amount: u64, input_reserve: u64, output_reserve: u64) -> u64 {
+    let output_amount = (output_reserve as u128)
+        .checked_mul(input_amount as u128)
+        .unwrap()
+        .checked_div((input_reserve as u128) + (input_amount as u128))
+        .unwrap();
+    output_amount as u64
+}
+
+// This function calculates input amount by using AMM formula
+// Params
+//   output_amount - Output amount
+//   input_reserve - Input reserve
+//   output_reserve - Output reserve
+// Return
+//   input_amount - Input amount
+//     input_amount = output_amount * input_reserve / (output_reserve - output_amount)
+fn calculate_input_amount(output_amount: u64, input_reserve: u64, output_reserve: u64) -> u64 {
+    let input_amount = (output_amount as u128)
+        .checked_mul(input_reserve as u128)
+        .unwrap()
+        .checked_div((output_reserve as u128) - (output_amount as u128))
+        .unwrap();
+    input_amount as u64
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{CompletionReward, UnsoldBaseAction};
+
+    const CURVES: [CurveType; 4] = [CurveType::ConstantProduct, CurveType::Linear, CurveType::Exponential, CurveType::Stepped];
+
+    pub(crate) fn pool_state(curve_type: CurveType) -> PoolState {
+        PoolState {
+            owner: Pubkey::default(),
+            base_mint: Pubkey::default(),
+            virt_base_reserves: 200_000_000_000_000,
+            real_base_reserves: 800_000_000_000_000,
+            quote_mint: Pubkey::default(),
+            virt_quote_reserves: 30_000_000_000,
+            real_quote_reserves: 0,
+            real_quote_threshold: 85_000_000_000,
+            complete: false,
+            curve_type,
+            initial_base_reserves: 800_000_000_000_000,
+            paused: false,
+            trading_fee: None,
+            created_at: 0,
+            created_slot: 0,
+            trading_start_ts: None,
+            presale_merkle_root: [0; 32],
+            presale_end_ts: 0,
+            deadline: None,
+            refunded_base_amount: 0,
+            unsold_base_action: UnsoldBaseAction::Burn,
+            creator_reward: 0,
+            price_cumulative_last: 0,
+            last_price_update_ts: 0,
+            observations_enabled: false,
+            reserved_creator_reward: 0,
+        }
+    }
+
+    // Applies a buy of quote_amount to pool_state, returns base tokens received
+    fn buy(pool_state: &mut PoolState, quote_amount: u64) -> u64 {
+        let base_amount = pool_state.compute_receivable_amount_on_buy(quote_amount).unwrap();
+        pool_state.real_base_reserves -= base_amount;
+        pool_state.real_quote_reserves += quote_amount;
+        base_amount
+    }
+
+    #[test]
+    fn exp2_values() {
+        assert_eq!(exp2(0).unwrap(), FIXED_POINT_ONE);
+        assert_eq!(exp2(3 << FIXED_POINT_BITS).unwrap(), 8 << FIXED_POINT_BITS);
+        let sqrt2 = exp2(FIXED_POINT_ONE / 2).unwrap();
+        assert_eq!(sqrt2, EXP2_TABLE[0]);
+        assert_eq!(exp2(63 << FIXED_POINT_BITS).unwrap(), 1 << 126);
+    }
+
+    #[test]
+    fn exp2_overflow() {
+        assert!(exp2(64 << FIXED_POINT_BITS).is_err());
+        assert!(exp2((63 << FIXED_POINT_BITS) + FIXED_POINT_ONE / 2).is_ok());
+        assert!(exp2((64 << FIXED_POINT_BITS) + FIXED_POINT_ONE / 2).is_err()); // Shifting out bits of a non-power of 2
+        assert!(exp2(200 << FIXED_POINT_BITS).is_err());
+    }
+
+    #[test]
+    fn buy_rounds_in_favour_of_pool() {
+        for curve_type in CURVES {
+            let pool_state = pool_state(curve_type);
+            for quote_amount in [1_000, 1_000_000_000, 50_000_000_000] {
+                let base_amount = pool_state.compute_receivable_amount_on_buy(quote_amount).unwrap();
+                assert!(base_amount > 0, "{:?}", curve_type);
+                assert!(pool_state.compute_required_amount_on_buy(base_amount).unwrap() <= quote_amount, "{:?}", curve_type);
+                assert!(pool_state.compute_required_amount_on_buy(base_amount + 2).unwrap() >= quote_amount, "{:?}", curve_type);
+            }
+        }
+    }
+
+    #[test]
+    fn price_increases_on_buy() {
+        for curve_type in CURVES {
+            let mut pool_state = pool_state(curve_type);
+            let mut spot_price = pool_state.spot_price().unwrap();
+            let mut base_amount = u64::MAX;
+            for _ in 0..8 {
+                let bought = buy(&mut pool_state, 10_000_000_000);
+                assert!(bought <= base_amount, "{:?}", curve_type);
+                let next_spot_price = pool_state.spot_price().unwrap();
+                assert!(next_spot_price >= spot_price, "{:?}", curve_type);
+                spot_price = next_spot_price;
+                base_amount = bought;
+            }
+        }
+    }
+
+    #[test]
+    fn threshold_buys_completion_amount() {
+        for curve_type in [CurveType::Linear, CurveType::Exponential, CurveType::Stepped] {
+            let pool_state = pool_state(curve_type);
+            let completion = completion_amount(&pool_state) as u64;
+            let cost = pool_state.compute_required_amount_on_buy(completion).unwrap();
+            let threshold = pool_state.real_quote_threshold;
+            assert!(cost.abs_diff(threshold) <= threshold / 1_000_000, "{:?} {}", curve_type, cost);
+        }
+    }
+
+    #[test]
+    fn completion_keeps_spot_price() {
+        for curve_type in CURVES {
+            let mut pool_state = pool_state(curve_type);
+            let threshold = pool_state.real_quote_threshold;
+            buy(&mut pool_state, threshold);
+            let spot_price = pool_state.spot_price().unwrap();
+            let creator_reward = pool_state.set_complete(CompletionReward::ThresholdShare(2_000));
+            assert!(creator_reward > 0);
+            assert_eq!(pool_state.spot_price().unwrap(), spot_price, "{:?}", curve_type);
+        }
+    }
+
+    #[test]
+    fn completion_keeps_migration_price() {
+        let mut pool_state = pool_state(CurveType::ConstantProduct);
+        buy(&mut pool_state, 20_000_000_000);
+        let quote_reserves = (pool_state.virt_quote_reserves + pool_state.real_quote_reserves) as u128;
+        let base_reserves = (pool_state.virt_base_reserves + pool_state.real_base_reserves) as u128;
+        pool_state.set_complete(CompletionReward::Fixed(1_000_000_000));
+        let quote_amount = pool_state.real_quote_reserves;
+        let base_amount = pool_state.compute_base_amount_at_price(quote_amount).unwrap();
+        assert_eq!(base_amount as u128, quote_amount as u128 * base_reserves / quote_reserves);
+    }
+
+    #[test]
+    fn sell_after_buy_returns_at_most_paid() {
+        for curve_type in CURVES {
+            let mut pool_state = pool_state(curve_type);
+            buy(&mut pool_state, 20_000_000_000);
+            let quote_amount = 5_000_000_000;
+            let base_amount = buy(&mut pool_state, quote_amount);
+            let output_amount = pool_state.compute_receivable_amount_on_sell(base_amount).unwrap();
+            assert!(output_amount <= quote_amount, "{:?}", curve_type);
+            assert!(quote_amount - output_amount <= quote_amount / 1_000_000, "{:?}", curve_type);
+        }
+    }
+
+    #[test]
+    fn required_amount_on_sell_inverts_receivable_amount() {
+        for curve_type in CURVES {
+            let mut pool_state = pool_state(curve_type);
+            buy(&mut pool_state, 40_000_000_000);
+            for quote_amount in [1, 1_000_000, 3_000_000_000, 35_000_000_000] {
+                let base_amount = pool_state.compute_required_amount_on_sell(quote_amount).unwrap().unwrap();
+                assert!(pool_state.compute_receivable_amount_on_sell(base_amount).unwrap() >= quote_amount, "{:?}", curve_type);
+                assert!(pool_state.compute_receivable_amount_on_sell(base_amount - 1).unwrap() < quote_amount, "{:?}", curve_type);
+            }
+        }
+    }
+
+    #[test]
+    fn required_amount_on_sell_above_cost() {
+        for curve_type in [CurveType::Linear, CurveType::Exponential, CurveType::Stepped] {
+            let mut pool_state = pool_state(curve_type);
+            buy(&mut pool_state, 1_000_000_000);
+            assert_eq!(pool_state.compute_required_amount_on_sell(2_000_000_000).unwrap(), None, "{:?}", curve_type);
+        }
+        let pool_state = pool_state(CurveType::ConstantProduct);
+        assert_eq!(pool_state.compute_required_amount_on_sell(30_000_000_000).unwrap(), None);
+    }
+
+    #[test]
+    fn exponential_cost_overflow() {
+        let mut pool_state = pool_state(CurveType::Exponential);
+        pool_state.virt_base_reserves = 0;
+        pool_state.virt_quote_reserves = u64::MAX - pool_state.real_quote_threshold; // Tiny completion_amount makes exponent overflow
+        pool_state.initial_base_reserves = 1_000;
+        pool_state.real_base_reserves = 1_000;
+        assert!(pool_state.compute_required_amount_on_buy(1_000).is_err());
+    }
+}
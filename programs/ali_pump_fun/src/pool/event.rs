@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::{CurveType, UnsoldBaseAction, WithdrawRecipient};
 
 // BondingCurve create event
 #[event]
@@ -8,6 +9,9 @@ pub struct CreateEvent {
     // pub quote_mint: Pubkey,  // Quote token mint address
     pub base_reserves: u64,     // Number of total base token reserves
     pub quote_reserves: u64,    // Number of total quote token reserves
+    pub curve_type: CurveType,  // Shape of the bonding curve
+    pub trading_start_ts: Option<i64>, // Time trading opens at (None: tradable immediately)
+    pub deadline: Option<i64>,  // Time the pool must complete by (None: no deadline)
     pub timestamp: i64,         // Creation time
 }
 
@@ -22,6 +26,7 @@ pub struct TradeEvent {
     pub base_reserves: u64,     // Updated base token reserves
     pub quote_reserves: u64,    // Updated quote token reserves
     pub is_buy: bool,           // Flag indicating whether the user bought or sold
+    pub referrer: Option<Pubkey>, // Referrer wallet address (if the trade was referred)
     pub timestamp: i64,         // Traded time
 }
 
@@ -31,16 +36,102 @@ pub struct CompleteEvent {
     // pub user: Pubkey,
     pub base_mint: Pubkey,      // Completed token mint address
     // pub quote_mint: Pubkey,  // Quote token mint address
+    pub creator_reward: u64,    // Creator completion reward claimable by the pool creator
     pub timestamp: i64,         // Completed time
 }
 
-// BondingCurve withdraw event
+// BondingCurve withdraw event (emitted for each transfer)
 #[event]
 pub struct WithdrawEvent {
     pub withdrawer: Pubkey,
+    pub recipient: Pubkey,      // Recipient address (vesting token account when locked, default when burnt)
+    pub recipient_kind: Option<WithdrawRecipient>, // Treasury, creator or migration escrow (None when burnt or locked)
+    pub unsold_base_action: Option<UnsoldBaseAction>, // Action taken on unsold base tokens (None for quote withdrawal)
     pub base_mint: Pubkey,      // Withdraw token mint address
     // pub quote_mint: Pubkey,  // Quote token mint address
     pub base_amount: u64,       // Withdrawn base token amount
     pub quote_amount: u64,      // Withdrawn quote token amount
     pub timestamp: i64,         // Withdraw time
 }
+
+// BondingCurve migrate event
+#[event]
+pub struct MigrateEvent {
+    pub migrator: Pubkey,           // Wallet address which triggered the migration
+    pub base_mint: Pubkey,          // Migrated token mint address
+    pub amm_pool: Pubkey,           // Seeded AMM pool address
+    pub base_amount: u64,           // Base token amount deposited into the AMM pool
+    pub quote_amount: u64,          // Quote token amount deposited into the AMM pool
+    pub burnt_base_amount: u64,     // Surplus base token amount burnt
+    pub lp_amount: u64,             // LP token amount locked
+    pub timestamp: i64,             // Migration time
+}
+
+// Creator fees claim event
+#[event]
+pub struct CreatorFeesClaimed {
+    pub creator: Pubkey,            // Creator wallet address
+    pub base_mint: Pubkey,          // Token mint address of the pool
+    pub amount: u64,                // Claimed quote token amount
+    pub timestamp: i64,             // Claim time
+}
+
+// Creator completion reward claim event
+#[event]
+pub struct CompletionRewardClaimed {
+    pub creator: Pubkey,            // Creator wallet address
+    pub base_mint: Pubkey,          // Token mint address of the pool
+    pub amount: u64,                // Claimed quote token amount
+    pub timestamp: i64,             // Claim time
+}
+
+// Presale set event
+#[event]
+pub struct PresaleSet {
+    pub creator: Pubkey,            // Creator wallet address
+    pub base_mint: Pubkey,          // Token mint address of the pool
+    pub merkle_root: [u8; 32],      // Merkle root of allowlisted (wallet, allocation) leaves
+    pub presale_end_ts: i64,        // Presale end time
+    pub timestamp: i64,             // Set time
+}
+
+// Refund event (holder returned base tokens for quote in refund state)
+#[event]
+pub struct RefundEvent {
+    pub user: Pubkey,               // Holder wallet address
+    pub base_mint: Pubkey,          // Token mint address of the pool
+    pub base_amount: u64,           // Returned (burnt) base token amount
+    pub quote_amount: u64,          // Refunded quote token amount
+    pub timestamp: i64,             // Refund time
+}
+
+// Unsold base tokens reclaim event
+#[event]
+pub struct UnsoldReclaimed {
+    pub creator: Pubkey,            // Creator wallet address
+    pub base_mint: Pubkey,          // Token mint address of the pool
+    pub base_amount: u64,           // Reclaimed base token amount
+    pub timestamp: i64,             // Reclaim time
+}
+
+// Locked unsold base tokens release event
+#[event]
+pub struct BaseVestingReleased {
+    pub beneficiary: Pubkey,        // Beneficiary wallet address
+    pub base_mint: Pubkey,          // Token mint address of the pool
+    pub base_amount: u64,           // Released base token amount
+    pub released_amount: u64,       // Total released base token amount
+    pub timestamp: i64,             // Release time
+}
+
+// Pool close event
+#[event]
+pub struct PoolClosed {
+    pub closer: Pubkey,             // Wallet address which closed the pool (withdrawer or creator)
+    pub base_mint: Pubkey,          // Token mint address of the pool
+    pub base_dust: u64,             // Burnt base token dust
+    pub quote_dust: u64,            // Quote token dust swept to withdrawer
+    pub creator_fees: u64,          // Unclaimed creator fees sent to creator
+    pub creator_reward: u64,        // Unclaimed creator completion reward sent to creator
+    pub timestamp: i64,             // Close time
+}
@@ -1,17 +1,25 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token::AssociatedToken, token::{self, Mint, Token, TokenAccount, Transfer}};
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount, Transfer},
+    token_interface::{self, TokenInterface, TransferChecked},
+};
 use crate::{
-    MainState, PoolState, 
+    MainState, PoolState, PoolObservations, TradeQuote, ReferrerStats, UserPosition, PresaleClaim, 
     TradeEvent, CompleteEvent, 
     error::PumpFunError, 
     main_state, 
-    utils::{calculate_trading_fee, calculate_total_amount, close_token_account, sync_native_amount}
+    utils::{
+        calculate_trading_fee, calculate_total_amount, split_trading_fee, close_token_account, sync_native_amount, is_native_mint,
+        create_associated_token_account, get_transfer_fee, calculate_transfer_fee, calculate_pre_transfer_fee_amount,
+        presale_leaf, verify_merkle_proof,
+    }
 };
 
 // Internal buy function
 // Params
 //   ctx - Buy context
-//   base_amount - Amount of base tokens to buy
+//   base_amount - Amount of base tokens to buy (sent by pool, transfer fee included)
 //   fee - Trading fee
 //   input_quote_amount - Amount of quote token to buy with (fee excluded)
 // Return
@@ -21,17 +29,88 @@ use crate::{
 fn buy_finalize(ctx: Context<ABuy>, base_amount: u64, fee: u64, input_quote_amount: u64) -> Result<()> {
     let pool_state = &mut ctx.accounts.pool_state;
     let buyer = ctx.accounts.buyer.to_account_info();
-    let buyer_base_ata = &ctx.accounts.buyer_base_ata;
+    let buyer_base_ata = ctx.accounts.buyer_base_ata.to_account_info();
     let buyer_quote_ata = &ctx.accounts.buyer_quote_ata;
     let token_program = ctx.accounts.token_program.to_account_info();
+    let base_token_program = ctx.accounts.base_token_program.to_account_info();
+
+    // Create buyer's base token ATA if not exists
+    create_associated_token_account(
+        buyer.clone(),
+        buyer_base_ata.clone(),
+        buyer.clone(),
+        ctx.accounts.base_mint.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        base_token_program.clone(),
+        ctx.accounts.associated_token_program.to_account_info(),
+    )?;
+
+    // Convert buyer's SOL to WSOL if quote is WSOL and buyer's WSOL is insufficient
+    if is_native_mint(&pool_state.quote_mint) {
+        sync_native_amount(buyer.clone(), buyer_quote_ata, fee + input_quote_amount, ctx.accounts.system_program.to_account_info(), token_program.clone())?;
+    }
+
+    let main_state = &ctx.accounts.main_state;
 
-    // Transfer fee (quote) from buyer to feeRecpient
+    // Record trade statistics in buyer's position and limit bought amount per wallet during anti-snipe window
+    //   Buys are always recorded during anti-snipe window as the limit relies on base_bought
+    let clock = Clock::get()?;
+    let in_anti_snipe_window = pool_state.in_anti_snipe_window(main_state, &clock);
+    if let Some(user_position) = &mut ctx.accounts.user_position {
+        user_position.pool = pool_state.key();
+        user_position.user = buyer.key();
+    }
+    let user_position = ctx.accounts.user_position.as_deref_mut().map(|user_position| &mut **user_position);
+    UserPosition::record_limited_buy(user_position, main_state, in_anti_snipe_window, base_amount, fee + input_quote_amount, fee, clock.unix_timestamp)?;
+
+    // Record price history sample
+    pool_state.record_observation(ctx.accounts.pool_observations.as_ref(), clock.unix_timestamp, input_quote_amount)?;
+
+    let referral_fee_share = if ctx.accounts.referrer.is_some() { main_state.referral_fee_share } else { 0 };
+    let (protocol_fee, creator_fee, referral_fee) = split_trading_fee(main_state.creator_fee_share, referral_fee_share, fee);
+
+    // Transfer protocol fee (quote) from buyer to feeRecpient
     let fee_transfer_cpi_account = Transfer {
         from: buyer_quote_ata.to_account_info(),
         to: ctx.accounts.fee_quote_ata.to_account_info(),
         authority: buyer.clone()
     };
-    token::transfer(CpiContext::new(token_program.clone(), fee_transfer_cpi_account), fee)?;
+    token::transfer(CpiContext::new(token_program.clone(), fee_transfer_cpi_account), protocol_fee)?;
+
+    // Transfer creator fee (quote) from buyer to creator's fee vault
+    if creator_fee > 0 {
+        let creator_fee_transfer_cpi_account = Transfer {
+            from: buyer_quote_ata.to_account_info(),
+            to: ctx.accounts.creator_fee_vault.to_account_info(),
+            authority: buyer.clone()
+        };
+        token::transfer(CpiContext::new(token_program.clone(), creator_fee_transfer_cpi_account), creator_fee)?;
+    }
+
+    // Transfer referral fee (quote) from buyer to referrer and record referrer stats
+    let referrer = ctx.accounts.referrer.as_ref().map(|referrer| referrer.key());
+    require!(
+        referrer.is_some() || (ctx.accounts.referrer_quote_ata.is_none() && ctx.accounts.referrer_stats.is_none()),
+        PumpFunError::MissingReferrerAccounts
+    ); // Referrer accounts are only passed together with referrer
+    if let Some(referrer) = referrer {
+        let (Some(referrer_quote_ata), Some(referrer_stats)) = (&ctx.accounts.referrer_quote_ata, &mut ctx.accounts.referrer_stats) else {
+            return Err(PumpFunError::MissingReferrerAccounts.into());
+        };
+        if referral_fee > 0 {
+            let referral_fee_transfer_cpi_account = Transfer {
+                from: buyer_quote_ata.to_account_info(),
+                to: referrer_quote_ata.to_account_info(),
+                authority: buyer.clone()
+            };
+            token::transfer(CpiContext::new(token_program.clone(), referral_fee_transfer_cpi_account), referral_fee)?;
+        }
+
+        referrer_stats.referrer = referrer;
+        referrer_stats.volume += fee + input_quote_amount;
+        referrer_stats.earnings += referral_fee;
+        referrer_stats.trade_count += 1;
+    }
     
     // Transfer input_quote_amount (quote) from buyer to pool
     let input_amount_transfer_cpi_account = Transfer {
@@ -42,20 +121,23 @@ fn buy_finalize(ctx: Context<ABuy>, base_amount: u64, fee: u64, input_quote_amou
     token::transfer(CpiContext::new(token_program.clone(), input_amount_transfer_cpi_account), input_quote_amount)?;
     
     // Transfer base tokens from pool to buyer
-    let output_amount_transfer_cpi_account = Transfer {
+    let output_amount_transfer_cpi_account = TransferChecked {
         from: ctx.accounts.reserver_base_ata.to_account_info(),
-        to: buyer_base_ata.to_account_info(),
+        mint: ctx.accounts.base_mint.to_account_info(),
+        to: buyer_base_ata.clone(),
         authority: pool_state.to_account_info()
     };
-    token::transfer(CpiContext::new_with_signer(token_program.clone(), output_amount_transfer_cpi_account, &[&[
+    token_interface::transfer_checked(CpiContext::new_with_signer(base_token_program, output_amount_transfer_cpi_account, &[&[
         PoolState::PREFIX_SEED,
         pool_state.base_mint.as_ref(),
         pool_state.quote_mint.as_ref(),
         &[ctx.bumps["pool_state"]]
-    ]]), base_amount)?;
+    ]]), base_amount, ctx.accounts.base_mint.decimals)?;
 
-    // // Unwrap WSOL (or close token account)
-    // close_token_account(buyer.clone(), buyer_quote_ata.to_account_info(), token_program)?;
+    // Unwrap remaining WSOL (close buyer's WSOL account unless it held WSOL before)
+    if is_native_mint(&pool_state.quote_mint) {
+        close_token_account(buyer.clone(), buyer_quote_ata, token_program.clone())?;
+    }
 
     // Emit (Buy) TradeEvent
     emit!(TradeEvent {
@@ -67,18 +149,20 @@ fn buy_finalize(ctx: Context<ABuy>, base_amount: u64, fee: u64, input_quote_amou
         base_reserves: pool_state.real_base_reserves + pool_state.virt_base_reserves, 
         quote_reserves: pool_state.virt_quote_reserves + pool_state.real_quote_reserves, 
         is_buy: true, 
+        referrer, 
         timestamp: Clock::get()?.unix_timestamp
     });
 
     // Check if bonding curve becomes complete
     if (pool_state.real_quote_reserves >= pool_state.real_quote_threshold) {
-        pool_state.complete = true;
+        let creator_reward = pool_state.set_complete(ctx.accounts.main_state.creator_completion_reward);
         
         // Emit CompleteEvent
         emit!(CompleteEvent {
             // user: buyer.key(), 
             base_mint: pool_state.base_mint, 
             // quote_mint: pool_state.quote_mint, 
+            creator_reward,
             timestamp: Clock::get()?.unix_timestamp,
         });
     }
@@ -86,46 +170,105 @@ fn buy_finalize(ctx: Context<ABuy>, base_amount: u64, fee: u64, input_quote_amou
     Ok(())
 }
 
-// This function buys base tokens on the bonding curve, with specified amount of quote
+// This function calculates buying base tokens with specified amount of quote, without moving funds
 // Params
-//   ctx - Buy context
+//   main_state - MainState account
+//   pool_state - PoolState account
+//   base_mint - Base token mint (transfer fee is withheld from output if Token-2022 mint has it)
 //   quote_amount - Amount of quote tokens to buy base tokens with
-//   min_base_amount - Minimum amount of base tokens to receive
 // Return
-//   Ok on success, ErrorCode on failure
-pub fn buy_tokens_from_exact_quote(ctx:Context<ABuy>, quote_amount: u64, min_base_amount: u64) -> Result<()> {
+//   TradeQuote on success, ErrorCode on failure
+pub fn compute_buy_tokens_from_exact_quote(main_state: &MainState, pool_state: &PoolState, base_mint: &AccountInfo, quote_amount: u64) -> Result<TradeQuote> {
     require!(quote_amount.gt(&0), PumpFunError::WrongQuoteAmount); // quote_amount must be greater than 0
-
-    let main_state = &mut ctx.accounts.main_state;
-    
-    let pool_state = &mut ctx.accounts.pool_state;
-    require!(pool_state.complete.eq(&false), PumpFunError::BondingCurveComplete); // BondingCurve must not be complete
-
-    let buyer = ctx.accounts.buyer.to_account_info();
-    let buyer_base_ata = &ctx.accounts.buyer_base_ata;
-    let buyer_quote_ata = &ctx.accounts.buyer_quote_ata;
-    let token_program = ctx.accounts.token_program.to_account_info();
-    let system_program = ctx.accounts.system_program.to_account_info();
+    pool_state.check_tradable(main_state, Clock::get()?.unix_timestamp)?; // Pool must be open for trading
 
     // if new real_quote_reserves exceeds threshold, restrict quote_amount
     let mut _quote_amount = quote_amount;
-    let mut fee = calculate_trading_fee(main_state.trading_fee, _quote_amount);
+    let mut fee = calculate_trading_fee(pool_state.effective_trading_fee(main_state), _quote_amount);
     if (pool_state.real_quote_reserves + (_quote_amount - fee) > pool_state.real_quote_threshold) {
-        _quote_amount = calculate_total_amount(main_state.trading_fee, pool_state.real_quote_threshold - pool_state.real_quote_reserves);
-        fee = calculate_trading_fee(main_state.trading_fee, _quote_amount);
+        _quote_amount = calculate_total_amount(pool_state.effective_trading_fee(main_state), pool_state.real_quote_threshold - pool_state.real_quote_reserves);
+        fee = calculate_trading_fee(pool_state.effective_trading_fee(main_state), _quote_amount);
     }
     
     let input_quote_amount = _quote_amount - fee;
-    let output_base_amount = pool_state.compute_receivable_amount_on_buy(input_quote_amount);
-    require!(output_base_amount >= min_base_amount, PumpFunError::TooFewOutputTokens); // Check minimum amount
+    let output_base_amount = pool_state.compute_receivable_amount_on_buy(input_quote_amount)?;
+    let transfer_fee = calculate_transfer_fee(&get_transfer_fee(base_mint)?, output_base_amount); // Withheld from base tokens sent by pool
+
+    let real_quote_reserves = pool_state.real_quote_reserves + input_quote_amount; // Increase Real Quote
+    let real_base_reserves = pool_state.real_base_reserves - output_base_amount; // Decrease Real Base
+
+    Ok(TradeQuote {
+        input_amount: _quote_amount,
+        output_amount: output_base_amount - transfer_fee,
+        fee,
+        transfer_fee,
+        real_base_reserves,
+        real_quote_reserves,
+        complete: real_quote_reserves >= pool_state.real_quote_threshold,
+    })
+}
+
+// This function calculates buying specified amount of base tokens, without moving funds
+// Params
+//   main_state - MainState account
+//   pool_state - PoolState account
+//   base_mint - Base token mint (pool sends more to cover transfer fee if Token-2022 mint has it)
+//   base_amount - Amount of base tokens to buy (received by buyer)
+// Return
+//   TradeQuote on success, ErrorCode on failure
+pub fn compute_buy_exact_tokens_from_quote(main_state: &MainState, pool_state: &PoolState, base_mint: &AccountInfo, base_amount: u64) -> Result<TradeQuote> {
+    pool_state.check_tradable(main_state, Clock::get()?.unix_timestamp)?; // Pool must be open for trading
 
-    pool_state.real_quote_reserves += input_quote_amount; // Increase Real Quote
-    pool_state.real_base_reserves -= output_base_amount; // Decrease Real Base
+    // Base tokens sent by pool so that base_amount is received after transfer fee
+    let base_transfer_fee = get_transfer_fee(base_mint)?;
+    let mut input_base_amount = calculate_pre_transfer_fee_amount(&base_transfer_fee, base_amount);
+
+    // base_amount must be greater than 0 and less than or equal to real_base_reserves
+    require!(base_amount.gt(&0) && input_base_amount.le(&pool_state.real_base_reserves), PumpFunError::WrongBaseAmount);
+
+    let mut input_quote_amount = pool_state.compute_required_amount_on_buy(input_base_amount)?;
+    // if new real_quote_reserves exceeds threshold, restrict quote_amount
+    if (pool_state.real_quote_reserves + input_quote_amount > pool_state.real_quote_threshold) {
+        input_quote_amount = pool_state.real_quote_threshold - pool_state.real_quote_reserves;
+        input_base_amount = pool_state.compute_receivable_amount_on_buy(input_quote_amount)?;
+    }
     
-    // // Convert Buyer's SOL to WSOL if necessary
-    // sync_native_amount(buyer.clone(), &buyer_quote_ata, _quote_amount, system_program.clone(), token_program.clone())?;
+    let total_quote_amount = calculate_total_amount(pool_state.effective_trading_fee(main_state), input_quote_amount);
+    let fee = calculate_trading_fee(pool_state.effective_trading_fee(main_state), total_quote_amount);
+    let transfer_fee = calculate_transfer_fee(&base_transfer_fee, input_base_amount);
+
+    let real_base_reserves = pool_state.real_base_reserves - input_base_amount; // Decrease Real Base
+    let real_quote_reserves = pool_state.real_quote_reserves + input_quote_amount; // Increase Real Quote
+
+    Ok(TradeQuote {
+        input_amount: fee + input_quote_amount,
+        output_amount: input_base_amount - transfer_fee,
+        fee,
+        transfer_fee,
+        real_base_reserves,
+        real_quote_reserves,
+        complete: real_quote_reserves >= pool_state.real_quote_threshold,
+    })
+}
+
+// This function buys base tokens on the bonding curve, with specified amount of quote
+// Params
+//   ctx - Buy context
+//   quote_amount - Amount of quote tokens to buy base tokens with
+//   min_base_amount - Minimum amount of base tokens to receive
+// Return
+//   Ok on success, ErrorCode on failure
+pub fn buy_tokens_from_exact_quote(ctx:Context<ABuy>, quote_amount: u64, min_base_amount: u64) -> Result<()> {
+    ctx.accounts.pool_state.check_public_buy(Clock::get()?.unix_timestamp)?;
+    let trade_quote = compute_buy_tokens_from_exact_quote(&ctx.accounts.main_state, &ctx.accounts.pool_state, &ctx.accounts.base_mint.to_account_info(), quote_amount)?;
+    require!(trade_quote.output_amount >= min_base_amount, PumpFunError::TooFewOutputTokens); // Check minimum amount
+
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.update_price_cumulative(Clock::get()?.unix_timestamp)?; // Accumulate price held before this trade
+    pool_state.real_quote_reserves = trade_quote.real_quote_reserves;
+    pool_state.real_base_reserves = trade_quote.real_base_reserves;
 
-    buy_finalize(ctx, output_base_amount, fee, input_quote_amount)
+    buy_finalize(ctx, trade_quote.output_amount + trade_quote.transfer_fee, trade_quote.fee, trade_quote.input_amount - trade_quote.fee)
 }
 
 // This function buys specified amount of base tokens on the bonding curve (required quote token amount is calculated internally)
@@ -136,41 +279,55 @@ pub fn buy_tokens_from_exact_quote(ctx:Context<ABuy>, quote_amount: u64, min_bas
 // Return
 //   Ok on success, ErrorCode on failure
 pub fn buy_exact_tokens_from_quote(ctx:Context<ABuy>, base_amount: u64, max_quote_amount: u64) -> Result<()> {
-    let main_state = &mut ctx.accounts.main_state;
-    
+    ctx.accounts.pool_state.check_public_buy(Clock::get()?.unix_timestamp)?;
+    let trade_quote = compute_buy_exact_tokens_from_quote(&ctx.accounts.main_state, &ctx.accounts.pool_state, &ctx.accounts.base_mint.to_account_info(), base_amount)?;
+    require!(trade_quote.input_amount <= max_quote_amount, PumpFunError::TooMuchInputQuote);
+
     let pool_state = &mut ctx.accounts.pool_state;
-    require!(pool_state.complete.eq(&false), PumpFunError::BondingCurveComplete); // BondingCurve must not be complete
+    pool_state.update_price_cumulative(Clock::get()?.unix_timestamp)?; // Accumulate price held before this trade
+    pool_state.real_base_reserves = trade_quote.real_base_reserves;
+    pool_state.real_quote_reserves = trade_quote.real_quote_reserves;
 
-    // base_amount must be greater than 0 and less than or equal to real_base_reserves
-    require!(base_amount.gt(&0) && base_amount.le(&pool_state.real_base_reserves), PumpFunError::WrongBaseAmount);
+    buy_finalize(ctx, trade_quote.output_amount + trade_quote.transfer_fee, trade_quote.fee, trade_quote.input_amount - trade_quote.fee)
+}
 
-    let buyer = ctx.accounts.buyer.to_account_info();
-    let buyer_base_ata = &ctx.accounts.buyer_base_ata;
-    let buyer_quote_ata = &ctx.accounts.buyer_quote_ata;
-    let token_program = ctx.accounts.token_program.to_account_info();
-    let system_program = ctx.accounts.system_program.to_account_info();
+// This function buys base tokens during presale with specified amount of quote, for allowlisted wallets only
+// Params
+//   ctx - Buy context (presale_claim is required)
+//   quote_amount - Amount of quote tokens to buy base tokens with
+//   min_base_amount - Minimum amount of base tokens to receive
+//   allocation - Maximum amount of base tokens the buyer can buy during presale (part of the allowlist leaf)
+//   proof - Merkle proof of (buyer, allocation) leaf
+// Return
+//   Ok on success, ErrorCode on failure
+pub fn presale_buy(ctx:Context<ABuy>, quote_amount: u64, min_base_amount: u64, allocation: u64, proof: Vec<[u8; 32]>) -> Result<()> {
+    let pool_state = &ctx.accounts.pool_state;
+    require!(pool_state.in_presale(Clock::get()?.unix_timestamp), PumpFunError::PresaleNotActive);
+    require!(
+        verify_merkle_proof(&proof, &pool_state.presale_merkle_root, presale_leaf(&ctx.accounts.buyer.key(), allocation)),
+        PumpFunError::InvalidMerkleProof
+    );
 
-    let mut input_base_amount = base_amount;
-    let mut input_quote_amount = pool_state.compute_required_amount_on_buy(input_base_amount);
-    // if new real_quote_reserves exceeds threshold, restrict quote_amount
-    if (pool_state.real_quote_reserves + input_quote_amount > pool_state.real_quote_threshold) {
-        input_quote_amount = pool_state.real_quote_threshold - pool_state.real_quote_reserves;
-        input_base_amount = pool_state.compute_receivable_amount_on_buy(input_quote_amount);
-    }
-    
-    let total_quote_amount = calculate_total_amount(main_state.trading_fee, input_quote_amount);
-    let fee = calculate_trading_fee(main_state.trading_fee, total_quote_amount);
-    require!(total_quote_amount <= max_quote_amount, PumpFunError::TooMuchInputQuote);
+    let trade_quote = compute_buy_tokens_from_exact_quote(&ctx.accounts.main_state, pool_state, &ctx.accounts.base_mint.to_account_info(), quote_amount)?;
+    require!(trade_quote.output_amount >= min_base_amount, PumpFunError::TooFewOutputTokens); // Check minimum amount
 
-    pool_state.real_base_reserves -= input_base_amount; // Decrease Real Base
-    pool_state.real_quote_reserves += input_quote_amount; // Increase Real Quote
-    
-    // // Convert Buyer's SOL to WSOL if necessary
-    // sync_native_amount(buyer.clone(), &buyer_quote_ata, total_quote_amount, system_program.clone(), token_program.clone())?;
+    // Record used allocation so it can't be bought again
+    let pool_key = pool_state.key();
+    let Some(presale_claim) = &mut ctx.accounts.presale_claim else {
+        return Err(PumpFunError::MissingPresaleClaim.into());
+    };
+    presale_claim.pool = pool_key;
+    presale_claim.user = ctx.accounts.buyer.key();
+    presale_claim.base_bought += trade_quote.output_amount;
+    require!(presale_claim.base_bought.le(&allocation), PumpFunError::PresaleAllocationExceeded);
 
-    buy_finalize(ctx, input_base_amount, fee, input_quote_amount)
-}
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.update_price_cumulative(Clock::get()?.unix_timestamp)?; // Accumulate price held before this trade
+    pool_state.real_quote_reserves = trade_quote.real_quote_reserves;
+    pool_state.real_base_reserves = trade_quote.real_base_reserves;
 
+    buy_finalize(ctx, trade_quote.output_amount + trade_quote.transfer_fee, trade_quote.fee, trade_quote.input_amount - trade_quote.fee)
+}
 
 // Buy context
 #[derive(Accounts)]
@@ -205,19 +362,26 @@ pub struct ABuy<'info> {
         bump,
     )]
     pub pool_state: Box<Account<'info, PoolState>>, // PoolState account
+    #[account(
+        mut,
+        seeds = [PoolState::CREATOR_FEE_VAULT_SEED, pool_state.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = pool_state,
+    )]
+    pub creator_fee_vault: Box<Account<'info, TokenAccount>>, // Pool creator's fee vault
 
     #[account(address = pool_state.base_mint)]
-    pub base_mint: Box<Account<'info, Mint>>, // Base token account
+    pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>, // Base token account (SPL Token or Token-2022)
     #[account(address = pool_state.quote_mint)]
     pub quote_mint: Box<Account<'info, Mint>>, // Quote token account
     
     #[account(
-        init_if_needed,
-        payer = buyer,
-        associated_token::mint = base_mint,
-        associated_token::authority = buyer,
+        mut,
+        address = get_associated_token_address_with_program_id(&buyer.key(), &base_mint.key(), &base_token_program.key())
     )]
-    pub buyer_base_ata: Box<Account<'info, TokenAccount>>, // Buyer's base token ATA
+    /// CHECK: created on buying if not exists
+    pub buyer_base_ata: UncheckedAccount<'info>, // Buyer's base token ATA
     #[account(
         init_if_needed,
         payer = buyer,
@@ -230,8 +394,9 @@ pub struct ABuy<'info> {
         mut,
         associated_token::mint = base_mint,
         associated_token::authority = pool_state,
+        associated_token::token_program = base_token_program,
     )]
-    pub reserver_base_ata: Box<Account<'info, TokenAccount>>, // PoolState's base token ATA
+    pub reserver_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>, // PoolState's base token ATA
     #[account(
         mut,
         associated_token::mint = quote_mint,
@@ -239,7 +404,48 @@ pub struct ABuy<'info> {
     )]
     pub reserver_quote_ata: Box<Account<'info, TokenAccount>>, // PoolState's quote token ATA
 
+    #[account(constraint = referrer.key() != buyer.key() @ PumpFunError::InvalidReferrer)]
+    /// CHECK: any wallet except the trader can refer
+    pub referrer: Option<AccountInfo<'info>>, // Referrer (optional)
+    #[account(
+        mut,
+        associated_token::mint = quote_mint,
+        associated_token::authority = referrer,
+    )]
+    pub referrer_quote_ata: Option<Box<Account<'info, TokenAccount>>>, // Referrer's quote token ATA (required with referrer)
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        seeds = [ReferrerStats::PREFIX_SEED, referrer.as_ref().map_or(&[][..], |referrer| referrer.key.as_ref())], // Rejected in handler without referrer
+        bump,
+        space = 8 + ReferrerStats::MAX_SIZE
+    )]
+    pub referrer_stats: Option<Box<Account<'info, ReferrerStats>>>, // Referrer's ReferrerStats account (required with referrer)
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        seeds = [UserPosition::PREFIX_SEED, pool_state.key().as_ref(), buyer.key().as_ref()],
+        bump,
+        space = 8 + UserPosition::MAX_SIZE
+    )]
+    pub user_position: Option<Box<Account<'info, UserPosition>>>, // Buyer's UserPosition account (required during anti-snipe window, optional otherwise)
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        seeds = [PresaleClaim::PREFIX_SEED, pool_state.key().as_ref(), buyer.key().as_ref()],
+        bump,
+        space = 8 + PresaleClaim::MAX_SIZE
+    )]
+    pub presale_claim: Option<Box<Account<'info, PresaleClaim>>>, // Buyer's PresaleClaim account (required on presale buy)
+    #[account(
+        mut,
+        seeds = [PoolObservations::PREFIX_SEED, pool_state.key().as_ref()],
+        bump,
+    )]
+    pub pool_observations: Option<AccountLoader<'info, PoolObservations>>, // Pool's PoolObservations account (required if enabled on the pool)
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Program<'info, Token>,
+    pub base_token_program: Interface<'info, TokenInterface>, // Token program of base token (SPL Token or Token-2022)
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token::{self, Mint, Token, TokenAccount, Transfer}, token_interface};
+use crate::{
+    PoolState, 
+    CompletionRewardClaimed, 
+    error::PumpFunError, 
+};
+
+// This function claims creator completion reward reserved when the bonding curve became complete
+// Params
+//   ctx - ClaimCompletionReward context
+// Return
+//   Ok on success, ErrorCode on failure
+//     If successful, emits CompletionRewardClaimed
+pub fn claim_completion_reward(ctx: Context<AClaimCompletionReward>) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state;
+    let amount = pool_state.creator_reward;
+    require!(pool_state.complete.eq(&true), PumpFunError::BondingCurveIncomplete); // BondingCurve must be complete
+    require!(amount.gt(&0), PumpFunError::NothingToClaim);
+    pool_state.creator_reward = 0;
+
+    // Transfer reward (quote) from pool to creator
+    let claim_transfer_cpi_account = Transfer {
+        from: ctx.accounts.reserver_quote_ata.to_account_info(),
+        to: ctx.accounts.creator_quote_ata.to_account_info(),
+        authority: pool_state.to_account_info()
+    };
+    token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), claim_transfer_cpi_account, &[&[
+        PoolState::PREFIX_SEED,
+        pool_state.base_mint.as_ref(),
+        pool_state.quote_mint.as_ref(),
+        &[ctx.bumps["pool_state"]]
+    ]]), amount)?;
+
+    emit!(CompletionRewardClaimed {
+        creator: ctx.accounts.creator.key(), 
+        base_mint: pool_state.base_mint, 
+        amount, 
+        timestamp: Clock::get()?.unix_timestamp
+    });
+
+    Ok(())
+}
+
+// ClaimCompletionReward context
+#[derive(Accounts)]
+pub struct AClaimCompletionReward<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>, // Pool creator
+
+    #[account(
+        mut,
+        seeds = [
+            PoolState::PREFIX_SEED,
+            base_mint.key().as_ref(), 
+            quote_mint.key().as_ref(),
+        ],
+        bump,
+        constraint = pool_state.owner == creator.key() @ PumpFunError::Unauthorised
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>, // PoolState account
+
+    #[account(address = pool_state.base_mint)]
+    pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>, // Base token account (SPL Token or Token-2022)
+    #[account(address = pool_state.quote_mint)]
+    pub quote_mint: Box<Account<'info, Mint>>, // Quote token account
+
+    #[account(
+        mut,
+        associated_token::mint = quote_mint,
+        associated_token::authority = pool_state,
+    )]
+    pub reserver_quote_ata: Box<Account<'info, TokenAccount>>, // PoolState's quote token ATA
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = quote_mint,
+        associated_token::authority = creator,
+    )]
+    pub creator_quote_ata: Box<Account<'info, TokenAccount>>, // Creator's quote token ATA
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
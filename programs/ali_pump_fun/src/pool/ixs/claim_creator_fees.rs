@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount, Transfer},
+    token_interface::{self, TokenInterface},
+};
+use crate::{
+    PoolState, 
+    CreatorFeesClaimed, 
+    error::PumpFunError, 
+};
+
+// This function claims all trading fees accumulated in the creator's fee vault
+// Params
+//   ctx - ClaimCreatorFees context
+// Return
+//   Ok on success, ErrorCode on failure
+//     If successful, emits CreatorFeesClaimed
+pub fn claim_creator_fees(ctx: Context<AClaimCreatorFees>) -> Result<()> {
+    let pool_state = &ctx.accounts.pool_state;
+    let creator_fee_vault = &ctx.accounts.creator_fee_vault;
+    let amount = creator_fee_vault.amount;
+    require!(amount.gt(&0), PumpFunError::NothingToClaim);
+
+    // Transfer accumulated fees (quote) from creator's fee vault to creator
+    let claim_transfer_cpi_account = Transfer {
+        from: creator_fee_vault.to_account_info(),
+        to: ctx.accounts.creator_quote_ata.to_account_info(),
+        authority: pool_state.to_account_info()
+    };
+    token::transfer(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), claim_transfer_cpi_account, &[&[
+        PoolState::PREFIX_SEED,
+        pool_state.base_mint.as_ref(),
+        pool_state.quote_mint.as_ref(),
+        &[ctx.bumps["pool_state"]]
+    ]]), amount)?;
+
+    emit!(CreatorFeesClaimed {
+        creator: ctx.accounts.creator.key(), 
+        base_mint: pool_state.base_mint, 
+        amount, 
+        timestamp: Clock::get()?.unix_timestamp
+    });
+
+    Ok(())
+}
+
+// ClaimCreatorFees context
+#[derive(Accounts)]
+pub struct AClaimCreatorFees<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>, // Pool creator
+
+    #[account(
+        seeds = [
+            PoolState::PREFIX_SEED,
+            base_mint.key().as_ref(), 
+            quote_mint.key().as_ref(),
+        ],
+        bump,
+        constraint = pool_state.owner == creator.key() @ PumpFunError::Unauthorised
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>, // PoolState account
+    #[account(
+        mut,
+        seeds = [PoolState::CREATOR_FEE_VAULT_SEED, pool_state.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = pool_state,
+    )]
+    pub creator_fee_vault: Box<Account<'info, TokenAccount>>, // Pool creator's fee vault
+
+    #[account(
+        address = pool_state.base_mint,
+        mint::token_program = base_token_program,
+    )]
+    pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>, // Base token account (SPL Token or Token-2022)
+    #[account(address = pool_state.quote_mint)]
+    pub quote_mint: Box<Account<'info, Mint>>, // Quote token account
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = quote_mint,
+        associated_token::authority = creator,
+    )]
+    pub creator_quote_ata: Box<Account<'info, TokenAccount>>, // Creator's quote token ATA
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub base_token_program: Interface<'info, TokenInterface>, // Token program of base token (SPL Token or Token-2022)
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,196 @@
+use crate::{
+    error::PumpFunError,
+    MainState, PoolState,
+    PoolClosed,
+    utils::get_withheld_transfer_fee,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer},
+    token_interface::{self, Burn, TokenInterface},
+};
+
+// This function closes a withdrawn or migrated pool (callable by withdrawer or creator)
+//   Dust is swept first: base dust is burnt, quote dust goes to withdrawer and unclaimed creator fees and completion reward go to creator
+//   Reserve ATAs, creator's fee vault and PoolState are closed, rent goes to creator who paid it on creation
+//   (Token-2022 base ATA holding withheld transfer fees is left open as it can't be closed)
+// Params
+//   ctx - ClosePool context
+// Return
+//   Ok on success, ErrorCode on failure
+//     PoolClosed is emitted on success
+pub fn close_pool(ctx: Context<AClosePool>) -> Result<()> {
+    let pool_state = &ctx.accounts.pool_state;
+
+    require!(pool_state.complete.eq(&true), PumpFunError::BondingCurveIncomplete); // BondingCurve must be complete
+    require!(pool_state.real_base_reserves.eq(&0) && pool_state.real_quote_reserves.eq(&0), PumpFunError::BondingCurveNotWithdrawn); // Withdrawn or migrated
+
+    let creator = ctx.accounts.creator.to_account_info();
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let base_token_program = ctx.accounts.base_token_program.to_account_info();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        PoolState::PREFIX_SEED,
+        pool_state.base_mint.as_ref(),
+        pool_state.quote_mint.as_ref(),
+        &[ctx.bumps["pool_state"]]
+    ]];
+
+    // Burn base dust
+    let base_dust = ctx.accounts.reserver_base_ata.amount;
+    if base_dust > 0 {
+        let burn_cpi_accounts = Burn {
+            mint: ctx.accounts.base_mint.to_account_info(),
+            from: ctx.accounts.reserver_base_ata.to_account_info(),
+            authority: pool_state.to_account_info()
+        };
+        token_interface::burn(CpiContext::new_with_signer(base_token_program.clone(), burn_cpi_accounts, signer_seeds), base_dust)?;
+    }
+
+    // Send unclaimed creator completion reward to creator
+    let creator_reward = pool_state.creator_reward;
+    if creator_reward > 0 {
+        let creator_reward_transfer_cpi_accounts = Transfer {
+            from: ctx.accounts.reserver_quote_ata.to_account_info(),
+            to: ctx.accounts.creator_quote_ata.to_account_info(),
+            authority: pool_state.to_account_info()
+        };
+        token::transfer(CpiContext::new_with_signer(token_program.clone(), creator_reward_transfer_cpi_accounts, signer_seeds), creator_reward)?;
+    }
+
+    // Sweep quote dust to withdrawer
+    let quote_dust = ctx.accounts.reserver_quote_ata.amount - creator_reward;
+    if quote_dust > 0 {
+        let quote_dust_transfer_cpi_accounts = Transfer {
+            from: ctx.accounts.reserver_quote_ata.to_account_info(),
+            to: ctx.accounts.withdrawer_quote_ata.to_account_info(),
+            authority: pool_state.to_account_info()
+        };
+        token::transfer(CpiContext::new_with_signer(token_program.clone(), quote_dust_transfer_cpi_accounts, signer_seeds), quote_dust)?;
+    }
+
+    // Send unclaimed creator fees to creator
+    let creator_fees = ctx.accounts.creator_fee_vault.amount;
+    if creator_fees > 0 {
+        let creator_fees_transfer_cpi_accounts = Transfer {
+            from: ctx.accounts.creator_fee_vault.to_account_info(),
+            to: ctx.accounts.creator_quote_ata.to_account_info(),
+            authority: pool_state.to_account_info()
+        };
+        token::transfer(CpiContext::new_with_signer(token_program.clone(), creator_fees_transfer_cpi_accounts, signer_seeds), creator_fees)?;
+    }
+
+    // Close PoolState's token accounts, creator receives rent
+    for token_account in [ctx.accounts.reserver_quote_ata.to_account_info(), ctx.accounts.creator_fee_vault.to_account_info()] {
+        let close_cpi_accounts = CloseAccount {
+            account: token_account,
+            destination: creator.clone(),
+            authority: pool_state.to_account_info(),
+        };
+        token::close_account(CpiContext::new_with_signer(token_program.clone(), close_cpi_accounts, signer_seeds))?;
+    }
+    if get_withheld_transfer_fee(&ctx.accounts.reserver_base_ata.to_account_info())?.eq(&0) {
+        let close_cpi_accounts = token_interface::CloseAccount {
+            account: ctx.accounts.reserver_base_ata.to_account_info(),
+            destination: creator.clone(),
+            authority: pool_state.to_account_info(),
+        };
+        token_interface::close_account(CpiContext::new_with_signer(base_token_program, close_cpi_accounts, signer_seeds))?;
+    }
+
+    emit!(PoolClosed {
+        closer: ctx.accounts.closer.key(),
+        base_mint: pool_state.base_mint,
+        base_dust,
+        quote_dust,
+        creator_fees,
+        creator_reward,
+        timestamp: Clock::get()?.unix_timestamp
+    });
+
+    // PoolState is closed by `close = creator`
+    Ok(())
+}
+
+// ClosePool context
+#[derive(Accounts)]
+pub struct AClosePool<'info> {
+    #[account(
+        mut,
+        constraint = closer.key() == main_state.withdrawer || closer.key() == pool_state.owner @ PumpFunError::Unauthorised
+    )]
+    pub closer: Signer<'info>, // Withdrawer or pool creator
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Box<Account<'info, MainState>>, // MainState account
+
+    #[account(
+        mut,
+        seeds = [
+            PoolState::PREFIX_SEED,
+            base_mint.key().as_ref(),
+            quote_mint.key().as_ref(),
+        ],
+        bump,
+        close = creator,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>, // PoolState account
+    #[account(mut, address = pool_state.owner)]
+    /// CHECK: receives rent, checked by address
+    pub creator: AccountInfo<'info>, // Pool creator
+    #[account(address = main_state.withdrawer)]
+    /// CHECK: receives quote dust, checked by address
+    pub withdrawer: AccountInfo<'info>, // Withdrawer
+
+    #[account(
+        mut,
+        address = pool_state.base_mint
+    )]
+    pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>, // Base token account (SPL Token or Token-2022)
+    #[account(address = pool_state.quote_mint)]
+    pub quote_mint: Box<Account<'info, Mint>>, // Quote token account
+
+    #[account(
+        mut,
+        associated_token::mint = base_mint,
+        associated_token::authority = pool_state,
+        associated_token::token_program = base_token_program,
+    )]
+    pub reserver_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>, // PoolState's base token ATA
+    #[account(
+        mut,
+        associated_token::mint = quote_mint,
+        associated_token::authority = pool_state,
+    )]
+    pub reserver_quote_ata: Box<Account<'info, TokenAccount>>, // PoolState's quote token ATA
+    #[account(
+        mut,
+        seeds = [PoolState::CREATOR_FEE_VAULT_SEED, pool_state.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = pool_state,
+    )]
+    pub creator_fee_vault: Box<Account<'info, TokenAccount>>, // Pool creator's fee vault
+
+    #[account(
+        init_if_needed,
+        payer = closer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = creator,
+    )]
+    pub creator_quote_ata: Box<Account<'info, TokenAccount>>, // Creator's quote token ATA
+    #[account(
+        init_if_needed,
+        payer = closer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = withdrawer,
+    )]
+    pub withdrawer_quote_ata: Box<Account<'info, TokenAccount>>, // Withdrawer's quote token ATA
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub base_token_program: Interface<'info, TokenInterface>, // Token program of base token (SPL Token or Token-2022)
+    pub system_program: Program<'info, System>,
+}
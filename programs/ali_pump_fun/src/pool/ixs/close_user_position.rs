@@ -0,0 +1,46 @@
+use crate::{
+    error::PumpFunError,
+    MainState, PoolState, UserPosition,
+};
+use anchor_lang::prelude::*;
+
+// This function closes the user's position account, rent goes back to the user
+//   Position can't be closed during anti-snipe window as it limits the user's buys
+// Params
+//   ctx - CloseUserPosition context
+// Return
+//   Ok on success, ErrorCode on failure
+pub fn close_user_position(ctx: Context<ACloseUserPosition>) -> Result<()> {
+    // Pool may have been closed already, anti-snipe window is over then
+    let pool_info = &ctx.accounts.pool_state;
+    if pool_info.owner.eq(&crate::ID) && !pool_info.data_is_empty() {
+        let pool_state = PoolState::try_deserialize(&mut &pool_info.data.borrow()[..])?;
+        require!(!pool_state.in_anti_snipe_window(&ctx.accounts.main_state, &Clock::get()?), PumpFunError::UserPositionLocked);
+    }
+
+    // UserPosition is closed by `close = user`
+    Ok(())
+}
+
+// CloseUserPosition context
+#[derive(Accounts)]
+pub struct ACloseUserPosition<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>, // Position owner
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Box<Account<'info, MainState>>, // MainState account
+
+    #[account(address = user_position.pool)]
+    /// CHECK: checked by address, deserialized only if the pool still exists
+    pub pool_state: UncheckedAccount<'info>, // PoolState account of the position
+    #[account(
+        mut,
+        seeds = [UserPosition::PREFIX_SEED, pool_state.key().as_ref(), user.key().as_ref()],
+        bump,
+        close = user,
+    )]
+    pub user_position: Box<Account<'info, UserPosition>>, // User's UserPosition account
+}
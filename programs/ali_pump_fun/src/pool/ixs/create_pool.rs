@@ -1,34 +1,166 @@
 use crate::{
-    constants::QUOTE_MINT_STR,
-    MainState, PoolState,
-    CreateEvent,
+    MainState, PoolState, QuoteConfig, UserPosition, CurveType,
+    CreateEvent, TradeEvent, CompleteEvent,
     error::PumpFunError,
-    utils::{sync_native_amount},
+    compute_buy_tokens_from_exact_quote,
+    utils::{sync_native_amount, close_token_account, split_trading_fee, is_native_mint, check_mint_extensions, get_transfer_fee, calculate_transfer_fee},
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token::{self, Mint, SyncNative, Token, TokenAccount, Transfer},
+    token_interface::{self, TokenInterface, TransferChecked},
 };
 
 
 /*** Note: Here, 'pool' means 'bonding curve' - they've got the same meaning ***/
 
+// Internal initial buy function - the creator buys at the launch price right after creation
+//   (recorded in creator's UserPosition and limited by max_buy_per_wallet like any buy during anti-snipe window)
+// Params
+//   ctx - CreatePool context
+//   quote_amount - Amount of quote tokens to buy base tokens with (fee included)
+// Return
+//   Ok on success, ErrorCode on failure
+//     If successful, emits (Buy) TradeEvent
+//       And if reaches complete marketCap, emits CompleteEvent as well
+fn initial_buy(ctx: Context<ACreatePool>, quote_amount: u64) -> Result<()> {
+    let trade_quote = compute_buy_tokens_from_exact_quote(&ctx.accounts.main_state, &ctx.accounts.pool_state, &ctx.accounts.base_mint.to_account_info(), quote_amount)?;
+    let base_amount = trade_quote.output_amount + trade_quote.transfer_fee;
+    let fee = trade_quote.fee;
+    let input_quote_amount = trade_quote.input_amount - fee;
+
+    let creator_fee_vault = &ctx.accounts.creator_fee_vault;
+    let (Some(fee_quote_ata), Some(creator_quote_ata)) = (
+        &ctx.accounts.fee_quote_ata, 
+        &ctx.accounts.creator_quote_ata
+    ) else {
+        return Err(PumpFunError::MissingInitialBuyAccounts.into());
+    };
+
+    let pool_state = &mut ctx.accounts.pool_state;
+    let creator = ctx.accounts.creator.to_account_info();
+    let token_program = ctx.accounts.token_program.to_account_info();
+
+    // Record initial buy in creator's position and limit bought amount per wallet during anti-snipe window
+    let clock = Clock::get()?;
+    let in_anti_snipe_window = pool_state.in_anti_snipe_window(&ctx.accounts.main_state, &clock);
+    if let Some(user_position) = &mut ctx.accounts.user_position {
+        user_position.pool = pool_state.key();
+        user_position.user = creator.key();
+    }
+    let user_position = ctx.accounts.user_position.as_deref_mut().map(|user_position| &mut **user_position);
+    UserPosition::record_limited_buy(user_position, &ctx.accounts.main_state, in_anti_snipe_window, base_amount, trade_quote.input_amount, fee, clock.unix_timestamp)?;
+
+    pool_state.update_price_cumulative(clock.unix_timestamp)?; // Accumulate price held before this trade
+    pool_state.real_quote_reserves = trade_quote.real_quote_reserves;
+    pool_state.real_base_reserves = trade_quote.real_base_reserves;
+
+    // Convert creator's SOL to WSOL if quote is WSOL and creator's WSOL is insufficient
+    if is_native_mint(&pool_state.quote_mint) {
+        sync_native_amount(creator.clone(), creator_quote_ata, trade_quote.input_amount, ctx.accounts.system_program.to_account_info(), token_program.clone())?;
+    }
+
+    let (protocol_fee, creator_fee, _) = split_trading_fee(ctx.accounts.main_state.creator_fee_share, 0, fee);
+
+    // Transfer protocol fee (quote) from creator to feeRecipient
+    let fee_transfer_cpi_account = Transfer {
+        from: creator_quote_ata.to_account_info(),
+        to: fee_quote_ata.to_account_info(),
+        authority: creator.clone()
+    };
+    token::transfer(CpiContext::new(token_program.clone(), fee_transfer_cpi_account), protocol_fee)?;
+
+    // Transfer creator fee (quote) from creator to creator's fee vault
+    if creator_fee > 0 {
+        let creator_fee_transfer_cpi_account = Transfer {
+            from: creator_quote_ata.to_account_info(),
+            to: creator_fee_vault.to_account_info(),
+            authority: creator.clone()
+        };
+        token::transfer(CpiContext::new(token_program.clone(), creator_fee_transfer_cpi_account), creator_fee)?;
+    }
+
+    // Transfer input_quote_amount (quote) from creator to pool
+    let input_amount_transfer_cpi_account = Transfer {
+        from: creator_quote_ata.to_account_info(),
+        to: ctx.accounts.reserver_quote_ata.to_account_info(),
+        authority: creator.clone()
+    };
+    token::transfer(CpiContext::new(token_program.clone(), input_amount_transfer_cpi_account), input_quote_amount)?;
+
+    // Transfer base tokens from pool to creator
+    let output_amount_transfer_cpi_account = TransferChecked {
+        from: ctx.accounts.reserver_base_ata.to_account_info(),
+        mint: ctx.accounts.base_mint.to_account_info(),
+        to: ctx.accounts.creator_base_ata.to_account_info(),
+        authority: pool_state.to_account_info()
+    };
+    token_interface::transfer_checked(CpiContext::new_with_signer(ctx.accounts.base_token_program.to_account_info(), output_amount_transfer_cpi_account, &[&[
+        PoolState::PREFIX_SEED,
+        pool_state.base_mint.as_ref(),
+        pool_state.quote_mint.as_ref(),
+        &[ctx.bumps["pool_state"]]
+    ]]), base_amount, ctx.accounts.base_mint.decimals)?;
+
+    // Unwrap remaining WSOL (close creator's WSOL account unless it held WSOL before)
+    if is_native_mint(&pool_state.quote_mint) {
+        close_token_account(creator.clone(), creator_quote_ata, token_program.clone())?;
+    }
+
+    // Emit (Buy) TradeEvent
+    emit!(TradeEvent {
+        user: creator.key(), 
+        base_mint: pool_state.base_mint, 
+        base_amount, 
+        quote_amount: trade_quote.input_amount, 
+        base_reserves: pool_state.real_base_reserves + pool_state.virt_base_reserves, 
+        quote_reserves: pool_state.virt_quote_reserves + pool_state.real_quote_reserves, 
+        is_buy: true, 
+        referrer: None, 
+        timestamp: Clock::get()?.unix_timestamp
+    });
+
+    // Check if bonding curve becomes complete
+    if trade_quote.complete {
+        let creator_reward = pool_state.set_complete(ctx.accounts.main_state.creator_completion_reward);
+        
+        // Emit CompleteEvent
+        emit!(CompleteEvent {
+            base_mint: pool_state.base_mint, 
+            creator_reward,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+    }
+
+    Ok(())
+}
+
 // This function creates a new pool
 // Params
 //   ctx - CreatePool context
-//   base_amount - Base token amount to put in the bonding curve
+//   curve_type - Shape of the bonding curve (must be allowed by MainState)
+//   initial_buy_quote_amount - Amount of quote tokens the creator buys with at the launch price (optional)
+//     fee_recipient, fee_quote_ata and creator_quote_ata accounts are required with it
+//     (user_position as well during anti-snipe window, fails with MaxBuyPerWalletExceeded above max_buy_per_wallet)
+//     (fails with TradingNotStarted if trading_start_ts is in the future)
+//   trading_start_ts - Time trading opens at (optional, tradable immediately if None)
+//     (fails with ScheduledStartWithSlotWindow if in the future while anti-snipe window is in slots)
+//   deadline - Time the pool must complete by, else it enters refund state (optional)
 // Return
 //   Ok on success, ErrorCode on Failure
-//     CreateEvent is emitted on success
-pub fn create_pool(ctx: Context<ACreatePool>) -> Result<()> {
+//     CreateEvent is emitted on success, followed by (Buy) TradeEvent on initial buy
+pub fn create_pool(ctx: Context<ACreatePool>, curve_type: CurveType, initial_buy_quote_amount: Option<u64>, trading_start_ts: Option<i64>, deadline: Option<i64>) -> Result<()> {
     let main_state = &mut ctx.accounts.main_state;
+    require!(main_state.paused.eq(&false), PumpFunError::Paused);
+    require!(main_state.allowed_curves & curve_type.mask() != 0, PumpFunError::CurveTypeNotAllowed);
 
     let base_amount: u64 = ctx.accounts.creator_base_ata.amount;
     require!(base_amount.eq(&ctx.accounts.base_mint.supply), PumpFunError::WrongBaseAmountOnCreation);
     require!(base_amount.eq(&main_state.total_supply), PumpFunError::WrongBaseAmountOnCreation);
     require!(&ctx.accounts.base_mint.mint_authority.is_some().eq(&false), PumpFunError::BaseTokenMustNotBeMintable);
     require!(&ctx.accounts.base_mint.freeze_authority.is_some().eq(&false), PumpFunError::BaseTokenMustNotBeFreezable);
+    check_mint_extensions(&ctx.accounts.base_mint.to_account_info())?; // Token-2022 mint must have only supported extensions
 
     let pool_state = &mut ctx.accounts.pool_state;
     let creator = ctx.accounts.creator.to_account_info();
@@ -36,27 +168,35 @@ pub fn create_pool(ctx: Context<ACreatePool>) -> Result<()> {
     let token_program = ctx.accounts.token_program.to_account_info();
     
     // Initialize all members of pool_state
-    pool_state.owner = creator.key(); // Creator's address
-    pool_state.base_mint = creator_base_ata.mint; // Base token mint address
-    pool_state.quote_mint = ctx.accounts.quote_mint.key(); // Quote token mint address
-    pool_state.real_base_reserves = main_state.total_supply; // Total supply of base tokens is all put into the pool
-    pool_state.virt_base_reserves = main_state.init_virt_base_reserves; // Initial virtual token reserves
-    pool_state.real_quote_reserves = 0; // 0 SOL
-    pool_state.virt_quote_reserves = main_state.init_virt_quote_reserves; // Initial virtual quote token reserves
-    pool_state.real_quote_threshold = main_state.real_quote_threshold; // Real quote token threshold
+    pool_state.init(main_state, &ctx.accounts.quote_config, creator.key(), creator_base_ata.mint, ctx.accounts.quote_mint.key(), curve_type, &Clock::get()?);
+    if let Some(trading_start_ts) = trading_start_ts {
+        require!(trading_start_ts.le(&pool_state.created_at) || main_state.allows_scheduled_start(), PumpFunError::ScheduledStartWithSlotWindow);
+    }
+    pool_state.trading_start_ts = trading_start_ts;
+    if let Some(deadline) = deadline {
+        require!(deadline.gt(&pool_state.created_at) && deadline.gt(&trading_start_ts.unwrap_or(0)), PumpFunError::InvalidDeadline);
+    }
+    pool_state.deadline = deadline;
 
     /* Transfer */
     // Transfer base tokens from creator to pool
-    let base_transfer_cpi_accounts = Transfer {
+    let base_transfer_cpi_accounts = TransferChecked {
         from: ctx.accounts.creator_base_ata.to_account_info(),
+        mint: ctx.accounts.base_mint.to_account_info(),
         to: ctx.accounts.reserver_base_ata.to_account_info(),
         authority: creator.clone(),
     };
-    token::transfer(
-        CpiContext::new(token_program.to_account_info(), base_transfer_cpi_accounts),
+    token_interface::transfer_checked(
+        CpiContext::new(ctx.accounts.base_token_program.to_account_info(), base_transfer_cpi_accounts),
         base_amount,
+        ctx.accounts.base_mint.decimals,
     )?;
 
+    // Pool holds total supply less transfer fee withheld (Token-2022)
+    let transfer_fee = calculate_transfer_fee(&get_transfer_fee(&ctx.accounts.base_mint.to_account_info())?, base_amount);
+    pool_state.real_base_reserves -= transfer_fee;
+    pool_state.initial_base_reserves -= transfer_fee;
+
     // Emit createPool event
     emit!(CreateEvent {
         creator: pool_state.owner, 
@@ -64,9 +204,17 @@ pub fn create_pool(ctx: Context<ACreatePool>) -> Result<()> {
         // quote_mint: pool_state.quote_mint, 
         base_reserves: pool_state.real_base_reserves + pool_state.virt_base_reserves, // the sum of real base token reserves and virtual base token reserves
         quote_reserves: pool_state.virt_quote_reserves, // virtual quote token reserves only
+        curve_type: pool_state.curve_type,
+        trading_start_ts: pool_state.trading_start_ts,
+        deadline: pool_state.deadline,
         timestamp: Clock::get()?.unix_timestamp
     });
 
+    if let Some(quote_amount) = initial_buy_quote_amount {
+        require!(pool_state.trading_started(Clock::get()?.unix_timestamp), PumpFunError::TradingNotStarted); // Scheduled pool can't be bought before trading start time
+        return initial_buy(ctx, quote_amount);
+    }
+
     Ok(())
 }
 
@@ -97,27 +245,31 @@ pub struct ACreatePool<'info> {
     #[account(
         constraint = base_mint.key().to_string() != quote_mint.key().to_string() @ PumpFunError::InvalidTokenPair
     )]
-    pub base_mint: Box<Account<'info, Mint>>, // Base token mint account
+    pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>, // Base token mint account (SPL Token or Token-2022)
+    pub quote_mint: Box<Account<'info, Mint>>, // Quote token mint account
     #[account(
-        constraint = quote_mint.key().to_string() == QUOTE_MINT_STR @ PumpFunError::UnknownQuoteMint
+        seeds = [QuoteConfig::PREFIX_SEED, quote_mint.key().as_ref()],
+        bump,
     )]
-    pub quote_mint: Box<Account<'info, Mint>>, // Quote token mint account
+    pub quote_config: Box<Account<'info, QuoteConfig>>, // QuoteConfig of quote_mint (must be whitelisted)
 
     #[account(
         mut,
-        associated_token::mint = base_mint,
-        associated_token::authority = creator
+        token::mint = base_mint,
+        token::authority = creator,
+        token::token_program = base_token_program,
     )]
-    pub creator_base_ata: Box<Account<'info, TokenAccount>>, // Creator's base token ATA
+    pub creator_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>, // Creator's base token account
 
     #[account(
         init,
         payer = creator,
         associated_token::mint = base_mint,
         associated_token::authority = pool_state,
+        associated_token::token_program = base_token_program,
         constraint = reserver_base_ata.amount == 0 // PoolState's base token amount must be 0
     )]
-    pub reserver_base_ata: Box<Account<'info, TokenAccount>>, // PoolState's base token ATA
+    pub reserver_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>, // PoolState's base token ATA
     #[account(
         init,
         payer = creator,
@@ -126,7 +278,43 @@ pub struct ACreatePool<'info> {
     )]
     pub reserver_quote_ata: Box<Account<'info, TokenAccount>>,
 
+    #[account(address = main_state.fee_recipient)]
+    /// CHECK: this should be set by admin
+    pub fee_recipient: Option<AccountInfo<'info>>, // FeeRecipient (required with initial buy)
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = quote_mint,
+        associated_token::authority = fee_recipient,
+    )]
+    pub fee_quote_ata: Option<Box<Account<'info, TokenAccount>>>, // FeeRecipient's quote token ATA (required with initial buy)
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = quote_mint,
+        associated_token::authority = creator,
+    )]
+    pub creator_quote_ata: Option<Box<Account<'info, TokenAccount>>>, // Creator's quote token ATA (required with initial buy)
+    #[account(
+        init,
+        payer = creator,
+        seeds = [PoolState::CREATOR_FEE_VAULT_SEED, pool_state.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = pool_state,
+    )]
+    pub creator_fee_vault: Box<Account<'info, TokenAccount>>, // (New) Pool creator's fee vault (rent is paid by creator and returned on close_pool)
+    #[account(
+        init_if_needed,
+        payer = creator,
+        seeds = [UserPosition::PREFIX_SEED, pool_state.key().as_ref(), creator.key().as_ref()],
+        bump,
+        space = 8 + UserPosition::MAX_SIZE
+    )]
+    pub user_position: Option<Box<Account<'info, UserPosition>>>, // Creator's UserPosition account (required with initial buy during anti-snipe window, optional otherwise)
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Program<'info, Token>,
+    pub base_token_program: Interface<'info, TokenInterface>, // Token program of base token (SPL Token or Token-2022)
     pub system_program: Program<'info, System>,
 }
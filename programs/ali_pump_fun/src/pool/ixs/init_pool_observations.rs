@@ -0,0 +1,48 @@
+use crate::{
+    error::PumpFunError,
+    PoolState, PoolObservations,
+};
+use anchor_lang::prelude::*;
+
+// This function creates price history ring buffer of a pool, every later trade must record a sample in it
+// Params
+//   ctx - InitPoolObservations context
+// Return
+//   Ok on success, ErrorCode on failure
+pub fn init_pool_observations(ctx: Context<AInitPoolObservations>) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state;
+    let mut pool_observations = ctx.accounts.pool_observations.load_init()?;
+    pool_observations.pool = pool_state.key();
+    pool_state.observations_enabled = true;
+
+    Ok(())
+}
+
+// InitPoolObservations context
+#[derive(Accounts)]
+pub struct AInitPoolObservations<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>, // Pool creator
+
+    #[account(
+        mut,
+        seeds = [
+            PoolState::PREFIX_SEED,
+            pool_state.base_mint.as_ref(),
+            pool_state.quote_mint.as_ref(),
+        ],
+        bump,
+        constraint = pool_state.owner == creator.key() @ PumpFunError::Unauthorised
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>, // PoolState account
+    #[account(
+        init,
+        payer = creator,
+        seeds = [PoolObservations::PREFIX_SEED, pool_state.key().as_ref()],
+        bump,
+        space = 8 + PoolObservations::MAX_SIZE
+    )]
+    pub pool_observations: AccountLoader<'info, PoolObservations>, // Pool's PoolObservations account
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,185 @@
+use crate::{
+    constants::{METADATA_PROGRAM_ID, METADATA_SEED, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH},
+    MainState, PoolState, QuoteConfig, CurveType,
+    CreateEvent,
+    error::PumpFunError,
+    utils::create_metadata_account,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, spl_token::instruction::AuthorityType, Mint, MintTo, SetAuthority, Token, TokenAccount},
+};
+use std::str::FromStr;
+
+
+/*** Note: Here, 'pool' means 'bonding curve' - they've got the same meaning ***/
+
+// This function creates a new base token with metadata and its pool in a single transaction
+//   The pool is the temporary mint authority: it mints total supply into the pool, writes metadata and revokes the authority
+// Params
+//   ctx - LaunchToken context
+//   name - Token name
+//   symbol - Token symbol
+//   uri - Token metadata URI
+//   decimals - Token decimals
+//   curve_type - Shape of the bonding curve (must be allowed by MainState)
+// Return
+//   Ok on success, ErrorCode on Failure
+//     CreateEvent is emitted on success
+pub fn launch_token(ctx: Context<ALaunchToken>, name: String, symbol: String, uri: String, decimals: u8, curve_type: CurveType) -> Result<()> {
+    let main_state = &ctx.accounts.main_state;
+    require!(main_state.paused.eq(&false), PumpFunError::Paused);
+    require!(main_state.allowed_curves & curve_type.mask() != 0, PumpFunError::CurveTypeNotAllowed);
+    require!(name.len() <= MAX_NAME_LENGTH && symbol.len() <= MAX_SYMBOL_LENGTH && uri.len() <= MAX_URI_LENGTH, PumpFunError::InvalidTokenMetadata);
+
+    let pool_state = &mut ctx.accounts.pool_state;
+    let creator = ctx.accounts.creator.to_account_info();
+    let token_program = ctx.accounts.token_program.to_account_info();
+
+    // Initialize all members of pool_state
+    pool_state.init(main_state, &ctx.accounts.quote_config, creator.key(), ctx.accounts.base_mint.key(), ctx.accounts.quote_mint.key(), curve_type, &Clock::get()?);
+
+    let base_mint_key = ctx.accounts.base_mint.key();
+    let quote_mint_key = ctx.accounts.quote_mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        PoolState::PREFIX_SEED,
+        base_mint_key.as_ref(),
+        quote_mint_key.as_ref(),
+        &[ctx.bumps["pool_state"]]
+    ]];
+
+    // Mint total supply of base tokens into pool
+    let mint_to_cpi_accounts = MintTo {
+        mint: ctx.accounts.base_mint.to_account_info(),
+        to: ctx.accounts.reserver_base_ata.to_account_info(),
+        authority: pool_state.to_account_info(),
+    };
+    token::mint_to(
+        CpiContext::new_with_signer(token_program.clone(), mint_to_cpi_accounts, signer_seeds),
+        main_state.total_supply,
+    )?;
+
+    // Write metadata of base token
+    create_metadata_account(
+        ctx.accounts.metadata_program.to_account_info(),
+        ctx.accounts.metadata.to_account_info(),
+        ctx.accounts.base_mint.to_account_info(),
+        pool_state.to_account_info(),
+        creator.clone(),
+        ctx.accounts.system_program.to_account_info(),
+        name,
+        symbol,
+        uri,
+        signer_seeds,
+    )?;
+
+    // Revoke mint authority (freeze authority is never set)
+    let revoke_cpi_accounts = SetAuthority {
+        current_authority: pool_state.to_account_info(),
+        account_or_mint: ctx.accounts.base_mint.to_account_info(),
+    };
+    token::set_authority(
+        CpiContext::new_with_signer(token_program, revoke_cpi_accounts, signer_seeds),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
+    // Emit createPool event
+    emit!(CreateEvent {
+        creator: pool_state.owner, 
+        base_mint: pool_state.base_mint, 
+        // quote_mint: pool_state.quote_mint, 
+        base_reserves: pool_state.real_base_reserves + pool_state.virt_base_reserves, // the sum of real base token reserves and virtual base token reserves
+        quote_reserves: pool_state.virt_quote_reserves, // virtual quote token reserves only
+        curve_type: pool_state.curve_type,
+        trading_start_ts: pool_state.trading_start_ts,
+        deadline: pool_state.deadline,
+        timestamp: Clock::get()?.unix_timestamp
+    });
+
+    Ok(())
+}
+
+// LaunchToken context
+#[derive(Accounts)]
+#[instruction(name: String, symbol: String, uri: String, decimals: u8)]
+pub struct ALaunchToken<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>, // Pool creator
+
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Box<Account<'info, MainState>>, // MainState account
+    #[account(
+        init,
+        payer = creator,
+        seeds =[
+            PoolState::PREFIX_SEED,
+            base_mint.key().as_ref(),
+            quote_mint.key().as_ref(),
+        ],
+        bump,
+        space = 8 + PoolState::MAX_SIZE
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>, // (New) PoolState account
+
+    #[account(
+        init,
+        payer = creator,
+        mint::decimals = decimals,
+        mint::authority = pool_state,
+    )]
+    pub base_mint: Box<Account<'info, Mint>>, // (New) Base token mint account (keypair signer)
+    #[account(
+        constraint = base_mint.key().to_string() != quote_mint.key().to_string() @ PumpFunError::InvalidTokenPair
+    )]
+    pub quote_mint: Box<Account<'info, Mint>>, // Quote token mint account
+    #[account(
+        seeds = [QuoteConfig::PREFIX_SEED, quote_mint.key().as_ref()],
+        bump,
+    )]
+    pub quote_config: Box<Account<'info, QuoteConfig>>, // QuoteConfig of quote_mint (must be whitelisted)
+
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = base_mint,
+        associated_token::authority = pool_state,
+    )]
+    pub reserver_base_ata: Box<Account<'info, TokenAccount>>, // PoolState's base token ATA
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = quote_mint,
+        associated_token::authority = pool_state,
+    )]
+    pub reserver_quote_ata: Box<Account<'info, TokenAccount>>, // PoolState's quote token ATA
+    #[account(
+        init,
+        payer = creator,
+        seeds = [PoolState::CREATOR_FEE_VAULT_SEED, pool_state.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = pool_state,
+    )]
+    pub creator_fee_vault: Box<Account<'info, TokenAccount>>, // (New) Pool creator's fee vault (rent is paid by creator and returned on close_pool)
+
+    #[account(
+        mut,
+        seeds = [METADATA_SEED, metadata_program.key().as_ref(), base_mint.key().as_ref()],
+        bump,
+        seeds::program = metadata_program.key(),
+    )]
+    /// CHECK: created by metadata program
+    pub metadata: UncheckedAccount<'info>, // Metadata account of base token
+    #[account(address = Pubkey::from_str(METADATA_PROGRAM_ID).unwrap())]
+    /// CHECK: Metaplex Token Metadata program
+    pub metadata_program: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,172 @@
+use crate::{
+    error::PumpFunError,
+    MainState, PoolState,
+    MigrateEvent,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount},
+    token_interface::{self, Burn, TokenInterface},
+};
+use cp_amm::{program::CpAmm, utils::integer_sqrt};
+
+// This function migrates a complete bonding curve into a constant product AMM pool (callable by anyone)
+//   Real quote reserves are paired with base tokens at the final curve price, surplus base tokens are burnt
+//   and LP tokens are minted to a PDA that no instruction can sign for, so liquidity is locked forever
+//   The AMM only pairs SPL Token mints, complete Token-2022 pools are withdrawn instead
+// Params
+//   ctx - MigratePool context
+// Return
+//   Ok on success, ErrorCode on failure
+//     MigrateEvent is emitted on success
+pub fn migrate_pool(ctx: Context<AMigratePool>) -> Result<()> {
+    let main_state = &ctx.accounts.main_state;
+    let pool_state = &mut ctx.accounts.pool_state;
+
+    require!(main_state.paused.eq(&false) && pool_state.paused.eq(&false), PumpFunError::Paused); // Neither program nor pool must be paused
+    require!(pool_state.complete.eq(&true), PumpFunError::BondingCurveIncomplete); // BondingCurve must be complete
+    require!(ctx.accounts.base_token_program.key().eq(&token::ID), PumpFunError::UnsupportedAmmBaseToken);
+    require!(pool_state.real_base_reserves.gt(&0) && pool_state.real_quote_reserves.gt(&0), PumpFunError::BondingCurveAlreadyWithdrawn);
+
+    let quote_amount = pool_state.real_quote_reserves;
+    let base_amount = pool_state.compute_base_amount_at_price(quote_amount)?;
+    let burnt_base_amount = pool_state.real_base_reserves - base_amount;
+    let lp_amount = integer_sqrt((base_amount as u128) * (quote_amount as u128)) as u64;
+
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let base_token_program = ctx.accounts.base_token_program.to_account_info();
+    let pool_seeds: &[&[u8]] = &[
+        PoolState::PREFIX_SEED,
+        pool_state.base_mint.as_ref(),
+        pool_state.quote_mint.as_ref(),
+        &[ctx.bumps["pool_state"]]
+    ];
+
+    // Burn base tokens that can't be paired at the final curve price
+    if burnt_base_amount > 0 {
+        let burn_cpi_accounts = Burn {
+            mint: ctx.accounts.base_mint.to_account_info(),
+            from: ctx.accounts.reserver_base_ata.to_account_info(),
+            authority: pool_state.to_account_info()
+        };
+        token_interface::burn(CpiContext::new_with_signer(base_token_program, burn_cpi_accounts, &[pool_seeds]), burnt_base_amount)?;
+    }
+
+    // Seed the AMM pool with real reserves, LP tokens go to the locker PDA
+    let initialize_cpi_accounts = cp_amm::cpi::accounts::AInitializePool {
+        payer: ctx.accounts.payer.to_account_info(),
+        authority: pool_state.to_account_info(),
+        amm_pool: ctx.accounts.amm_pool.to_account_info(),
+        base_mint: ctx.accounts.base_mint.to_account_info(),
+        quote_mint: ctx.accounts.quote_mint.to_account_info(),
+        lp_mint: ctx.accounts.lp_mint.to_account_info(),
+        base_vault: ctx.accounts.amm_base_vault.to_account_info(),
+        quote_vault: ctx.accounts.amm_quote_vault.to_account_info(),
+        source_base_ata: ctx.accounts.reserver_base_ata.to_account_info(),
+        source_quote_ata: ctx.accounts.reserver_quote_ata.to_account_info(),
+        lp_recipient: ctx.accounts.lp_locker.to_account_info(),
+        recipient_lp_ata: ctx.accounts.locker_lp_ata.to_account_info(),
+        associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+        token_program: token_program.clone(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+    };
+    cp_amm::cpi::initialize_pool(
+        CpiContext::new_with_signer(ctx.accounts.amm_program.to_account_info(), initialize_cpi_accounts, &[pool_seeds]),
+        base_amount,
+        quote_amount
+    )?;
+
+    // Emit MigrateEvent
+    emit!(MigrateEvent {
+        migrator: ctx.accounts.payer.key(),
+        base_mint: pool_state.base_mint,
+        amm_pool: ctx.accounts.amm_pool.key(),
+        base_amount,
+        quote_amount,
+        burnt_base_amount,
+        lp_amount,
+        timestamp: Clock::get()?.unix_timestamp
+    });
+
+    pool_state.real_base_reserves = 0;
+    pool_state.real_quote_reserves = 0;
+
+    Ok(())
+}
+
+// MigratePool context
+#[derive(Accounts)]
+pub struct AMigratePool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>, // Anyone paying rent for the AMM accounts
+
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Box<Account<'info, MainState>>, // MainState account
+    #[account(
+        mut,
+        seeds = [
+            PoolState::PREFIX_SEED,
+            base_mint.key().as_ref(),
+            quote_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>, // PoolState account
+
+    #[account(
+        mut,
+        address = pool_state.base_mint
+    )]
+    pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>, // Base token account (SPL Token or Token-2022)
+    #[account(address = pool_state.quote_mint)]
+    pub quote_mint: Box<Account<'info, Mint>>, // Quote token account
+
+    #[account(
+        mut,
+        associated_token::mint = base_mint,
+        associated_token::authority = pool_state,
+        associated_token::token_program = base_token_program,
+    )]
+    pub reserver_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>, // PoolState's base token ATA
+    #[account(
+        mut,
+        associated_token::mint = quote_mint,
+        associated_token::authority = pool_state,
+    )]
+    pub reserver_quote_ata: Box<Account<'info, TokenAccount>>, // PoolState's quote token ATA
+
+    #[account(
+        seeds = [PoolState::LP_LOCKER_SEED, pool_state.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: PDA without data, owner of the locked LP tokens
+    pub lp_locker: AccountInfo<'info>,
+
+    #[account(mut)]
+    /// CHECK: validated and initialized by the AMM program
+    pub amm_pool: AccountInfo<'info>, // AmmPool account
+    #[account(mut)]
+    /// CHECK: validated and initialized by the AMM program
+    pub lp_mint: AccountInfo<'info>, // AmmPool's LP token mint
+    #[account(mut)]
+    /// CHECK: validated and initialized by the AMM program
+    pub amm_base_vault: AccountInfo<'info>, // AmmPool's base token ATA
+    #[account(mut)]
+    /// CHECK: validated and initialized by the AMM program
+    pub amm_quote_vault: AccountInfo<'info>, // AmmPool's quote token ATA
+    #[account(mut)]
+    /// CHECK: validated and initialized by the AMM program
+    pub locker_lp_ata: AccountInfo<'info>, // Locker's LP token ATA
+
+    pub amm_program: Program<'info, CpAmm>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub base_token_program: Interface<'info, TokenInterface>, // Token program of base token (SPL Token or Token-2022)
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
@@ -1,11 +1,53 @@
 pub mod create_pool;
 pub use create_pool::*;
 
+pub mod launch_token;
+pub use launch_token::*;
+
 pub mod buy;
 pub use buy::*;
 
 pub mod sell;
 pub use sell::*;
 
+pub mod close_user_position;
+pub use close_user_position::*;
+
+pub mod quote;
+pub use quote::*;
+
+pub mod claim_creator_fees;
+pub use claim_creator_fees::*;
+
+pub mod claim_completion_reward;
+pub use claim_completion_reward::*;
+
+pub mod set_pool_pause;
+pub use set_pool_pause::*;
+
+pub mod set_presale;
+pub use set_presale::*;
+
+pub mod init_pool_observations;
+pub use init_pool_observations::*;
+
+pub mod refund;
+pub use refund::*;
+
+pub mod reclaim_unsold;
+pub use reclaim_unsold::*;
+
 pub mod withdraw;
 pub use withdraw::*;
+
+pub mod release_vested_base;
+pub use release_vested_base::*;
+
+pub mod migrate_pool;
+pub use migrate_pool::*;
+
+pub mod close_pool;
+pub use close_pool::*;
+
+pub mod close_refunded_pool;
+pub use close_refunded_pool::*;
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::{
+    MainState, PoolState, UserPosition, TradeQuote, PriceObservation,
+    error::PumpFunError,
+    compute_buy_tokens_from_exact_quote, compute_buy_exact_tokens_from_quote, compute_sell,
+};
+
+// Internal function checking a quoted public buy the same way the buy does
+//   Public curve must be open and buy limit per wallet of anti-snipe window must not be exceeded
+// Params
+//   ctx - Quote context (user_position is the buyer's, None for a wallet without position)
+//   trade_quote - Quoted buy
+// Return
+//   TradeQuote on success, ErrorCode on failure
+fn check_quoted_buy(ctx: &Context<AQuote>, trade_quote: TradeQuote) -> Result<TradeQuote> {
+    let main_state = &ctx.accounts.main_state;
+    let pool_state = &ctx.accounts.pool_state;
+    let clock = Clock::get()?;
+    pool_state.check_public_buy(clock.unix_timestamp)?;
+
+    let base_bought = ctx.accounts.user_position.as_ref().map_or(0, |user_position| user_position.base_bought);
+    let base_amount = trade_quote.output_amount + trade_quote.transfer_fee; // Recorded like buy_finalize does
+    main_state.check_buy_limit(pool_state.in_anti_snipe_window(main_state, &clock), base_bought + base_amount)?;
+    Ok(trade_quote)
+}
+
+// This function simulates buy_tokens_from_exact_quote without moving funds
+// Params
+//   ctx - Quote context
+//   quote_amount - Amount of quote tokens to buy base tokens with
+// Return
+//   TradeQuote on success (passed via return data), ErrorCode on failure
+pub fn quote_buy_exact_quote(ctx: Context<AQuote>, quote_amount: u64) -> Result<TradeQuote> {
+    let trade_quote = compute_buy_tokens_from_exact_quote(&ctx.accounts.main_state, &ctx.accounts.pool_state, &ctx.accounts.base_mint.to_account_info(), quote_amount)?;
+    check_quoted_buy(&ctx, trade_quote)
+}
+
+// This function simulates buy_exact_tokens_from_quote without moving funds
+// Params
+//   ctx - Quote context
+//   base_amount - Amount of base tokens to buy
+// Return
+//   TradeQuote on success (passed via return data), ErrorCode on failure
+pub fn quote_buy_exact_base(ctx: Context<AQuote>, base_amount: u64) -> Result<TradeQuote> {
+    let trade_quote = compute_buy_exact_tokens_from_quote(&ctx.accounts.main_state, &ctx.accounts.pool_state, &ctx.accounts.base_mint.to_account_info(), base_amount)?;
+    check_quoted_buy(&ctx, trade_quote)
+}
+
+// This function simulates sell without moving funds
+// Params
+//   ctx - Quote context
+//   base_amount - Amount of base tokens to sell
+// Return
+//   TradeQuote on success (passed via return data), ErrorCode on failure
+pub fn quote_sell(ctx: Context<AQuote>, base_amount: u64) -> Result<TradeQuote> {
+    compute_sell(&ctx.accounts.main_state, &ctx.accounts.pool_state, &ctx.accounts.base_mint.to_account_info(), base_amount)
+}
+
+// This function observes cumulative price of the pool at current time
+//   Consumers store observations and compute TWAP between them with PriceObservation::twap
+// Params
+//   ctx - Quote context
+// Return
+//   PriceObservation on success (passed via return data), ErrorCode on failure
+pub fn observe_price(ctx: Context<AQuote>) -> Result<PriceObservation> {
+    ctx.accounts.pool_state.observe_price(Clock::get()?.unix_timestamp)
+}
+
+// Quote context
+#[derive(Accounts)]
+pub struct AQuote<'info> {
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Box<Account<'info, MainState>>, // MainState account
+    #[account(
+        seeds = [
+            PoolState::PREFIX_SEED,
+            pool_state.base_mint.as_ref(),
+            pool_state.quote_mint.as_ref(),
+        ],
+        bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>, // PoolState account
+    #[account(address = pool_state.base_mint)]
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>, // Base token account (for transfer fee)
+    #[account(constraint = user_position.pool == pool_state.key() @ PumpFunError::InvalidUserPosition)]
+    pub user_position: Option<Box<Account<'info, UserPosition>>>, // Buyer's UserPosition account (for buy limit of anti-snipe window, optional)
+}
@@ -0,0 +1,123 @@
+use crate::{
+    error::PumpFunError,
+    PoolState,
+    UnsoldReclaimed,
+    utils::{create_associated_token_account, get_withheld_transfer_fee},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token::Mint,
+    token_interface::{self, CloseAccount, TokenInterface, TransferChecked},
+};
+
+// This function is called by creator to reclaim unsold base tokens once the pool is in refund state
+//   PoolState's base token ATA is closed and its rent goes to creator (unless it holds withheld Token-2022 transfer fees)
+//   real_base_reserves is kept unchanged so that refunds stay pro-rata
+// Params
+//   ctx - ReclaimUnsold context
+// Return
+//   Ok on success, ErrorCode on failure
+//     UnsoldReclaimed is emitted on success
+pub fn reclaim_unsold(ctx: Context<AReclaimUnsold>) -> Result<()> {
+    let pool_state = &ctx.accounts.pool_state;
+    let timestamp = Clock::get()?.unix_timestamp;
+    require!(pool_state.in_refund(timestamp), PumpFunError::RefundNotActive);
+
+    let base_amount = ctx.accounts.reserver_base_ata.amount;
+    require!(base_amount.gt(&0), PumpFunError::BondingCurveAlreadyWithdrawn);
+
+    let creator = ctx.accounts.creator.to_account_info();
+    let creator_base_ata = ctx.accounts.creator_base_ata.to_account_info();
+    let base_token_program = ctx.accounts.base_token_program.to_account_info();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        PoolState::PREFIX_SEED,
+        pool_state.base_mint.as_ref(),
+        pool_state.quote_mint.as_ref(),
+        &[ctx.bumps["pool_state"]]
+    ]];
+
+    // Create creator's base token ATA if not exists
+    create_associated_token_account(
+        creator.clone(),
+        creator_base_ata.clone(),
+        creator.clone(),
+        ctx.accounts.base_mint.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        base_token_program.clone(),
+        ctx.accounts.associated_token_program.to_account_info(),
+    )?;
+
+    // Transfer unsold base tokens from pool to creator
+    let reclaim_transfer_cpi_accounts = TransferChecked {
+        from: ctx.accounts.reserver_base_ata.to_account_info(),
+        mint: ctx.accounts.base_mint.to_account_info(),
+        to: creator_base_ata,
+        authority: pool_state.to_account_info()
+    };
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(base_token_program.clone(), reclaim_transfer_cpi_accounts, signer_seeds),
+        base_amount,
+        ctx.accounts.base_mint.decimals,
+    )?;
+
+    // Close PoolState's base token ATA, creator receives rent
+    if get_withheld_transfer_fee(&ctx.accounts.reserver_base_ata.to_account_info())?.eq(&0) {
+        let close_cpi_accounts = CloseAccount {
+            account: ctx.accounts.reserver_base_ata.to_account_info(),
+            destination: creator.clone(),
+            authority: pool_state.to_account_info(),
+        };
+        token_interface::close_account(CpiContext::new_with_signer(base_token_program, close_cpi_accounts, signer_seeds))?;
+    }
+
+    emit!(UnsoldReclaimed {
+        creator: creator.key(),
+        base_mint: pool_state.base_mint,
+        base_amount,
+        timestamp
+    });
+
+    Ok(())
+}
+
+// ReclaimUnsold context
+#[derive(Accounts)]
+pub struct AReclaimUnsold<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>, // Pool creator
+
+    #[account(
+        seeds = [
+            PoolState::PREFIX_SEED,
+            base_mint.key().as_ref(),
+            quote_mint.key().as_ref(),
+        ],
+        bump,
+        constraint = pool_state.owner == creator.key() @ PumpFunError::Unauthorised
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>, // PoolState account
+
+    #[account(address = pool_state.base_mint)]
+    pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>, // Base token account (SPL Token or Token-2022)
+    #[account(address = pool_state.quote_mint)]
+    pub quote_mint: Box<Account<'info, Mint>>, // Quote token account
+
+    #[account(
+        mut,
+        associated_token::mint = base_mint,
+        associated_token::authority = pool_state,
+        associated_token::token_program = base_token_program,
+    )]
+    pub reserver_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>, // PoolState's base token ATA
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&creator.key(), &base_mint.key(), &base_token_program.key())
+    )]
+    /// CHECK: created on reclaim if not exists
+    pub creator_base_ata: UncheckedAccount<'info>, // Creator's base token ATA
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub base_token_program: Interface<'info, TokenInterface>, // Token program of base token (SPL Token or Token-2022)
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,132 @@
+use crate::{
+    error::PumpFunError,
+    MainState, PoolState,
+    RefundEvent,
+    utils::{check_balance, close_token_account, is_native_mint},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount, Transfer},
+    token_interface::{self, Burn, TokenInterface},
+};
+
+// This function returns base tokens for pro-rata share of real_quote_reserves, once the pool is in refund state
+//   Returned base tokens are burnt
+// Params
+//   ctx - Refund context
+//   base_amount - Amount of base tokens to return
+// Return
+//   Ok on success, ErrorCode on failure
+//     RefundEvent is emitted on success
+pub fn refund(ctx: Context<ARefund>, base_amount: u64) -> Result<()> {
+    let main_state = &ctx.accounts.main_state;
+    let pool_state = &mut ctx.accounts.pool_state;
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    require!(main_state.paused.eq(&false) && pool_state.paused.eq(&false), PumpFunError::Paused); // Neither program nor pool must be paused
+    require!(pool_state.in_refund(timestamp), PumpFunError::RefundNotActive);
+    require!(check_balance(&ctx.accounts.holder_base_ata, base_amount), PumpFunError::InsufficientFund);
+
+    let quote_amount = pool_state.compute_refund_amount(base_amount)?;
+    pool_state.real_quote_reserves -= quote_amount;
+    pool_state.refunded_base_amount += base_amount;
+
+    let holder = ctx.accounts.holder.to_account_info();
+    let holder_quote_ata = ctx.accounts.holder_quote_ata.to_account_info();
+    let token_program = ctx.accounts.token_program.to_account_info();
+
+    // Burn base tokens returned by holder
+    let burn_cpi_accounts = Burn {
+        mint: ctx.accounts.base_mint.to_account_info(),
+        from: ctx.accounts.holder_base_ata.to_account_info(),
+        authority: holder.clone(),
+    };
+    token_interface::burn(CpiContext::new(ctx.accounts.base_token_program.to_account_info(), burn_cpi_accounts), base_amount)?;
+
+    // Transfer refunded quote tokens from pool to holder
+    let refund_transfer_cpi_accounts = Transfer {
+        from: ctx.accounts.reserver_quote_ata.to_account_info(),
+        to: holder_quote_ata.clone(),
+        authority: pool_state.to_account_info()
+    };
+    token::transfer(CpiContext::new_with_signer(token_program.clone(), refund_transfer_cpi_accounts, &[&[
+        PoolState::PREFIX_SEED,
+        pool_state.base_mint.as_ref(),
+        pool_state.quote_mint.as_ref(),
+        &[ctx.bumps["pool_state"]]
+    ]]), quote_amount)?;
+
+    // Unwrap WSOL (close holder's WSOL account unless it held WSOL before), holder receives lamports
+    if is_native_mint(&pool_state.quote_mint) {
+        close_token_account(holder.clone(), &ctx.accounts.holder_quote_ata, token_program)?;
+    }
+
+    emit!(RefundEvent {
+        user: holder.key(),
+        base_mint: pool_state.base_mint,
+        base_amount,
+        quote_amount,
+        timestamp
+    });
+
+    Ok(())
+}
+
+// Refund context
+#[derive(Accounts)]
+pub struct ARefund<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>, // Base token holder
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Box<Account<'info, MainState>>, // MainState account
+
+    #[account(
+        mut,
+        seeds = [
+            PoolState::PREFIX_SEED,
+            base_mint.key().as_ref(),
+            quote_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>, // PoolState account
+
+    #[account(
+        mut,
+        address = pool_state.base_mint
+    )]
+    pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>, // Base token account (SPL Token or Token-2022)
+    #[account(address = pool_state.quote_mint)]
+    pub quote_mint: Box<Account<'info, Mint>>, // Quote token account
+
+    #[account(
+        mut,
+        token::mint = base_mint,
+        token::authority = holder,
+        token::token_program = base_token_program,
+    )]
+    pub holder_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>, // Holder's base token account
+    #[account(
+        init_if_needed,
+        payer = holder,
+        associated_token::mint = quote_mint,
+        associated_token::authority = holder,
+    )]
+    pub holder_quote_ata: Box<Account<'info, TokenAccount>>, // Holder's quote token ATA
+
+    #[account(
+        mut,
+        associated_token::mint = quote_mint,
+        associated_token::authority = pool_state,
+    )]
+    pub reserver_quote_ata: Box<Account<'info, TokenAccount>>, // PoolState's quote token ATA
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub base_token_program: Interface<'info, TokenInterface>, // Token program of base token (SPL Token or Token-2022)
+    pub system_program: Program<'info, System>,
+}
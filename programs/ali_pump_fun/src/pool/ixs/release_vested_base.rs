@@ -0,0 +1,102 @@
+use crate::{
+    error::PumpFunError,
+    BaseVesting,
+    BaseVestingReleased,
+    utils::create_associated_token_account,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token_interface::{self, TokenInterface, TransferChecked},
+};
+
+// This function releases vested part of locked unsold base tokens to beneficiary
+// Params
+//   ctx - ReleaseVestedBase context
+// Return
+//   Ok on success, ErrorCode on failure
+//     BaseVestingReleased is emitted on success
+pub fn release_vested_base(ctx: Context<AReleaseVestedBase>) -> Result<()> {
+    let base_vesting = &mut ctx.accounts.base_vesting;
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    let base_amount = base_vesting.releasable_amount(timestamp);
+    require!(base_amount.gt(&0), PumpFunError::NothingToRelease);
+    base_vesting.released_amount += base_amount;
+
+    let base_token_program = ctx.accounts.base_token_program.to_account_info();
+
+    // Create beneficiary's base token ATA if not exists
+    create_associated_token_account(
+        ctx.accounts.beneficiary.to_account_info(),
+        ctx.accounts.beneficiary_base_ata.to_account_info(),
+        ctx.accounts.beneficiary.to_account_info(),
+        ctx.accounts.base_mint.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        base_token_program.clone(),
+        ctx.accounts.associated_token_program.to_account_info(),
+    )?;
+
+    // Transfer released base tokens from BaseVesting to beneficiary
+    let release_transfer_cpi_accounts = TransferChecked {
+        from: ctx.accounts.vesting_base_ata.to_account_info(),
+        mint: ctx.accounts.base_mint.to_account_info(),
+        to: ctx.accounts.beneficiary_base_ata.to_account_info(),
+        authority: base_vesting.to_account_info()
+    };
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(base_token_program, release_transfer_cpi_accounts, &[&[
+            BaseVesting::PREFIX_SEED,
+            base_vesting.pool.as_ref(),
+            &[ctx.bumps["base_vesting"]]
+        ]]),
+        base_amount,
+        ctx.accounts.base_mint.decimals
+    )?;
+
+    emit!(BaseVestingReleased {
+        beneficiary: ctx.accounts.beneficiary.key(),
+        base_mint: ctx.accounts.base_mint.key(),
+        base_amount,
+        released_amount: base_vesting.released_amount,
+        timestamp
+    });
+
+    Ok(())
+}
+
+// ReleaseVestedBase context
+#[derive(Accounts)]
+pub struct AReleaseVestedBase<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>, // BaseVesting beneficiary
+
+    #[account(
+        mut,
+        seeds = [BaseVesting::PREFIX_SEED, base_vesting.pool.as_ref()],
+        bump,
+        has_one = beneficiary @ PumpFunError::Unauthorised,
+        has_one = base_mint,
+    )]
+    pub base_vesting: Box<Account<'info, BaseVesting>>, // Pool's BaseVesting account (works after the pool is closed)
+
+    pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>, // Base token account (SPL Token or Token-2022)
+
+    #[account(
+        mut,
+        token::mint = base_mint,
+        token::authority = base_vesting,
+        token::token_program = base_token_program,
+    )]
+    pub vesting_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>, // BaseVesting's base token ATA
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&beneficiary.key(), &base_mint.key(), &base_token_program.key())
+    )]
+    /// CHECK: created on release if not exists
+    pub beneficiary_base_ata: UncheckedAccount<'info>, // Beneficiary's base token ATA
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub base_token_program: Interface<'info, TokenInterface>, // Token program of base token (SPL Token or Token-2022)
+    pub system_program: Program<'info, System>,
+}
@@ -1,55 +1,53 @@
 use anchor_lang::prelude::*;
-use anchor_spl::{associated_token::AssociatedToken, token::{self, Mint, Token, TokenAccount, Transfer}};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount, Transfer},
+    token_interface::{self, TokenInterface, TransferChecked},
+};
 use crate::{
-    MainState, PoolState, 
+    MainState, PoolState, PoolObservations, TradeQuote, ReferrerStats, UserPosition, 
     TradeEvent, 
     error::PumpFunError, 
     main_state, 
-    utils::{calculate_trading_fee, close_token_account, sync_native_amount, check_balance}, 
+    utils::{
+        calculate_trading_fee, calculate_gross_amount, split_trading_fee, close_token_account, sync_native_amount, check_balance, is_native_mint,
+        get_transfer_fee, calculate_transfer_fee, calculate_pre_transfer_fee_amount,
+    }, 
 };
 
-// This function sells base tokens on the bonding curve
+// Internal sell function
 // Params
 //   ctx - Sell context
-//   base_amount - Amount of base tokens to sell
-//   min_quote_amount - Minimum amount of quote token to receive
+//   base_amount - Amount of base tokens to sell (sent by seller, transfer fee included)
+//   fee - Trading fee
+//   output_amount - Amount of quote tokens to send to the seller (fee excluded)
 // Return
 //   Ok on success, ErrorCode on failure
-pub fn sell(ctx:Context<ASell>, base_amount: u64, min_quote_amount: u64) -> Result<()> {
-    // base_amount must be greater than 0 and less than real_base_reserves
-    require!(base_amount.gt(&0), PumpFunError::WrongBaseAmount);
-    
-    let main_state = &mut ctx.accounts.main_state;
-
-    let pool_state = &mut ctx.accounts.pool_state;
-    require!(pool_state.complete.eq(&false), PumpFunError::BondingCurveComplete); // BondingCurve must not be complete
+//     If successful, emits (Sell) TradeEvent
+fn sell_finalize(ctx: Context<ASell>, base_amount: u64, fee: u64, output_amount: u64) -> Result<()> {
+    require!(check_balance(&ctx.accounts.seller_base_ata, base_amount), PumpFunError::InsufficientFund);
 
+    let pool_state = &ctx.accounts.pool_state;
     let seller = ctx.accounts.seller.to_account_info();
     let seller_base_ata = &ctx.accounts.seller_base_ata;
     let seller_quote_ata = &ctx.accounts.seller_quote_ata;
     let fee_quote_ata = &ctx.accounts.fee_quote_ata;
     let token_program = ctx.accounts.token_program.to_account_info();
-    
-    let input_base_amount = base_amount;
-    let _output_amount = pool_state.compute_receivable_amount_on_sell(input_base_amount);
-    
-    let fee = calculate_trading_fee(main_state.trading_fee, _output_amount);
-    let output_amount = _output_amount - fee;
-
-    require!(output_amount >= min_quote_amount, PumpFunError::TooLowOutputQuote);
-
-    pool_state.real_base_reserves += input_base_amount; // Increase Real Base
-    pool_state.real_quote_reserves -= _output_amount; // Decrease Real Quote
 
     // Transfer base tokens from seller to pool
-    let input_amount_transfer_cpi_account = Transfer {
+    let input_amount_transfer_cpi_account = TransferChecked {
         from: seller_base_ata.to_account_info(),
+        mint: ctx.accounts.base_mint.to_account_info(),
         to: ctx.accounts.reserver_base_ata.to_account_info(),
         authority: seller.clone()
     };
-    token::transfer(CpiContext::new(token_program.clone(), input_amount_transfer_cpi_account), input_base_amount)?;
+    token_interface::transfer_checked(CpiContext::new(ctx.accounts.base_token_program.to_account_info(), input_amount_transfer_cpi_account), base_amount, ctx.accounts.base_mint.decimals)?;
     
-    // Transfer fee (quote) from pool to feeRecipient
+    let main_state = &ctx.accounts.main_state;
+    let referral_fee_share = if ctx.accounts.referrer.is_some() { main_state.referral_fee_share } else { 0 };
+    let (protocol_fee, creator_fee, referral_fee) = split_trading_fee(main_state.creator_fee_share, referral_fee_share, fee);
+
+    // Transfer protocol fee (quote) from pool to feeRecipient
     let fee_transfer_cpi_account = Transfer {
         from: ctx.accounts.reserver_quote_ata.to_account_info(),
         to: fee_quote_ata.to_account_info(),
@@ -60,7 +58,52 @@ pub fn sell(ctx:Context<ASell>, base_amount: u64, min_quote_amount: u64) -> Resu
         pool_state.base_mint.as_ref(),
         pool_state.quote_mint.as_ref(),
         &[ctx.bumps["pool_state"]]
-    ]]), fee)?;
+    ]]), protocol_fee)?;
+
+    // Transfer creator fee (quote) from pool to creator's fee vault
+    if creator_fee > 0 {
+        let creator_fee_transfer_cpi_account = Transfer {
+            from: ctx.accounts.reserver_quote_ata.to_account_info(),
+            to: ctx.accounts.creator_fee_vault.to_account_info(),
+            authority: pool_state.to_account_info()
+        };
+        token::transfer(CpiContext::new_with_signer(token_program.clone(), creator_fee_transfer_cpi_account, &[&[
+            PoolState::PREFIX_SEED,
+            pool_state.base_mint.as_ref(),
+            pool_state.quote_mint.as_ref(),
+            &[ctx.bumps["pool_state"]]
+        ]]), creator_fee)?;
+    }
+
+    // Transfer referral fee (quote) from pool to referrer and record referrer stats
+    let referrer = ctx.accounts.referrer.as_ref().map(|referrer| referrer.key());
+    require!(
+        referrer.is_some() || (ctx.accounts.referrer_quote_ata.is_none() && ctx.accounts.referrer_stats.is_none()),
+        PumpFunError::MissingReferrerAccounts
+    ); // Referrer accounts are only passed together with referrer
+    if let Some(referrer) = referrer {
+        let (Some(referrer_quote_ata), Some(referrer_stats)) = (&ctx.accounts.referrer_quote_ata, &mut ctx.accounts.referrer_stats) else {
+            return Err(PumpFunError::MissingReferrerAccounts.into());
+        };
+        if referral_fee > 0 {
+            let referral_fee_transfer_cpi_account = Transfer {
+                from: ctx.accounts.reserver_quote_ata.to_account_info(),
+                to: referrer_quote_ata.to_account_info(),
+                authority: pool_state.to_account_info()
+            };
+            token::transfer(CpiContext::new_with_signer(token_program.clone(), referral_fee_transfer_cpi_account, &[&[
+                PoolState::PREFIX_SEED,
+                pool_state.base_mint.as_ref(),
+                pool_state.quote_mint.as_ref(),
+                &[ctx.bumps["pool_state"]]
+            ]]), referral_fee)?;
+        }
+
+        referrer_stats.referrer = referrer;
+        referrer_stats.volume += output_amount + fee;
+        referrer_stats.earnings += referral_fee;
+        referrer_stats.trade_count += 1;
+    }
 
     // Transfer output_amount (quote) from pool to seller
     let output_amount_transfer_cpi_account = Transfer {
@@ -75,8 +118,23 @@ pub fn sell(ctx:Context<ASell>, base_amount: u64, min_quote_amount: u64) -> Resu
         &[ctx.bumps["pool_state"]]
     ]]), output_amount)?;
 
-    // // Unwrap WSOL (or close token account)
-    // close_token_account(seller.clone(), seller_quote_ata.to_account_info(), token_program)?;
+    // Unwrap WSOL (close seller's WSOL account unless it held WSOL before), seller receives lamports
+    if is_native_mint(&pool_state.quote_mint) {
+        close_token_account(seller.clone(), seller_quote_ata, token_program.clone())?;
+    }
+
+    // Record trade statistics in seller's position
+    let timestamp = Clock::get()?.unix_timestamp;
+    if let Some(user_position) = &mut ctx.accounts.user_position {
+        if main_state.track_positions {
+            user_position.pool = pool_state.key();
+            user_position.user = seller.key();
+            user_position.record_sell(base_amount, output_amount, fee, timestamp);
+        }
+    }
+
+    // Record price history sample
+    pool_state.record_observation(ctx.accounts.pool_observations.as_ref(), timestamp, output_amount + fee)?;
 
     // Emit (Sell) TradeEvent
     emit!(TradeEvent {
@@ -88,15 +146,125 @@ pub fn sell(ctx:Context<ASell>, base_amount: u64, min_quote_amount: u64) -> Resu
         base_reserves: pool_state.real_base_reserves + pool_state.virt_base_reserves, 
         quote_reserves: pool_state.virt_quote_reserves + pool_state.real_quote_reserves, 
         is_buy: false, 
-        timestamp: Clock::get()?.unix_timestamp,
+        referrer, 
+        timestamp,
     });
 
     Ok(())
 }
 
+// This function calculates selling base tokens, without moving funds
+// Params
+//   main_state - MainState account
+//   pool_state - PoolState account
+//   base_mint - Base token mint (transfer fee is withheld from input if Token-2022 mint has it)
+//   base_amount - Amount of base tokens to sell
+// Return
+//   TradeQuote on success, ErrorCode on failure
+pub fn compute_sell(main_state: &MainState, pool_state: &PoolState, base_mint: &AccountInfo, base_amount: u64) -> Result<TradeQuote> {
+    // base_amount must be greater than 0 and less than real_base_reserves
+    require!(base_amount.gt(&0), PumpFunError::WrongBaseAmount);
+    pool_state.check_tradable(main_state, Clock::get()?.unix_timestamp)?; // Pool must be open for trading
+
+    let transfer_fee = calculate_transfer_fee(&get_transfer_fee(base_mint)?, base_amount);
+    let input_base_amount = base_amount - transfer_fee; // Base tokens received by pool
+    let _output_amount = pool_state.compute_receivable_amount_on_sell(input_base_amount)?;
+    
+    let fee = calculate_trading_fee(pool_state.effective_trading_fee(main_state), _output_amount);
+    let output_amount = _output_amount - fee;
+
+    Ok(TradeQuote {
+        input_amount: base_amount,
+        output_amount,
+        fee,
+        transfer_fee,
+        real_base_reserves: pool_state.real_base_reserves + input_base_amount, // Increase Real Base
+        real_quote_reserves: pool_state.real_quote_reserves - _output_amount, // Decrease Real Quote
+        complete: false,
+    })
+}
+
+// This function calculates selling base tokens for specified amount of quote, without moving funds
+//   Required base token amount is rounded up, any surplus of the curve output stays in the pool
+// Params
+//   main_state - MainState account
+//   pool_state - PoolState account
+//   base_mint - Base token mint (seller sends more to cover transfer fee if Token-2022 mint has it)
+//   quote_amount - Amount of quote tokens to receive (fee excluded)
+// Return
+//   TradeQuote on success, ErrorCode on failure
+pub fn compute_sell_tokens_for_exact_quote(main_state: &MainState, pool_state: &PoolState, base_mint: &AccountInfo, quote_amount: u64) -> Result<TradeQuote> {
+    require!(quote_amount.gt(&0), PumpFunError::WrongQuoteAmount); // quote_amount must be greater than 0
+    pool_state.check_tradable(main_state, Clock::get()?.unix_timestamp)?; // Pool must be open for trading
+
+    // Quote amount to take from the curve so that quote_amount is left after fee
+    let required_output_amount = calculate_gross_amount(pool_state.effective_trading_fee(main_state), quote_amount);
+    require!(required_output_amount <= pool_state.real_quote_reserves, PumpFunError::TooMuchOutputQuote);
+
+    let input_base_amount = pool_state
+        .compute_required_amount_on_sell(required_output_amount)?
+        .ok_or(PumpFunError::TooMuchOutputQuote)?;
+    require!(input_base_amount.gt(&0), PumpFunError::WrongBaseAmount);
+
+    let _output_amount = pool_state.compute_receivable_amount_on_sell(input_base_amount)?; // Not less than required_output_amount
+    let fee = calculate_trading_fee(pool_state.effective_trading_fee(main_state), _output_amount);
+
+    // Base tokens sent by seller so that pool receives input_base_amount after transfer fee
+    let base_transfer_fee = get_transfer_fee(base_mint)?;
+    let base_amount = calculate_pre_transfer_fee_amount(&base_transfer_fee, input_base_amount);
+    let transfer_fee = calculate_transfer_fee(&base_transfer_fee, base_amount);
+
+    Ok(TradeQuote {
+        input_amount: base_amount,
+        output_amount: quote_amount,
+        fee,
+        transfer_fee,
+        real_base_reserves: pool_state.real_base_reserves + (base_amount - transfer_fee), // Increase Real Base
+        real_quote_reserves: pool_state.real_quote_reserves - (quote_amount + fee), // Decrease Real Quote
+        complete: false,
+    })
+}
+
+// This function sells base tokens on the bonding curve
+// Params
+//   ctx - Sell context
+//   base_amount - Amount of base tokens to sell
+//   min_quote_amount - Minimum amount of quote token to receive
+// Return
+//   Ok on success, ErrorCode on failure
+pub fn sell(ctx:Context<ASell>, base_amount: u64, min_quote_amount: u64) -> Result<()> {
+    let trade_quote = compute_sell(&ctx.accounts.main_state, &ctx.accounts.pool_state, &ctx.accounts.base_mint.to_account_info(), base_amount)?;
+    require!(trade_quote.output_amount >= min_quote_amount, PumpFunError::TooLowOutputQuote);
+
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.update_price_cumulative(Clock::get()?.unix_timestamp)?; // Accumulate price held before this trade
+    pool_state.real_base_reserves = trade_quote.real_base_reserves;
+    pool_state.real_quote_reserves = trade_quote.real_quote_reserves;
+
+    sell_finalize(ctx, trade_quote.input_amount, trade_quote.fee, trade_quote.output_amount)
+}
+
+// This function sells base tokens on the bonding curve for specified amount of quote (required base token amount is calculated internally)
+// Params
+//   ctx - Sell context
+//   quote_amount - Amount of quote tokens to receive (fee excluded)
+//   max_base_amount - Maximum amount of base tokens allowed to sell
+// Return
+//   Ok on success, ErrorCode on failure
+pub fn sell_tokens_for_exact_quote(ctx:Context<ASell>, quote_amount: u64, max_base_amount: u64) -> Result<()> {
+    let trade_quote = compute_sell_tokens_for_exact_quote(&ctx.accounts.main_state, &ctx.accounts.pool_state, &ctx.accounts.base_mint.to_account_info(), quote_amount)?;
+    require!(trade_quote.input_amount <= max_base_amount, PumpFunError::TooMuchInputBase); // Check maximum amount
+
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.update_price_cumulative(Clock::get()?.unix_timestamp)?; // Accumulate price held before this trade
+    pool_state.real_base_reserves = trade_quote.real_base_reserves;
+    pool_state.real_quote_reserves = trade_quote.real_quote_reserves;
+
+    sell_finalize(ctx, trade_quote.input_amount, trade_quote.fee, trade_quote.output_amount)
+}
+
 // Sell context
 #[derive(Accounts)]
-#[instruction(base_amount: u64)]
 pub struct ASell<'info> {
     #[account(mut)]
     pub seller: Signer<'info>, // Seller
@@ -128,19 +296,27 @@ pub struct ASell<'info> {
         bump,
     )]
     pub pool_state: Box<Account<'info, PoolState>>, // PoolState account
+    #[account(
+        mut,
+        seeds = [PoolState::CREATOR_FEE_VAULT_SEED, pool_state.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = pool_state,
+    )]
+    pub creator_fee_vault: Box<Account<'info, TokenAccount>>, // Pool creator's fee vault
 
     #[account(address = pool_state.base_mint)]
-    pub base_mint: Box<Account<'info, Mint>>, // Base token account
+    pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>, // Base token account (SPL Token or Token-2022)
     #[account(address = pool_state.quote_mint)]
     pub quote_mint: Box<Account<'info, Mint>>, // Quote token account
     
     #[account(
         mut,
-        associated_token::mint = base_mint,
-        associated_token::authority = seller,
-        constraint = check_balance(seller_base_ata.as_ref(), base_amount) @ PumpFunError::InsufficientFund
+        token::mint = base_mint,
+        token::authority = seller,
+        token::token_program = base_token_program,
     )]
-    pub seller_base_ata: Box<Account<'info, TokenAccount>>, // Seller's base token ATA
+    pub seller_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>, // Seller's base token account
     #[account(
         init_if_needed,
         payer = seller,
@@ -153,8 +329,9 @@ pub struct ASell<'info> {
         mut,
         associated_token::mint = base_mint,
         associated_token::authority = pool_state,
+        associated_token::token_program = base_token_program,
     )]
-    pub reserver_base_ata: Box<Account<'info, TokenAccount>>, // PoolState's base token ATA
+    pub reserver_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>, // PoolState's base token ATA
     #[account(
         mut,
         associated_token::mint = quote_mint,
@@ -162,7 +339,40 @@ pub struct ASell<'info> {
     )]
     pub reserver_quote_ata: Box<Account<'info, TokenAccount>>, // PoolState's quote token ATA
 
+    #[account(constraint = referrer.key() != seller.key() @ PumpFunError::InvalidReferrer)]
+    /// CHECK: any wallet except the trader can refer
+    pub referrer: Option<AccountInfo<'info>>, // Referrer (optional)
+    #[account(
+        mut,
+        associated_token::mint = quote_mint,
+        associated_token::authority = referrer,
+    )]
+    pub referrer_quote_ata: Option<Box<Account<'info, TokenAccount>>>, // Referrer's quote token ATA (required with referrer)
+    #[account(
+        init_if_needed,
+        payer = seller,
+        seeds = [ReferrerStats::PREFIX_SEED, referrer.as_ref().map_or(&[][..], |referrer| referrer.key.as_ref())], // Rejected in handler without referrer
+        bump,
+        space = 8 + ReferrerStats::MAX_SIZE
+    )]
+    pub referrer_stats: Option<Box<Account<'info, ReferrerStats>>>, // Referrer's ReferrerStats account (required with referrer)
+    #[account(
+        init_if_needed,
+        payer = seller,
+        seeds = [UserPosition::PREFIX_SEED, pool_state.key().as_ref(), seller.key().as_ref()],
+        bump,
+        space = 8 + UserPosition::MAX_SIZE
+    )]
+    pub user_position: Option<Box<Account<'info, UserPosition>>>, // Seller's UserPosition account (optional)
+    #[account(
+        mut,
+        seeds = [PoolObservations::PREFIX_SEED, pool_state.key().as_ref()],
+        bump,
+    )]
+    pub pool_observations: Option<AccountLoader<'info, PoolObservations>>, // Pool's PoolObservations account (required if enabled on the pool)
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Program<'info, Token>,
+    pub base_token_program: Interface<'info, TokenInterface>, // Token program of base token (SPL Token or Token-2022)
     pub system_program: Program<'info, System>,
 }
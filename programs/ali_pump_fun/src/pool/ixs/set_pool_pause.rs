@@ -0,0 +1,54 @@
+use crate::{
+    error::PumpFunError,
+    MainState, PoolState,
+    Paused, Unpaused,
+};
+use anchor_lang::prelude::*;
+
+// This function pauses/unpauses a single pool
+// Params
+//   ctx - SetPoolPause context
+//   paused - true to pause, false to unpause
+// Return
+//   Ok on success, ErrorCode on failure
+//     Paused or Unpaused is emitted on success
+pub fn set_pool_pause(ctx: Context<ASetPoolPause>, paused: bool) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state;
+    require!(pool_state.paused.ne(&paused), PumpFunError::PauseStateUnchanged);
+
+    pool_state.paused = paused;
+
+    let pauser = ctx.accounts.pauser.key();
+    let pool = Some(pool_state.key());
+    let timestamp = Clock::get()?.unix_timestamp;
+    if paused {
+        emit!(Paused { pauser, pool, timestamp });
+    } else {
+        emit!(Unpaused { pauser, pool, timestamp });
+    }
+
+    Ok(())
+}
+
+// SetPoolPause context - passed with accounts
+#[derive(Accounts)]
+pub struct ASetPoolPause<'info> {
+    pub pauser: Signer<'info>, // Current pauser
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+        has_one = pauser,
+    )]
+    pub main_state: Box<Account<'info, MainState>>, // MainState account
+
+    #[account(
+        mut,
+        seeds = [
+            PoolState::PREFIX_SEED,
+            pool_state.base_mint.as_ref(),
+            pool_state.quote_mint.as_ref(),
+        ],
+        bump,
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>, // PoolState account
+}
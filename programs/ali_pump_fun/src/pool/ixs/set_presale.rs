@@ -0,0 +1,62 @@
+use crate::{
+    error::PumpFunError,
+    MainState, PoolState,
+    PresaleSet,
+};
+use anchor_lang::prelude::*;
+
+// This function sets allowlisted presale phase of a pool
+// Params
+//   ctx - SetPresale context
+//   merkle_root - Merkle root of allowlisted (wallet, allocation) leaves
+//   presale_end_ts - Presale end time, public curve opens afterwards
+//     (fails with ScheduledStartWithSlotWindow while anti-snipe window is in slots)
+// Return
+//   Ok on success, ErrorCode on failure
+//     PresaleSet is emitted on success
+pub fn set_presale(ctx: Context<ASetPresale>, merkle_root: [u8; 32], presale_end_ts: i64) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state;
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    // Presale can be set only once, before any token is sold
+    require!(pool_state.presale_end_ts.eq(&0), PumpFunError::InvalidPresale);
+    require!(pool_state.real_base_reserves.eq(&pool_state.initial_base_reserves), PumpFunError::InvalidPresale);
+    require!(merkle_root.ne(&[0; 32]) && presale_end_ts.gt(&timestamp), PumpFunError::InvalidPresale);
+    require!(ctx.accounts.main_state.allows_scheduled_start(), PumpFunError::ScheduledStartWithSlotWindow); // Presale delays public trading
+
+    pool_state.presale_merkle_root = merkle_root;
+    pool_state.presale_end_ts = presale_end_ts;
+
+    emit!(PresaleSet {
+        creator: ctx.accounts.creator.key(),
+        base_mint: pool_state.base_mint,
+        merkle_root,
+        presale_end_ts,
+        timestamp
+    });
+
+    Ok(())
+}
+
+// SetPresale context - passed with accounts
+#[derive(Accounts)]
+pub struct ASetPresale<'info> {
+    pub creator: Signer<'info>, // Pool creator
+    #[account(
+        seeds = [MainState::PREFIX_SEED],
+        bump,
+    )]
+    pub main_state: Box<Account<'info, MainState>>, // MainState account
+
+    #[account(
+        mut,
+        seeds = [
+            PoolState::PREFIX_SEED,
+            pool_state.base_mint.as_ref(),
+            pool_state.quote_mint.as_ref(),
+        ],
+        bump,
+        constraint = pool_state.owner == creator.key() @ PumpFunError::Unauthorised
+    )]
+    pub pool_state: Box<Account<'info, PoolState>>, // PoolState account
+}
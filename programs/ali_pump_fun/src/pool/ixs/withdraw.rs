@@ -1,76 +1,189 @@
 use crate::{
-    constants::{QUOTE_MINT_STR},
     error::PumpFunError,
-    MainState, PoolState,
+    BaseVesting, MainState, PoolState, UnsoldBaseAction, WithdrawRecipient,
     WithdrawEvent,
-    utils::{close_token_account}
+    utils::{close_token_account, is_native_mint, create_associated_token_account}
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer}
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount, Transfer},
+    token_interface::{self, Burn, TokenInterface, TransferChecked},
 };
-use std::str::FromStr;
 
-// This function is called by withdrawer to withdraw all remaining base tokens and deposited quote tokens from the bonding curve
+// This function is called by withdrawer to withdraw base and quote tokens from the complete bonding curve
+//   Withdrawn quote tokens are split between treasury, creator and migration escrow by MainState.withdrawal_policy
+//   Withdrawn (unsold) base tokens are burnt, locked in BaseVesting or sent to migration escrow by PoolState.unsold_base_action
+//   Base and quote can be withdrawn separately and in multiple calls
 // Params
 //   ctx - Withdraw context
+//   base_amount - Amount of base tokens to withdraw (0 to skip)
+//   quote_amount - Amount of quote tokens to withdraw (0 to skip)
 // Return
 //   Ok on success, ErrorCode on failure
-pub fn withdraw(ctx: Context<AWithdraw>) -> Result<()> {
+//     WithdrawEvent is emitted for each transfer
+pub fn withdraw(ctx: Context<AWithdraw>, base_amount: u64, quote_amount: u64) -> Result<()> {
     let withdrawer = ctx.accounts.withdrawer.to_account_info();
     let main_state = &ctx.accounts.main_state;
     let pool_state = &mut ctx.accounts.pool_state;
 
+    require!(main_state.paused.eq(&false) && pool_state.paused.eq(&false), PumpFunError::Paused); // Neither program nor pool must be paused
 	require!(pool_state.complete.eq(&true), PumpFunError::BondingCurveIncomplete); // BondingCurve must be complete
-    require!(pool_state.real_base_reserves.gt(&0) && pool_state.real_quote_reserves.gt(&0), PumpFunError::BondingCurveAlreadyWithdrawn);
+    require!(
+        (base_amount.gt(&0) || quote_amount.gt(&0)) && base_amount.le(&pool_state.real_base_reserves) && quote_amount.le(&pool_state.real_quote_reserves),
+        PumpFunError::InvalidWithdrawAmount
+    );
 
-    let withdrawer_base_ata = ctx.accounts.withdrawer_base_ata.to_account_info();
-    let withdrawer_quote_ata = ctx.accounts.withdrawer_quote_ata.to_account_info();
-    let token_program = ctx.accounts.token_program.to_account_info();
+    pool_state.real_base_reserves -= base_amount;
+    pool_state.real_quote_reserves -= quote_amount;
 
-    // Transfer base tokens from pool to withdrawer
-    let pool_base_transfer_cpi_account = Transfer {
-        from: ctx.accounts.reserver_base_ata.to_account_info(),
-        to: withdrawer_base_ata.clone(),
-        authority: pool_state.to_account_info()
-    };
-    token::transfer(CpiContext::new_with_signer(token_program.clone(), pool_base_transfer_cpi_account, &[&[
-        PoolState::PREFIX_SEED,
-        pool_state.base_mint.as_ref(),
-        pool_state.quote_mint.as_ref(),
-        &[ctx.bumps["pool_state"]]
-    ]]), pool_state.real_base_reserves)?;
-
-    // Transfer quote tokens from pool to withdrawer
-    let pool_quote_transfer_cpi_account = Transfer {
-        from: ctx.accounts.reserver_quote_ata.to_account_info(),
-        to: withdrawer_quote_ata.clone(),
-        authority: pool_state.to_account_info()
-    };
-    token::transfer(CpiContext::new_with_signer(token_program.clone(), pool_quote_transfer_cpi_account, &[&[
+    let pool_state = &ctx.accounts.pool_state;
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let base_token_program = ctx.accounts.base_token_program.to_account_info();
+    let signer_seeds: &[&[&[u8]]] = &[&[
         PoolState::PREFIX_SEED,
         pool_state.base_mint.as_ref(),
         pool_state.quote_mint.as_ref(),
         &[ctx.bumps["pool_state"]]
-    ]]), pool_state.real_quote_reserves)?;
-
-    // // Unwrap WSOL (or close token account)
-    // close_token_account(withdrawer.clone(), withdrawer_quote_ata.to_account_info(), token_program)?;
-
-    // Emit WithdrawEvent
-    emit!(WithdrawEvent {
-        withdrawer: withdrawer.key(),
-        base_mint: pool_state.base_mint,
-        // quote_mint: pool_state.quote_mint,
-        base_amount: pool_state.real_base_reserves,
-        quote_amount: pool_state.real_quote_reserves,
-        timestamp: Clock::get()?.unix_timestamp
-    });
-
-    pool_state.real_base_reserves = 0;
-    pool_state.real_quote_reserves = 0;
-    
+    ]];
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    if base_amount > 0 {
+        let base_mint = ctx.accounts.base_mint.to_account_info();
+        let reserver_base_ata = ctx.accounts.reserver_base_ata.to_account_info();
+        let unsold_base_action = pool_state.unsold_base_action;
+
+        let (recipient, recipient_kind) = match unsold_base_action {
+            UnsoldBaseAction::Burn => {
+                // Burn unsold base tokens
+                let burn_cpi_accounts = Burn {
+                    mint: base_mint,
+                    from: reserver_base_ata,
+                    authority: pool_state.to_account_info()
+                };
+                token_interface::burn(CpiContext::new_with_signer(base_token_program.clone(), burn_cpi_accounts, signer_seeds), base_amount)?;
+
+                (Pubkey::default(), None)
+            }
+            UnsoldBaseAction::Lock => {
+                let (Some(base_vesting), Some(vesting_base_ata)) = (ctx.accounts.base_vesting.as_mut(), ctx.accounts.vesting_base_ata.as_ref()) else {
+                    return err!(PumpFunError::MissingVestingAccounts);
+                };
+                require!(
+                    vesting_base_ata.key().eq(&get_associated_token_address_with_program_id(&base_vesting.key(), &base_mint.key(), &base_token_program.key())),
+                    PumpFunError::MissingVestingAccounts
+                );
+
+                // Set up vesting on first lock, every lock restarts the schedule so that newly locked tokens never vest immediately
+                if base_vesting.start_ts.eq(&0) {
+                    base_vesting.pool = pool_state.key();
+                    base_vesting.base_mint = base_mint.key();
+                    base_vesting.beneficiary = main_state.withdrawal_policy.treasury;
+                }
+                base_vesting.lock(base_amount, timestamp, main_state.vesting_duration);
+
+                // Create BaseVesting's base token ATA if not exists
+                create_associated_token_account(
+                    withdrawer.clone(),
+                    vesting_base_ata.to_account_info(),
+                    base_vesting.to_account_info(),
+                    base_mint.clone(),
+                    ctx.accounts.system_program.to_account_info(),
+                    base_token_program.clone(),
+                    ctx.accounts.associated_token_program.to_account_info(),
+                )?;
+
+                // Lock unsold base tokens in BaseVesting
+                let pool_base_transfer_cpi_account = TransferChecked {
+                    from: reserver_base_ata,
+                    mint: base_mint,
+                    to: vesting_base_ata.to_account_info(),
+                    authority: pool_state.to_account_info()
+                };
+                token_interface::transfer_checked(
+                    CpiContext::new_with_signer(base_token_program.clone(), pool_base_transfer_cpi_account, signer_seeds),
+                    base_amount,
+                    ctx.accounts.base_mint.decimals
+                )?;
+
+                (vesting_base_ata.key(), None)
+            }
+            UnsoldBaseAction::Pair => {
+                // Create migration escrow's base token ATA if not exists
+                create_associated_token_account(
+                    withdrawer.clone(),
+                    ctx.accounts.migration_escrow_base_ata.to_account_info(),
+                    ctx.accounts.migration_escrow.to_account_info(),
+                    base_mint.clone(),
+                    ctx.accounts.system_program.to_account_info(),
+                    base_token_program.clone(),
+                    ctx.accounts.associated_token_program.to_account_info(),
+                )?;
+
+                // Send unsold base tokens to migration escrow to be paired into liquidity
+                let pool_base_transfer_cpi_account = TransferChecked {
+                    from: reserver_base_ata,
+                    mint: base_mint,
+                    to: ctx.accounts.migration_escrow_base_ata.to_account_info(),
+                    authority: pool_state.to_account_info()
+                };
+                token_interface::transfer_checked(
+                    CpiContext::new_with_signer(base_token_program.clone(), pool_base_transfer_cpi_account, signer_seeds),
+                    base_amount,
+                    ctx.accounts.base_mint.decimals
+                )?;
+
+                (ctx.accounts.migration_escrow.key(), Some(WithdrawRecipient::MigrationEscrow))
+            }
+        };
+
+        emit!(WithdrawEvent {
+            withdrawer: withdrawer.key(),
+            recipient,
+            recipient_kind,
+            unsold_base_action: Some(unsold_base_action),
+            base_mint: pool_state.base_mint,
+            base_amount,
+            quote_amount: 0,
+            timestamp
+        });
+    }
+
+    let (treasury_quote_amount, creator_quote_amount, escrow_quote_amount) = main_state.withdrawal_policy.split(quote_amount);
+    let recipients = [
+        (WithdrawRecipient::Treasury, &ctx.accounts.treasury, &ctx.accounts.treasury_quote_ata, treasury_quote_amount),
+        (WithdrawRecipient::Creator, &ctx.accounts.creator, &ctx.accounts.creator_quote_ata, creator_quote_amount),
+        (WithdrawRecipient::MigrationEscrow, &ctx.accounts.migration_escrow, &ctx.accounts.migration_escrow_quote_ata, escrow_quote_amount),
+    ];
+
+    for (recipient_kind, recipient, recipient_quote_ata, recipient_quote_amount) in recipients {
+        if recipient_quote_amount > 0 {
+            // Transfer quote tokens from pool to recipient
+            let pool_quote_transfer_cpi_account = Transfer {
+                from: ctx.accounts.reserver_quote_ata.to_account_info(),
+                to: recipient_quote_ata.to_account_info(),
+                authority: pool_state.to_account_info()
+            };
+            token::transfer(CpiContext::new_with_signer(token_program.clone(), pool_quote_transfer_cpi_account, signer_seeds), recipient_quote_amount)?;
+
+            emit!(WithdrawEvent {
+                withdrawer: withdrawer.key(),
+                recipient: recipient.key(),
+                recipient_kind: Some(recipient_kind),
+                unsold_base_action: None,
+                base_mint: pool_state.base_mint,
+                base_amount: 0,
+                quote_amount: recipient_quote_amount,
+                timestamp
+            });
+        }
+    }
+
+    // Unwrap WSOL (close treasury's WSOL account unless it held WSOL before) when withdrawer is the treasury, withdrawer receives lamports
+    if is_native_mint(&pool_state.quote_mint) && treasury_quote_amount > 0 && ctx.accounts.treasury.key() == withdrawer.key() {
+        close_token_account(withdrawer.clone(), &ctx.accounts.treasury_quote_ata, token_program)?;
+    }
+
     Ok(())
 }
 
@@ -90,18 +203,18 @@ pub struct AWithdraw<'info> {
         mut,
         seeds = [
             PoolState::PREFIX_SEED,
-            base_mint.key().as_ref(), 
+            base_mint.key().as_ref(),
             quote_mint.key().as_ref(),
         ],
         bump,
     )]
     pub pool_state: Box<Account<'info, PoolState>>, // PoolState account
-    
+
     #[account(
         mut,
         address = pool_state.base_mint
     )]
-    pub base_mint: Box<Account<'info, Mint>>, // Base token account
+    pub base_mint: Box<InterfaceAccount<'info, token_interface::Mint>>, // Base token account (SPL Token or Token-2022)
     #[account(
         mut,
         address = pool_state.quote_mint
@@ -112,8 +225,9 @@ pub struct AWithdraw<'info> {
         mut,
         associated_token::mint = base_mint,
         associated_token::authority = pool_state,
+        associated_token::token_program = base_token_program,
     )]
-    pub reserver_base_ata: Box<Account<'info, TokenAccount>>, // PoolState's base token ATA
+    pub reserver_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>, // PoolState's base token ATA
     #[account(
         mut,
         associated_token::mint = quote_mint,
@@ -121,22 +235,59 @@ pub struct AWithdraw<'info> {
     )]
     pub reserver_quote_ata: Box<Account<'info, TokenAccount>>, // PoolState's quote token ATA
 
+    #[account(address = main_state.withdrawal_policy.treasury)]
+    /// CHECK: checked by address
+    pub treasury: AccountInfo<'info>, // Treasury
     #[account(
         init_if_needed,
         payer = withdrawer,
-        associated_token::mint = base_mint,
-        associated_token::authority = withdrawer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = treasury,
+    )]
+    pub treasury_quote_ata: Box<Account<'info, TokenAccount>>, // Treasury's quote token ATA
+
+    #[account(address = pool_state.owner)]
+    /// CHECK: checked by address
+    pub creator: AccountInfo<'info>, // Pool creator
+    #[account(
+        init_if_needed,
+        payer = withdrawer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = creator,
+    )]
+    pub creator_quote_ata: Box<Account<'info, TokenAccount>>, // Creator's quote token ATA
+
+    #[account(address = main_state.withdrawal_policy.migration_escrow)]
+    /// CHECK: checked by address
+    pub migration_escrow: AccountInfo<'info>, // Liquidity migration escrow
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&migration_escrow.key(), &base_mint.key(), &base_token_program.key())
     )]
-    pub withdrawer_base_ata: Box<Account<'info, TokenAccount>>, // Admin's base token ATA
+    /// CHECK: created on withdrawal if not exists
+    pub migration_escrow_base_ata: UncheckedAccount<'info>, // Migration escrow's base token ATA
     #[account(
         init_if_needed,
         payer = withdrawer,
         associated_token::mint = quote_mint,
-        associated_token::authority = withdrawer,
+        associated_token::authority = migration_escrow,
+    )]
+    pub migration_escrow_quote_ata: Box<Account<'info, TokenAccount>>, // Migration escrow's quote token ATA
+
+    #[account(
+        init_if_needed,
+        payer = withdrawer,
+        seeds = [BaseVesting::PREFIX_SEED, pool_state.key().as_ref()],
+        bump,
+        space = 8 + BaseVesting::MAX_SIZE
     )]
-    pub withdrawer_quote_ata: Box<Account<'info, TokenAccount>>, // Admin's quote token ATA
+    pub base_vesting: Option<Box<Account<'info, BaseVesting>>>, // Pool's BaseVesting account (required when unsold base tokens are locked)
+    #[account(mut)]
+    /// CHECK: checked against BaseVesting's ATA address, created on withdrawal if not exists
+    pub vesting_base_ata: Option<UncheckedAccount<'info>>, // BaseVesting's base token ATA (required when unsold base tokens are locked)
 
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Program<'info, Token>,
+    pub base_token_program: Interface<'info, TokenInterface>, // Token program of base token (SPL Token or Token-2022)
     pub system_program: Program<'info, System>,
 }
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::{CompletionReward, CurveType, MainState, QuoteConfig, WindowUnit, UnsoldBaseAction, constants::OBSERVATION_COUNT, error::PumpFunError};
 
 // BondingCurve struct
 #[account]
@@ -15,12 +16,569 @@ pub struct PoolState {
                                     //   Starts with 0 quote token and is changed on buying/selling tokens
                                     //   When the bonding curve is complete, it should become real_quote_threshold
     pub real_quote_threshold: u64,  // Real quote token threshold
-    pub complete: bool              // Flag indicating whether the bonding curve is complete or not
+    pub complete: bool,             // Flag indicating whether the bonding curve is complete or not
+    pub curve_type: CurveType,      // Shape of the bonding curve
+    pub initial_base_reserves: u64, // Amount of real base tokens put into the bonding curve on creation
+    pub paused: bool,               // Flag indicating whether the pool is paused
+    pub trading_fee: Option<u64>,   // Trading fee overriding MainState.trading_fee (taken from QuoteConfig on creation)
+    pub created_at: i64,            // Pool creation time
+    pub created_slot: u64,          // Pool creation slot
+    pub trading_start_ts: Option<i64>, // Time trading opens at (None: tradable immediately)
+    pub presale_merkle_root: [u8; 32], // Merkle root of allowlisted (wallet, allocation) leaves for presale
+    pub presale_end_ts: i64,        // Presale end time (0: no presale), public curve opens afterwards
+    pub deadline: Option<i64>,      // Time the pool must complete by, else it enters refund state (None: no deadline)
+    pub refunded_base_amount: u64,  // Amount of base tokens returned (burnt) by holders in refund state
+    pub unsold_base_action: UnsoldBaseAction, // Action taken on unsold base tokens on withdrawal (taken from MainState on creation)
+    pub creator_reward: u64,        // Creator completion reward reserved from real_quote_reserves on completion, not claimed yet
+    pub price_cumulative_last: u128, // Sum of spot price (Q64.64) weighted by seconds it was held, wraps on overflow
+    pub last_price_update_ts: i64,  // Time price_cumulative_last was last updated
+    pub observations_enabled: bool, // Flag indicating whether trades must record samples in PoolObservations
+    pub reserved_creator_reward: u64, // Creator completion reward taken out of real_quote_reserves on completion, still counted in the curve price
+}
+
+// Vesting of locked unsold base tokens of a pool
+#[account]
+pub struct BaseVesting {
+    pub pool: Pubkey,               // PoolState address (BaseVesting outlives the pool once it's closed)
+    pub base_mint: Pubkey,          // Base token address
+    pub beneficiary: Pubkey,        // Address receiving released base tokens (treasury at the time of locking)
+    pub total_amount: u64,          // Amount of base tokens locked (not released at the start of the current schedule)
+    pub released_amount: u64,       // Amount of base tokens released in the current schedule
+    pub start_ts: i64,              // Release start time (last lock)
+    pub end_ts: i64,                // Time all locked base tokens are released
+}
+
+impl BaseVesting {
+    pub const MAX_SIZE: usize = std::mem::size_of::<Self>();    // Size of BaseVesting
+    pub const PREFIX_SEED: &'static [u8] = b"base_vesting";     // Seed of BaseVesting
+
+    // This function calculates amount of base tokens releasable now
+    // Params
+    //   self - BaseVesting struct itself
+    //   now - Current time
+    // Return
+    //   vested amount (linear between start_ts and end_ts) less released amount
+    pub fn releasable_amount(&self, now: i64) -> u64 {
+        let vested_amount = if now >= self.end_ts {
+            self.total_amount
+        } else {
+            let elapsed = now.saturating_sub(self.start_ts).max(0) as u128;
+            let duration = (self.end_ts - self.start_ts) as u128;
+            (self.total_amount as u128 * elapsed / duration) as u64
+        };
+        vested_amount - self.released_amount
+    }
+
+    // This function locks more base tokens, restarting the schedule from now
+    // Params
+    //   self - BaseVesting struct itself
+    //   base_amount - Amount of base tokens to lock
+    //   now - Current time
+    //   duration - Vesting duration
+    // Return
+    //   Unreleased base tokens together with base_amount are released linearly until now + duration
+    pub fn lock(&mut self, base_amount: u64, now: i64, duration: i64) {
+        self.total_amount = self.total_amount - self.released_amount + base_amount;
+        self.released_amount = 0;
+        self.start_ts = now;
+        self.end_ts = now + duration;
+    }
+}
+
+// Per-wallet position in a pool
+#[account]
+pub struct UserPosition {
+    pub pool: Pubkey,               // PoolState address
+    pub user: Pubkey,               // Wallet address
+    pub base_bought: u64,           // Cumulative amount of base tokens bought
+    pub base_sold: u64,             // Cumulative amount of base tokens sold
+    pub quote_spent: u64,           // Cumulative amount of quote tokens spent on buying (fee included)
+    pub quote_received: u64,        // Cumulative amount of quote tokens received on selling (fee excluded)
+    pub fees_paid: u64,             // Cumulative trading fees paid in quote
+    pub trade_count: u64,           // Number of trades
+    pub first_trade_ts: i64,        // First trade time
+    pub last_trade_ts: i64,         // Last trade time
+}
+
+// Per-wallet presale allocation usage
+#[account]
+pub struct PresaleClaim {
+    pub pool: Pubkey,               // PoolState address
+    pub user: Pubkey,               // Allowlisted wallet address
+    pub base_bought: u64,           // Amount of base tokens bought out of the allocation
+}
+
+impl PresaleClaim {
+    pub const MAX_SIZE: usize = std::mem::size_of::<Self>();    // Size of PresaleClaim
+    pub const PREFIX_SEED: &'static [u8] = b"presale_claim";    // Seed of PresaleClaim
+}
+
+impl UserPosition {
+    pub const MAX_SIZE: usize = std::mem::size_of::<Self>();    // Size of UserPosition
+    pub const PREFIX_SEED: &'static [u8] = b"user_position";    // Seed of UserPosition
+
+    // This function records trade statistics of a buy
+    // Params
+    //   self - UserPosition struct itself
+    //   base_amount - Amount of base tokens bought
+    //   quote_amount - Amount of quote tokens spent (fee included)
+    //   fee - Trading fee in quote
+    //   timestamp - Trade time
+    pub fn record_buy(&mut self, base_amount: u64, quote_amount: u64, fee: u64, timestamp: i64) {
+        self.base_bought += base_amount;
+        self.quote_spent += quote_amount;
+        self.record_trade(fee, timestamp);
+    }
+
+    // This function records trade statistics of a sell
+    // Params
+    //   self - UserPosition struct itself
+    //   base_amount - Amount of base tokens sold
+    //   quote_amount - Amount of quote tokens received (fee excluded)
+    //   fee - Trading fee in quote
+    //   timestamp - Trade time
+    pub fn record_sell(&mut self, base_amount: u64, quote_amount: u64, fee: u64, timestamp: i64) {
+        self.base_sold += base_amount;
+        self.quote_received += quote_amount;
+        self.record_trade(fee, timestamp);
+    }
+
+    // This function records a buy in the buyer's position and limits bought amount per wallet during anti-snipe window
+    //   Buys are always recorded during anti-snipe window as the limit relies on base_bought
+    // Params
+    //   user_position - Buyer's UserPosition (pool and user already set, required during anti-snipe window)
+    //   main_state - MainState account
+    //   in_anti_snipe_window - Flag indicating whether the pool is in anti-snipe window
+    //   base_amount - Amount of base tokens bought
+    //   quote_amount - Amount of quote tokens spent (fee included)
+    //   fee - Trading fee in quote
+    //   timestamp - Trade time
+    // Return
+    //   Ok on success, ErrorCode if position is missing or base_bought exceeds max_buy_per_wallet during anti-snipe window
+    pub fn record_limited_buy(user_position: Option<&mut UserPosition>, main_state: &MainState, in_anti_snipe_window: bool, base_amount: u64, quote_amount: u64, fee: u64, timestamp: i64) -> Result<()> {
+        let Some(user_position) = user_position else {
+            require!(!in_anti_snipe_window, PumpFunError::MissingUserPosition);
+            return Ok(());
+        };
+        if main_state.track_positions || in_anti_snipe_window {
+            user_position.record_buy(base_amount, quote_amount, fee, timestamp);
+        }
+        main_state.check_buy_limit(in_anti_snipe_window, user_position.base_bought)
+    }
+
+    fn record_trade(&mut self, fee: u64, timestamp: i64) {
+        self.fees_paid += fee;
+        if self.trade_count == 0 {
+            self.first_trade_ts = timestamp;
+        }
+        self.trade_count += 1;
+        self.last_trade_ts = timestamp;
+    }
+}
+
+// Referrer statistics struct
+#[account]
+pub struct ReferrerStats {
+    pub referrer: Pubkey,           // Referrer wallet address
+    pub volume: u64,                // Cumulative quote token volume of referred trades (trading fee included)
+    pub earnings: u64,              // Cumulative referral fees earned in quote
+    pub trade_count: u64,           // Number of referred trades
+}
+
+impl ReferrerStats {
+    pub const MAX_SIZE: usize = std::mem::size_of::<Self>();    // Size of ReferrerStats
+    pub const PREFIX_SEED: &'static [u8] = b"referrer_stats";   // Seed of ReferrerStats
+}
+
+// Trade sample of PoolObservations
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+pub struct Observation {
+    pub timestamp: i64,             // Trade time
+    pub price: u128,                // Spot price after the trade (Q64.64)
+    pub cumulative_volume: u64,     // Cumulative quote volume of the pool including the trade (wraps on overflow)
+}
+
+// Price history of a pool - ring buffer of trade samples written by each trade
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+pub struct PoolObservations {
+    pub pool: Pubkey,               // PoolState address
+    pub head: u64,                  // Index the next sample is written at
+    pub count: u64,                 // Number of samples written (up to OBSERVATION_COUNT)
+    pub cumulative_volume: u64,     // Cumulative quote volume of recorded trades (wraps on overflow)
+    pub observations: [Observation; OBSERVATION_COUNT], // Trade samples
+}
+
+// OHLC candle - produced from PoolObservations
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Candle {
+    pub open_ts: i64,               // Candle start time
+    pub open: u128,                 // First sampled price (Q64.64)
+    pub high: u128,                 // Highest sampled price (Q64.64)
+    pub low: u128,                  // Lowest sampled price (Q64.64)
+    pub close: u128,                // Last sampled price (Q64.64)
+    pub volume: u64,                // Quote volume of trades in the candle
+    pub trade_count: u64,           // Number of trades in the candle
+}
+
+impl PoolObservations {
+    pub const MAX_SIZE: usize = std::mem::size_of::<Self>();        // Size of PoolObservations
+    pub const PREFIX_SEED: &'static [u8] = b"pool_observations";    // Seed of PoolObservations
+
+    // This function writes a trade sample, overwriting the oldest one when the buffer is full
+    // Params
+    //   self - PoolObservations struct itself
+    //   timestamp - Trade time
+    //   price - Spot price after the trade (Q64.64)
+    //   volume - Quote volume of the trade
+    pub fn record(&mut self, timestamp: i64, price: u128, volume: u64) {
+        self.cumulative_volume = self.cumulative_volume.wrapping_add(volume);
+        self.observations[self.head as usize] = Observation {
+            timestamp,
+            price,
+            cumulative_volume: self.cumulative_volume,
+        };
+        self.head = (self.head + 1) % OBSERVATION_COUNT as u64;
+        if self.count < OBSERVATION_COUNT as u64 {
+            self.count += 1;
+        }
+    }
+
+    // This function returns stored samples
+    // Params
+    //   self - PoolObservations struct itself
+    // Return
+    //   samples from the oldest to the newest
+    pub fn samples(&self) -> Vec<Observation> {
+        let start = (self.head + OBSERVATION_COUNT as u64 - self.count) % OBSERVATION_COUNT as u64;
+        (0..self.count)
+            .map(|i| self.observations[((start + i) % OBSERVATION_COUNT as u64) as usize])
+            .collect()
+    }
+
+    // This function aggregates stored samples into candles
+    //   Candles without trades are skipped, volume of the oldest sample is unknown once the buffer has wrapped
+    // Params
+    //   self - PoolObservations struct itself
+    //   start_ts - Start time of the first candle, earlier samples are ignored
+    //   interval - Candle length in seconds
+    // Return
+    //   candles from the oldest to the newest, empty if interval is not positive
+    pub fn candles(&self, start_ts: i64, interval: i64) -> Vec<Candle> {
+        let mut candles: Vec<Candle> = Vec::new();
+        if interval <= 0 {
+            return candles;
+        }
+
+        // Cumulative volume before the oldest sample is known only if nothing was overwritten
+        let samples = self.samples();
+        let mut prev_cumulative_volume = if self.count < OBSERVATION_COUNT as u64 { Some(0u64) } else { None };
+        for sample in samples {
+            let volume = match prev_cumulative_volume {
+                Some(prev) => sample.cumulative_volume.wrapping_sub(prev),
+                None => 0,
+            };
+            prev_cumulative_volume = Some(sample.cumulative_volume);
+            if sample.timestamp < start_ts {
+                continue;
+            }
+
+            let open_ts = start_ts + (sample.timestamp - start_ts) / interval * interval;
+            match candles.last_mut() {
+                Some(candle) if candle.open_ts == open_ts => {
+                    candle.high = candle.high.max(sample.price);
+                    candle.low = candle.low.min(sample.price);
+                    candle.close = sample.price;
+                    candle.volume = candle.volume.wrapping_add(volume);
+                    candle.trade_count += 1;
+                }
+                _ => candles.push(Candle {
+                    open_ts,
+                    open: sample.price,
+                    high: sample.price,
+                    low: sample.price,
+                    close: sample.price,
+                    volume,
+                    trade_count: 1,
+                }),
+            }
+        }
+        candles
+    }
+}
+
+// Cumulative price observation - returned by observe_price instruction
+//   TWAP between two observations = (later.price_cumulative - earlier.price_cumulative) / (later.timestamp - earlier.timestamp)
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy)]
+pub struct PriceObservation {
+    pub price_cumulative: u128,     // Cumulative price (Q64.64 price * seconds) at timestamp
+    pub timestamp: i64,             // Observation time
+}
+
+impl PriceObservation {
+    // This function calculates time-weighted average price between two observations
+    // Params
+    //   self - Earlier observation
+    //   later - Later observation of the same pool
+    // Return
+    //   TWAP of base token in quote (Q64.64 fixed point), None if no time elapsed between observations
+    pub fn twap(&self, later: &PriceObservation) -> Option<u128> {
+        let elapsed = later.timestamp.checked_sub(self.timestamp)?;
+        if elapsed <= 0 {
+            return None;
+        }
+        Some(later.price_cumulative.wrapping_sub(self.price_cumulative) / elapsed as u128)
+    }
+}
+
+// Trade simulation result - returned by quote instructions
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy)]
+pub struct TradeQuote {
+    pub input_amount: u64,          // Amount of tokens paid by the trader (fee included on buying)
+    pub output_amount: u64,         // Amount of tokens received by the trader (fee excluded on selling)
+    pub fee: u64,                   // Trading fee in quote
+    pub transfer_fee: u64,          // Base token transfer fee withheld by Token-2022 mint (0 if none)
+    pub real_base_reserves: u64,    // Real base token reserves after the trade
+    pub real_quote_reserves: u64,   // Real quote token reserves after the trade
+    pub complete: bool,             // Flag indicating whether the trade completes the bonding curve
 }
 
 impl PoolState {
     pub const MAX_SIZE: usize = std::mem::size_of::<Self>();    // Size of PoolState
     pub const PREFIX_SEED: &'static [u8] = b"pool";             // Seed of PoolState
+    pub const LP_LOCKER_SEED: &'static [u8] = b"lp_locker";     // Seed of the PDA locking migrated LP tokens
+    pub const CREATOR_FEE_VAULT_SEED: &'static [u8] = b"creator_fee_vault"; // Seed of the creator's fee vault (quote token account)
+
+    // This function initializes all members of a new pool
+    // Params
+    //   self - PoolState struct itself
+    //   main_state - MainState account
+    //   quote_config - QuoteConfig of quote token
+    //   creator - Creator's address
+    //   base_mint - Base token mint address
+    //   quote_mint - Quote token mint address
+    //   curve_type - Shape of the bonding curve
+    //   clock - Clock at creation
+    #[allow(clippy::too_many_arguments)]
+    pub fn init(&mut self, main_state: &MainState, quote_config: &QuoteConfig, creator: Pubkey, base_mint: Pubkey, quote_mint: Pubkey, curve_type: CurveType, clock: &Clock) {
+        self.owner = creator; // Creator's address
+        self.base_mint = base_mint; // Base token mint address
+        self.quote_mint = quote_mint; // Quote token mint address
+        self.real_base_reserves = main_state.total_supply; // Total supply of base tokens is all put into the pool
+        self.virt_base_reserves = main_state.init_virt_base_reserves; // Initial virtual token reserves
+        self.real_quote_reserves = 0; // 0 SOL
+        self.virt_quote_reserves = quote_config.init_virt_quote_reserves; // Initial virtual quote token reserves
+        self.real_quote_threshold = quote_config.real_quote_threshold; // Real quote token threshold
+        self.trading_fee = quote_config.trading_fee; // Trading fee override of quote token
+        self.curve_type = curve_type; // Shape of the bonding curve
+        self.initial_base_reserves = main_state.total_supply; // Real base token reserves on creation
+        self.paused = false;
+        self.created_at = clock.unix_timestamp; // Start of anti-snipe window
+        self.created_slot = clock.slot;
+        self.trading_start_ts = None; // Tradable immediately
+        self.presale_merkle_root = [0; 32]; // No presale
+        self.presale_end_ts = 0;
+        self.deadline = None; // No deadline
+        self.refunded_base_amount = 0;
+        self.unsold_base_action = main_state.unsold_base_action; // Fixed for the pool's lifetime
+        self.creator_reward = 0;
+        self.price_cumulative_last = 0;
+        self.last_price_update_ts = clock.unix_timestamp;
+        self.observations_enabled = false;
+        self.reserved_creator_reward = 0;
+    }
+
+    // This function accumulates spot price held since the last update, must be called before reserves change on trading
+    //   The price set by a trade only starts accumulating from the next trade, so it can't be manipulated inside one transaction
+    // Params
+    //   self - PoolState struct itself
+    //   now - Current time
+    // Return
+    //   Ok on success, ErrorCode if spot price can't be evaluated
+    pub fn update_price_cumulative(&mut self, now: i64) -> Result<()> {
+        let elapsed = now.saturating_sub(self.last_price_update_ts);
+        if elapsed > 0 {
+            self.price_cumulative_last = self.price_cumulative_last.wrapping_add(self.spot_price()?.wrapping_mul(elapsed as u128));
+            self.last_price_update_ts = now;
+        }
+        Ok(())
+    }
+
+    // This function records a trade sample in PoolObservations, after reserves changed on trading
+    // Params
+    //   self - PoolState struct itself
+    //   pool_observations - Pool's PoolObservations account (required if enabled on the pool)
+    //   timestamp - Trade time
+    //   volume - Quote volume of the trade (fee excluded)
+    // Return
+    //   Ok on success, ErrorCode if PoolObservations is enabled but missing
+    pub fn record_observation(&self, pool_observations: Option<&AccountLoader<PoolObservations>>, timestamp: i64, volume: u64) -> Result<()> {
+        match pool_observations {
+            Some(pool_observations) => pool_observations.load_mut()?.record(timestamp, self.spot_price()?, volume),
+            None => require!(!self.observations_enabled, PumpFunError::MissingPoolObservations),
+        }
+        Ok(())
+    }
+
+    // This function observes cumulative price at current time (extrapolated from the last update with current spot price)
+    // Params
+    //   self - PoolState struct itself
+    //   now - Current time
+    // Return
+    //   PriceObservation at now
+    pub fn observe_price(&self, now: i64) -> Result<PriceObservation> {
+        let elapsed = now.saturating_sub(self.last_price_update_ts).max(0);
+        Ok(PriceObservation {
+            price_cumulative: self.price_cumulative_last.wrapping_add(self.spot_price()?.wrapping_mul(elapsed as u128)),
+            timestamp: now,
+        })
+    }
+
+    // This function returns current marginal price of base token
+    // Params
+    //   self - PoolState struct itself
+    // Return
+    //   price in quote (Q64.64 fixed point)
+    pub fn spot_price(&self) -> Result<u128> {
+        self.curve_type.curve().spot_price(self)
+    }
+
+    // This function marks the bonding curve complete and reserves creator completion reward
+    //   Reward is taken out of real_quote_reserves, so it is neither withdrawn nor migrated,
+    //   but stays in reserved_creator_reward so the curve price doesn't drop on completion
+    // Params
+    //   self - PoolState struct itself
+    //   completion_reward - Creator completion reward of MainState
+    // Return
+    //   creator completion reward in quote
+    pub fn set_complete(&mut self, completion_reward: CompletionReward) -> u64 {
+        let creator_reward = completion_reward.amount(self.real_quote_threshold).min(self.real_quote_reserves);
+        self.complete = true;
+        self.real_quote_reserves -= creator_reward;
+        self.creator_reward = creator_reward;
+        self.reserved_creator_reward = creator_reward;
+        creator_reward
+    }
+
+    // This function checks whether the pool deadline has passed
+    // Params
+    //   self - PoolState struct itself
+    //   now - Current time
+    // Return
+    //   true if deadline is set and has passed
+    pub fn deadline_passed(&self, now: i64) -> bool {
+        match self.deadline {
+            Some(deadline) => now >= deadline,
+            None => false,
+        }
+    }
+
+    // This function checks whether the pool is in refund state
+    // Params
+    //   self - PoolState struct itself
+    //   now - Current time
+    // Return
+    //   true if deadline has passed without completing the bonding curve
+    pub fn in_refund(&self, now: i64) -> bool {
+        !self.complete && self.deadline_passed(now)
+    }
+
+    // This function calculates pro-rata quote tokens refunded for base tokens in refund state
+    // Params
+    //   self - PoolState struct itself
+    //   base_amount - Amount of base tokens returned
+    // Return
+    //   quote_amount - Share of real_quote_reserves, ErrorCode if base_amount exceeds base tokens held by holders
+    pub fn compute_refund_amount(&self, base_amount: u64) -> Result<u64> {
+        // Base tokens held by holders: sold by the curve and not refunded yet
+        let outstanding_base_amount = self.initial_base_reserves - self.real_base_reserves - self.refunded_base_amount;
+        require!(base_amount.gt(&0) && base_amount.le(&outstanding_base_amount), PumpFunError::WrongBaseAmount);
+        Ok((base_amount as u128 * self.real_quote_reserves as u128 / outstanding_base_amount as u128) as u64)
+    }
+
+    // This function checks whether the pool is in presale phase
+    // Params
+    //   self - PoolState struct itself
+    //   now - Current time
+    // Return
+    //   true if presale is set and has not ended yet
+    pub fn in_presale(&self, now: i64) -> bool {
+        now < self.presale_end_ts
+    }
+
+    // This function checks whether trading has started on the pool
+    // Params
+    //   self - PoolState struct itself
+    //   now - Current time
+    // Return
+    //   true if trading start time is not set or has passed
+    pub fn trading_started(&self, now: i64) -> bool {
+        match self.trading_start_ts {
+            Some(trading_start_ts) => now >= trading_start_ts,
+            None => true,
+        }
+    }
+
+    // This function checks whether the pool is open for trading, shared by trades and their quotes
+    // Params
+    //   self - PoolState struct itself
+    //   main_state - MainState account
+    //   now - Current time
+    // Return
+    //   Ok if neither program nor pool is paused, trading has started, deadline has not passed and curve is not complete
+    pub fn check_tradable(&self, main_state: &MainState, now: i64) -> Result<()> {
+        require!(main_state.paused.eq(&false) && self.paused.eq(&false), PumpFunError::Paused); // Neither program nor pool must be paused
+        require!(self.trading_started(now), PumpFunError::TradingNotStarted); // Trading start time must have passed
+        require!(!self.deadline_passed(now), PumpFunError::DeadlinePassed); // Pool deadline must not have passed
+        require!(self.complete.eq(&false), PumpFunError::BondingCurveComplete); // BondingCurve must not be complete
+        Ok(())
+    }
+
+    // This function checks whether the public curve is open for buying, shared by buys and their quotes
+    // Params
+    //   self - PoolState struct itself
+    //   now - Current time
+    // Return
+    //   Ok unless presale is active (public curve opens after presale)
+    pub fn check_public_buy(&self, now: i64) -> Result<()> {
+        require!(!self.in_presale(now), PumpFunError::PresaleActive);
+        Ok(())
+    }
+
+    // This function returns the time public trading opens at
+    // Params
+    //   self - PoolState struct itself
+    // Return
+    //   latest of creation time, trading start time and presale end time
+    pub fn trading_open_ts(&self) -> i64 {
+        self.created_at.max(self.trading_start_ts.unwrap_or(0)).max(self.presale_end_ts)
+    }
+
+    // This function checks whether the pool is still in anti-snipe window
+    //   Window starts when public trading opens, window in slots runs from creation (scheduled start is rejected then)
+    // Params
+    //   self - PoolState struct itself
+    //   main_state - MainState account
+    //   clock - Current clock
+    // Return
+    //   true if anti-snipe window is enabled and has not passed yet
+    pub fn in_anti_snipe_window(&self, main_state: &MainState, clock: &Clock) -> bool {
+        match main_state.anti_snipe_window_unit {
+            WindowUnit::Slots => clock.slot < self.created_slot.saturating_add(main_state.anti_snipe_window),
+            WindowUnit::Seconds => clock.unix_timestamp < self.trading_open_ts().saturating_add(main_state.anti_snipe_window as i64),
+        }
+    }
+
+    // This function returns trading fee applied on the pool
+    // Params
+    //   self - PoolState struct itself
+    //   main_state - MainState account
+    // Return
+    //   trading fee (pool's override if set, else MainState's)
+    pub fn effective_trading_fee(&self, main_state: &MainState) -> u64 {
+        self.trading_fee.unwrap_or(main_state.trading_fee)
+    }
 
     // This function calculates receivable amount on buying tokens
     // Params
@@ -28,10 +586,8 @@ impl PoolState {
     //   quote_amount - Amount of quote token to buy with
     // Return
     //   base_amount - Receivable amount of tokens
-    pub fn compute_receivable_amount_on_buy(&mut self, quote_amount: u64) -> u64 {
-        let base_amount =
-            calculate_output_amount(quote_amount, self.virt_quote_reserves + self.real_quote_reserves, self.virt_base_reserves + self.real_base_reserves);
-        base_amount
+    pub fn compute_receivable_amount_on_buy(&self, quote_amount: u64) -> Result<u64> {
+        self.curve_type.curve().compute_receivable_amount_on_buy(self, quote_amount)
     }
 
     // This function calculates required amount of quote token on buying tokens
@@ -40,10 +596,8 @@ impl PoolState {
     //   base_amount - Amount of base tokens to buy
     // Return
     //   quote_amount - Required amount of quote
-    pub fn compute_required_amount_on_buy(&mut self, base_amount: u64) -> u64 {
-        let quote_amount =
-            calculate_input_amount(base_amount, self.virt_quote_reserves + self.real_quote_reserves, self.virt_base_reserves + self.real_base_reserves);
-        quote_amount
+    pub fn compute_required_amount_on_buy(&self, base_amount: u64) -> Result<u64> {
+        self.curve_type.curve().compute_required_amount_on_buy(self, base_amount)
     }
 
     // This function calculates receivable amount of quote tokens on selling base tokens
@@ -52,43 +606,143 @@ impl PoolState {
     //   base_amount - Amount of base tokens to sell
     // Return
     //   quote_amount - Receivable amount of quote tokens
-    pub fn compute_receivable_amount_on_sell(&mut self, base_amount: u64) -> u64 {
-        let quote_amount =
-            calculate_output_amount(base_amount, self.virt_base_reserves + self.real_base_reserves, self.virt_quote_reserves + self.real_quote_reserves);
-        quote_amount
-    }
-}
-
-// This function calculates output amount by using AMM formula
-// Params
-//   input_amount - Input amount
-//   input_reserve - Input reserve
-//   output_reserve - Output reserve
-// Return
-//   output_amount - Output amount
-//     output_amount = output_reserve * input_amount / (input_reserve + input_amount)
-fn calculate_output_amount(input_amount: u64, input_reserve: u64, output_reserve: u64) -> u64 {
-    let output_amount = (output_reserve as u128)
-        .checked_mul(input_amount as u128)
-        .unwrap()
-        .checked_div((input_reserve as u128) + (input_amount as u128))
-        .unwrap();
-    output_amount as u64
-}
-
-// This function calculates input amount by using AMM formula
-// Params
-//   output_amount - Output amount
-//   input_reserve - Input reserve
-//   output_reserve - Output reserve
-// Return
-//   input_amount - Input amount
-//     input_amount = output_amount * input_reserve / (output_reserve - output_amount)
-fn calculate_input_amount(output_amount: u64, input_reserve: u64, output_reserve: u64) -> u64 {
-    let input_amount = (output_amount as u128)
-        .checked_mul(input_reserve as u128)
-        .unwrap()
-        .checked_div((output_reserve as u128) - (output_amount as u128))
-        .unwrap();
-    input_amount as u64
+    pub fn compute_receivable_amount_on_sell(&self, base_amount: u64) -> Result<u64> {
+        self.curve_type.curve().compute_receivable_amount_on_sell(self, base_amount)
+    }
+
+    // This function calculates required amount of base tokens on selling for quote tokens
+    // Params
+    //   self - PoolState struct itself
+    //   quote_amount - Amount of quote tokens to receive (fee included)
+    // Return
+    //   base_amount - Required amount of base tokens, None if the curve can't pay out quote_amount
+    pub fn compute_required_amount_on_sell(&self, quote_amount: u64) -> Result<Option<u64>> {
+        self.curve_type.curve().compute_required_amount_on_sell(self, quote_amount)
+    }
+
+    // This function calculates amount of base tokens matching quote tokens at the current curve price
+    // Params
+    //   self - PoolState struct itself
+    //   quote_amount - Amount of quote tokens to pair
+    // Return
+    //   base_amount - Amount of base tokens (capped by real_base_reserves)
+    pub fn compute_base_amount_at_price(&self, quote_amount: u64) -> Result<u64> {
+        let base_amount = self.curve_type.curve().compute_base_amount_at_price(self, quote_amount)?;
+        Ok(std::cmp::min(base_amount, self.real_base_reserves))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CompletionReward, WithdrawalPolicy, pool::curve::tests::pool_state};
+
+    fn main_state() -> MainState {
+        MainState {
+            owner: Pubkey::default(),
+            pending_owner: Pubkey::default(),
+            withdrawer: Pubkey::default(),
+            pauser: Pubkey::default(),
+            fee_recipient: Pubkey::default(),
+            trading_fee: 1_000,
+            total_supply: 1_000_000_000_000_000,
+            init_virt_base_reserves: 200_000_000_000_000,
+            init_virt_quote_reserves: 30_000_000_000,
+            real_quote_threshold: 85_000_000_000,
+            allowed_curves: CurveType::ConstantProduct.mask(),
+            creator_fee_share: 0,
+            referral_fee_share: 0,
+            update_delay: 0,
+            paused: false,
+            anti_snipe_window: 60,
+            anti_snipe_window_unit: WindowUnit::Seconds,
+            max_buy_per_wallet: 10_000_000_000_000,
+            withdrawal_policy: WithdrawalPolicy {
+                treasury: Pubkey::default(),
+                creator_share: 0,
+                migration_escrow: Pubkey::default(),
+                migration_escrow_share: 0,
+            },
+            unsold_base_action: UnsoldBaseAction::Burn,
+            vesting_duration: 0,
+            creator_completion_reward: CompletionReward::Fixed(0),
+            track_positions: false,
+        }
+    }
+
+    fn user_position() -> UserPosition {
+        UserPosition {
+            pool: Pubkey::default(),
+            user: Pubkey::default(),
+            base_bought: 0,
+            base_sold: 0,
+            quote_spent: 0,
+            quote_received: 0,
+            fees_paid: 0,
+            trade_count: 0,
+            first_trade_ts: 0,
+            last_trade_ts: 0,
+        }
+    }
+
+    #[test]
+    fn initial_buy_above_max_buy_per_wallet_rejected() {
+        let main_state = main_state();
+        let pool_state = pool_state(CurveType::ConstantProduct);
+        let clock = Clock { slot: pool_state.created_slot, unix_timestamp: pool_state.created_at, ..Clock::default() };
+        let in_anti_snipe_window = pool_state.in_anti_snipe_window(&main_state, &clock);
+        assert!(in_anti_snipe_window, "pool must be in anti-snipe window on creation");
+
+        let quote_amount = 5_000_000_000;
+        let base_amount = pool_state.compute_receivable_amount_on_buy(quote_amount).unwrap();
+        assert!(base_amount > main_state.max_buy_per_wallet);
+        let res = UserPosition::record_limited_buy(Some(&mut user_position()), &main_state, in_anti_snipe_window, base_amount, quote_amount, 0, clock.unix_timestamp);
+        assert_eq!(res.unwrap_err(), PumpFunError::MaxBuyPerWalletExceeded.into());
+
+        let res = UserPosition::record_limited_buy(None, &main_state, in_anti_snipe_window, base_amount, quote_amount, 0, clock.unix_timestamp);
+        assert_eq!(res.unwrap_err(), PumpFunError::MissingUserPosition.into());
+    }
+
+    #[test]
+    fn initial_buy_within_max_buy_per_wallet_recorded() {
+        let main_state = main_state();
+        let pool_state = pool_state(CurveType::ConstantProduct);
+        let clock = Clock { slot: pool_state.created_slot, unix_timestamp: pool_state.created_at, ..Clock::default() };
+        let in_anti_snipe_window = pool_state.in_anti_snipe_window(&main_state, &clock);
+
+        let quote_amount = 100_000_000;
+        let base_amount = pool_state.compute_receivable_amount_on_buy(quote_amount).unwrap();
+        let mut user_position = user_position();
+        UserPosition::record_limited_buy(Some(&mut user_position), &main_state, in_anti_snipe_window, base_amount, quote_amount, 1_000_000, clock.unix_timestamp).unwrap();
+        assert_eq!(user_position.base_bought, base_amount, "buy must be recorded during anti-snipe window even if positions are not tracked");
+        assert_eq!(user_position.trade_count, 1);
+
+        let after_window = Clock { unix_timestamp: pool_state.created_at + main_state.anti_snipe_window as i64, ..clock };
+        assert!(!pool_state.in_anti_snipe_window(&main_state, &after_window));
+        UserPosition::record_limited_buy(None, &main_state, false, base_amount, quote_amount, 0, after_window.unix_timestamp).unwrap();
+    }
+
+    #[test]
+    fn anti_snipe_window_starts_when_trading_opens() {
+        let mut main_state = main_state();
+        let mut pool_state = pool_state(CurveType::ConstantProduct);
+        pool_state.created_at = 1_000;
+        pool_state.trading_start_ts = Some(2_000);
+        pool_state.presale_end_ts = 3_000;
+        assert_eq!(pool_state.trading_open_ts(), 3_000);
+        let window = main_state.anti_snipe_window as i64;
+        let clock = |unix_timestamp| Clock { unix_timestamp, ..Clock::default() };
+        assert!(pool_state.in_anti_snipe_window(&main_state, &clock(3_000 + window - 1)), "window must run from presale end");
+        assert!(!pool_state.in_anti_snipe_window(&main_state, &clock(3_000 + window)));
+
+        pool_state.presale_end_ts = 0;
+        assert!(pool_state.in_anti_snipe_window(&main_state, &clock(2_000 + window - 1)), "window must run from trading start");
+        assert!(!pool_state.in_anti_snipe_window(&main_state, &clock(2_000 + window)));
+
+        assert!(main_state.allows_scheduled_start());
+        main_state.anti_snipe_window_unit = WindowUnit::Slots;
+        assert!(!main_state.allows_scheduled_start(), "scheduled start must be rejected while window is in slots");
+        main_state.anti_snipe_window = 0;
+        assert!(main_state.allows_scheduled_start());
+    }
 }
@@ -1,9 +1,21 @@
 use crate::{
-    constants::{FEE_PER_DIV, QUOTE_MINT_STR},
+    constants::{FEE_PER_DIV, MERKLE_LEAF_PREFIX, MERKLE_NODE_PREFIX},
     error::PumpFunError,
 };
-use anchor_lang::{prelude::*, solana_program::program::invoke};
-use anchor_spl::token::{self, CloseAccount, SyncNative, TokenAccount};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{hash::hashv, instruction::{AccountMeta, Instruction}, program::{invoke, invoke_signed}, program_pack::Pack},
+};
+use anchor_spl::{
+    associated_token::{self, Create},
+    token::{self, spl_token, CloseAccount, SyncNative, TokenAccount},
+    token_interface,
+    token_2022::spl_token_2022::{
+        extension::{transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig}, BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+        pod::pod_from_bytes,
+        state::Account as Token2022Account,
+    },
+};
 
 // This function checks accounts's base/quote token balance
 // Params
@@ -11,7 +23,7 @@ use anchor_spl::token::{self, CloseAccount, SyncNative, TokenAccount};
 //   required_amount - Amount of base/quote token that the creator must have
 // Return
 //   true if sufficient, else false
-pub fn check_balance(ata: &TokenAccount, require_amount: u64) -> bool {
+pub fn check_balance(ata: &token_interface::TokenAccount, require_amount: u64) -> bool {
     // if (ata.mint.to_string() == QUOTE_MINT_STR) {
     //     return true;
     // }
@@ -37,6 +49,19 @@ pub fn calculate_trading_fee(fee: u64, amount: u64) -> u64 {
         .unwrap() as u64
 }
 
+// This function splits trading fee between the protocol, the pool creator and the referrer
+// Params
+//   creator_fee_share - Creator's share of trading fee (in FEE_PER_DIV units)
+//   referral_fee_share - Referrer's share of trading fee (in FEE_PER_DIV units, 0 if no referrer)
+//   fee - Trading fee in quote
+// Return
+//   (protocol fee, creator fee, referral fee) in quote
+pub fn split_trading_fee(creator_fee_share: u64, referral_fee_share: u64, fee: u64) -> (u64, u64, u64) {
+    let creator_fee = calculate_trading_fee(creator_fee_share, fee);
+    let referral_fee = calculate_trading_fee(referral_fee_share, fee);
+    (fee - creator_fee - referral_fee, creator_fee, referral_fee)
+}
+
 // This function calculates total amount of quote
 // Params
 //   fee - feeBps
@@ -51,6 +76,36 @@ pub fn calculate_total_amount(fee: u64, input_amount: u64) -> u64 {
         .unwrap() as u64
 }
 
+// This function calculates minimum amount of quote that is left with net_amount after trading fee
+// Params
+//   fee - feeBps
+//   net_amount - Amount of quote after trading fee
+// Return
+//   total amount in quote
+pub fn calculate_gross_amount(fee: u64, net_amount: u64) -> u64 {
+    let divider = FEE_PER_DIV.checked_mul(100).unwrap() - fee as u128;
+    let mut gross_amount = (net_amount as u128)
+        .checked_mul(FEE_PER_DIV.checked_mul(100).unwrap())
+        .unwrap()
+        .checked_add(divider - 1)
+        .unwrap()
+        .checked_div(divider)
+        .unwrap() as u64;
+    while gross_amount > 0 && gross_amount - 1 - calculate_trading_fee(fee, gross_amount - 1) >= net_amount {
+        gross_amount -= 1;
+    }
+    gross_amount
+}
+
+// This function checks whether the mint is wrapped SOL
+// Params
+//   mint - Token mint address
+// Return
+//   true if WSOL, else false
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    mint.eq(&spl_token::native_mint::ID)
+}
+
 // This function converts required amount of owner's SOL to WSOL if insufficient
 // Params
 //   owner - Owner
@@ -97,23 +152,258 @@ pub fn sync_native_amount<'a>(
     Ok(())
 }
 
-// This function closes token account (WSOL is converted to SOL)
+// This function unwraps WSOL wrapped or received by the current instruction (closes owner's WSOL account)
+//   WSOL held before the instruction stays wrapped, so the account is kept if it wasn't empty
 // Params
 //   owner - Owner
-//   ata - Owner's token ATA
+//   ata - Owner's WSOL ATA (loaded at the start of the instruction, not reloaded)
 //   token_program - Token program
 // Return
 //   Ok
 pub fn close_token_account<'a>(
     owner: AccountInfo<'a>,
-    ata: AccountInfo<'a>,
+    ata: &Account<'a, TokenAccount>,
     token_program: AccountInfo<'a>,
 ) -> Result<()> {
+    if ata.amount > 0 {
+        return Ok(());
+    }
     let cpi_accounts = CloseAccount {
-        account: ata,
+        account: ata.to_account_info(),
         authority: owner.clone(),
         destination: owner,
     };
     token::close_account(CpiContext::new(token_program, cpi_accounts))?;
     Ok(())
 }
+
+// This function creates owner's associated token account if it doesn't exist (SPL Token or Token-2022)
+// Params
+//   payer - Rent payer
+//   ata - Owner's associated token account
+//   owner - Owner
+//   mint - Token mint
+//   system_program - System program
+//   token_program - Token program of the mint
+//   associated_token_program - Associated token program
+// Return
+//   Ok on success, ErrorCode on failure
+pub fn create_associated_token_account<'a>(
+    payer: AccountInfo<'a>,
+    ata: AccountInfo<'a>,
+    owner: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    system_program: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    associated_token_program: AccountInfo<'a>,
+) -> Result<()> {
+    let cpi_accounts = Create {
+        payer,
+        associated_token: ata,
+        authority: owner,
+        mint,
+        system_program,
+        token_program,
+    };
+    associated_token::create_idempotent(CpiContext::new(associated_token_program, cpi_accounts))
+}
+
+// This function creates Metaplex metadata account of the mint (CreateMetadataAccountV3 instruction)
+//   Metadata is immutable, without creators, collection and uses
+// Params
+//   metadata_program - Metaplex Token Metadata program
+//   metadata - Metadata account (PDA of metadata program)
+//   mint - Token mint
+//   authority - Mint authority, also set as update authority
+//   payer - Rent payer
+//   system_program - System program
+//   name, symbol, uri - Token metadata
+//   signer_seeds - Seeds of authority if it's PDA
+// Return
+//   Ok on success, ErrorCode on failure
+#[allow(clippy::too_many_arguments)]
+pub fn create_metadata_account<'a>(
+    metadata_program: AccountInfo<'a>,
+    metadata: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    authority: AccountInfo<'a>,
+    payer: AccountInfo<'a>,
+    system_program: AccountInfo<'a>,
+    name: String,
+    symbol: String,
+    uri: String,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+    let mut data = vec![CREATE_METADATA_ACCOUNT_V3];
+    (name, symbol, uri, 0u16).serialize(&mut data)?; // DataV2 with 0 seller fee
+    data.extend_from_slice(&[
+        0, // No creators
+        0, // No collection
+        0, // No uses
+        0, // Not mutable
+        0, // No collection details
+    ]);
+    let ix = Instruction {
+        program_id: metadata_program.key(),
+        accounts: vec![
+            AccountMeta::new(metadata.key(), false),
+            AccountMeta::new_readonly(mint.key(), false),
+            AccountMeta::new_readonly(authority.key(), true),
+            AccountMeta::new(payer.key(), true),
+            AccountMeta::new_readonly(authority.key(), true),
+            AccountMeta::new_readonly(system_program.key(), false),
+        ],
+        data,
+    };
+    invoke_signed(
+        &ix,
+        &[metadata, mint, authority, payer, system_program, metadata_program],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+// This function returns TLV extension entries of Token-2022 mint
+//   Extension types are read as raw numbers, so newer extensions (e.g. metadata pointer) are also listed
+// Params
+//   data - Mint account data
+// Return
+//   (extension type, extension data) list, empty for classic SPL Token mint
+fn get_mint_extensions(data: &[u8]) -> Vec<(u16, &[u8])> {
+    const EXTENSIONS_START: usize = spl_token::state::Account::LEN + 1; // Mint is padded to account size, followed by account type
+    let mut extensions = vec![];
+    let mut index = EXTENSIONS_START;
+    while index + 4 <= data.len() {
+        let extension_type = u16::from_le_bytes([data[index], data[index + 1]]);
+        let length = u16::from_le_bytes([data[index + 2], data[index + 3]]) as usize;
+        if extension_type == u16::from(ExtensionType::Uninitialized) || index + 4 + length > data.len() {
+            break;
+        }
+        extensions.push((extension_type, &data[index + 4..index + 4 + length]));
+        index += 4 + length;
+    }
+    extensions
+}
+
+// This function checks that base token mint has only supported extensions
+//   Supported: transfer fee (with revoked authorities, so the fee can't change or be harvested), metadata pointer and token metadata
+// Params
+//   mint - Base token mint
+// Return
+//   Ok if supported, ErrorCode on failure
+pub fn check_mint_extensions(mint: &AccountInfo) -> Result<()> {
+    const METADATA_POINTER: u16 = 18; // Not known by spl-token-2022 0.6
+    const TOKEN_METADATA: u16 = 19;
+    let data = mint.try_borrow_data()?;
+    for (extension_type, extension) in get_mint_extensions(&data) {
+        require!(
+            extension_type == u16::from(ExtensionType::TransferFeeConfig) || extension_type == METADATA_POINTER || extension_type == TOKEN_METADATA,
+            PumpFunError::UnsupportedMintExtension
+        );
+        if extension_type == u16::from(ExtensionType::TransferFeeConfig) {
+            let config = pod_from_bytes::<TransferFeeConfig>(extension)?;
+            require!(
+                Option::<Pubkey>::from(config.transfer_fee_config_authority).is_none() && Option::<Pubkey>::from(config.withdraw_withheld_authority).is_none(),
+                PumpFunError::BaseTokenTransferFeeMustNotBeManaged
+            );
+        }
+    }
+    Ok(())
+}
+
+// This function returns transfer fee of the mint in the current epoch
+// Params
+//   mint - Token mint
+// Return
+//   TransferFee if mint has transfer fee extension, else None
+pub fn get_transfer_fee(mint: &AccountInfo) -> Result<Option<TransferFee>> {
+    let data = mint.try_borrow_data()?;
+    let Some((_, config)) = get_mint_extensions(&data)
+        .into_iter()
+        .find(|(extension_type, _)| *extension_type == u16::from(ExtensionType::TransferFeeConfig)) else {
+        return Ok(None);
+    };
+    let config = pod_from_bytes::<TransferFeeConfig>(config)?;
+    Ok(Some(*config.get_epoch_fee(Clock::get()?.epoch)))
+}
+
+// This function returns transfer fee withheld in token account (Token-2022 account can't be closed until it's harvested)
+// Params
+//   token_account - Token account (SPL Token or Token-2022)
+// Return
+//   withheld amount, 0 if account has no transfer fee extension
+pub fn get_withheld_transfer_fee(token_account: &AccountInfo) -> Result<u64> {
+    let data = token_account.try_borrow_data()?;
+    let account = StateWithExtensions::<Token2022Account>::unpack(&data)?;
+    Ok(account.get_extension::<TransferFeeAmount>().map_or(0, |transfer_fee_amount| u64::from(transfer_fee_amount.withheld_amount)))
+}
+
+// This function calculates transfer fee withheld from the transferred amount
+// Params
+//   transfer_fee - Transfer fee of the mint
+//   amount - Transferred amount
+// Return
+//   transfer fee
+pub fn calculate_transfer_fee(transfer_fee: &Option<TransferFee>, amount: u64) -> u64 {
+    transfer_fee.map_or(0, |transfer_fee| transfer_fee.calculate_fee(amount).unwrap())
+}
+
+// This function calculates amount to transfer so that net_amount is received after transfer fee
+// Params
+//   transfer_fee - Transfer fee of the mint
+//   net_amount - Received amount
+// Return
+//   transferred amount
+pub fn calculate_pre_transfer_fee_amount(transfer_fee: &Option<TransferFee>, net_amount: u64) -> u64 {
+    transfer_fee.map_or(net_amount, |transfer_fee| transfer_fee.calculate_pre_fee_amount(net_amount).unwrap())
+}
+
+// This function computes presale allowlist leaf of a wallet
+// Params
+//   user - Allowlisted wallet address
+//   allocation - Maximum amount of base tokens the wallet can buy during presale
+// Return
+//   sha256(0x00 || user || allocation as little-endian u64)
+pub fn presale_leaf(user: &Pubkey, allocation: u64) -> [u8; 32] {
+    hashv(&[MERKLE_LEAF_PREFIX, user.as_ref(), &allocation.to_le_bytes()]).to_bytes()
+}
+
+// This function verifies Merkle proof (sibling pairs are sorted and hashed as sha256(0x01 || left || right))
+// Params
+//   proof - Sibling hashes from leaf to root
+//   root - Merkle root
+//   leaf - Leaf hash
+// Return
+//   true if proof is valid, else false
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed_root = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            hashv(&[MERKLE_NODE_PREFIX, &node, sibling]).to_bytes()
+        } else {
+            hashv(&[MERKLE_NODE_PREFIX, sibling, &node]).to_bytes()
+        }
+    });
+    computed_root.eq(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presale_proof_hashes_are_domain_separated() {
+        let (user, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (leaf, other_leaf) = (presale_leaf(&user, 1_000), presale_leaf(&other, 2_000));
+        assert_eq!(leaf, hashv(&[&[0x00], user.as_ref(), &1_000u64.to_le_bytes()]).to_bytes());
+
+        let (left, right) = if leaf <= other_leaf { (leaf, other_leaf) } else { (other_leaf, leaf) };
+        let root = hashv(&[&[0x01], &left, &right]).to_bytes();
+        assert!(verify_merkle_proof(&[other_leaf], &root, leaf));
+        assert!(verify_merkle_proof(&[leaf], &root, other_leaf));
+        assert!(!verify_merkle_proof(&[other_leaf], &root, presale_leaf(&user, 1_001)), "allocation must be bound to the leaf");
+
+        let unprefixed_root = hashv(&[&left, &right]).to_bytes();
+        assert!(!verify_merkle_proof(&[other_leaf], &unprefixed_root, leaf), "nodes must be hashed with node prefix");
+    }
+}
@@ -0,0 +1,7 @@
+pub const FEE_PER_DIV: u128 = 1000; // 1000 for 1%
+pub const SWAP_FEE: u64 = 300; // 0.3%
+
+pub const LP_DECIMALS: u8 = 9; // Decimals of LP token mint
+
+pub const PUMP_FUN_PROGRAM_ID: &str = "3gfMvGB7L2TrBw8CwaRzKZ9KySKrh4cFG23ZUFKoBN99"; // ali_pump_fun program, the only one allowed to create pools
+pub const PUMP_FUN_POOL_SEED: &[u8] = b"pool"; // Seed of ali_pump_fun PoolState, which deposits reserves on migration
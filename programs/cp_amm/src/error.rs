@@ -0,0 +1,19 @@
+use anchor_lang::prelude::error_code;
+
+#[error_code]
+pub enum AmmError {
+    #[msg("Invalid token pair")]
+    InvalidTokenPair,
+
+    #[msg("Base token amount must be greater than 0")]
+    WrongBaseAmount,
+    #[msg("Quote token amount must be greater than 0")]
+    WrongQuoteAmount,
+    #[msg("Input amount must be greater than 0")]
+    WrongInputAmount,
+
+    #[msg("Insufficient initial liquidity")]
+    InsufficientLiquidity,
+    #[msg("Too few output tokens")]
+    TooFewOutputTokens,
+}
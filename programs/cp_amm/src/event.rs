@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+// AmmPool initialization event
+#[event]
+pub struct PoolInitialized {
+    pub pool: Pubkey,           // AmmPool address
+    pub base_mint: Pubkey,      // Base token mint address
+    pub quote_mint: Pubkey,     // Quote token mint address
+    pub base_amount: u64,       // Deposited base token amount
+    pub quote_amount: u64,      // Deposited quote token amount
+    pub lp_amount: u64,         // Minted LP token amount
+    pub lp_recipient: Pubkey,   // Owner of minted LP tokens
+    pub timestamp: i64,         // Initialization time
+}
+
+// Swap event
+#[event]
+pub struct SwapEvent {
+    pub user: Pubkey,           // Trader wallet address
+    pub pool: Pubkey,           // AmmPool address
+    pub amount_in: u64,         // Input token amount
+    pub amount_out: u64,        // Output token amount
+    pub base_to_quote: bool,    // Flag indicating whether base tokens were swapped for quote tokens
+    pub base_reserves: u64,     // Updated base token reserves
+    pub quote_reserves: u64,    // Updated quote token reserves
+    pub timestamp: i64,         // Swap time
+}
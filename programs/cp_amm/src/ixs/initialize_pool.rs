@@ -0,0 +1,171 @@
+use crate::{
+    constants::{LP_DECIMALS, SWAP_FEE, PUMP_FUN_PROGRAM_ID, PUMP_FUN_POOL_SEED},
+    error::AmmError,
+    utils::integer_sqrt,
+    AmmPool, PoolInitialized,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, MintTo, Token, TokenAccount, Transfer},
+};
+use std::str::FromStr;
+
+// This function creates a new constant product pool and seeds it with initial liquidity
+//   Only ali_pump_fun PoolState of the same pair can deposit, so nobody can take the pool address ahead of migration
+// Params
+//   ctx - InitializePool context
+//   base_amount - Base token amount to deposit
+//   quote_amount - Quote token amount to deposit
+// Return
+//   Ok on success, ErrorCode on failure
+//     PoolInitialized is emitted on success
+pub fn initialize_pool(ctx: Context<AInitializePool>, base_amount: u64, quote_amount: u64) -> Result<()> {
+    require!(base_amount.gt(&0), AmmError::WrongBaseAmount);
+    require!(quote_amount.gt(&0), AmmError::WrongQuoteAmount);
+
+    let lp_amount = integer_sqrt((base_amount as u128) * (quote_amount as u128)) as u64;
+    require!(lp_amount.gt(&0), AmmError::InsufficientLiquidity);
+
+    let amm_pool = &mut ctx.accounts.amm_pool;
+    let authority = ctx.accounts.authority.to_account_info();
+    let token_program = ctx.accounts.token_program.to_account_info();
+
+    // Initialize all members of amm_pool
+    amm_pool.base_mint = ctx.accounts.base_mint.key();
+    amm_pool.quote_mint = ctx.accounts.quote_mint.key();
+    amm_pool.lp_mint = ctx.accounts.lp_mint.key();
+    amm_pool.base_vault = ctx.accounts.base_vault.key();
+    amm_pool.quote_vault = ctx.accounts.quote_vault.key();
+    amm_pool.fee = SWAP_FEE;
+
+    // Transfer base tokens from depositor to pool
+    let base_transfer_cpi_accounts = Transfer {
+        from: ctx.accounts.source_base_ata.to_account_info(),
+        to: ctx.accounts.base_vault.to_account_info(),
+        authority: authority.clone(),
+    };
+    token::transfer(CpiContext::new(token_program.clone(), base_transfer_cpi_accounts), base_amount)?;
+
+    // Transfer quote tokens from depositor to pool
+    let quote_transfer_cpi_accounts = Transfer {
+        from: ctx.accounts.source_quote_ata.to_account_info(),
+        to: ctx.accounts.quote_vault.to_account_info(),
+        authority: authority.clone(),
+    };
+    token::transfer(CpiContext::new(token_program.clone(), quote_transfer_cpi_accounts), quote_amount)?;
+
+    // Mint LP tokens to recipient
+    let mint_to_cpi_accounts = MintTo {
+        mint: ctx.accounts.lp_mint.to_account_info(),
+        to: ctx.accounts.recipient_lp_ata.to_account_info(),
+        authority: amm_pool.to_account_info(),
+    };
+    token::mint_to(CpiContext::new_with_signer(token_program.clone(), mint_to_cpi_accounts, &[&[
+        AmmPool::PREFIX_SEED,
+        amm_pool.base_mint.as_ref(),
+        amm_pool.quote_mint.as_ref(),
+        &[ctx.bumps["amm_pool"]]
+    ]]), lp_amount)?;
+
+    emit!(PoolInitialized {
+        pool: amm_pool.key(),
+        base_mint: amm_pool.base_mint,
+        quote_mint: amm_pool.quote_mint,
+        base_amount,
+        quote_amount,
+        lp_amount,
+        lp_recipient: ctx.accounts.lp_recipient.key(),
+        timestamp: Clock::get()?.unix_timestamp
+    });
+
+    Ok(())
+}
+
+// InitializePool context
+#[derive(Accounts)]
+pub struct AInitializePool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>, // Rent payer
+    #[account(
+        seeds = [
+            PUMP_FUN_POOL_SEED,
+            base_mint.key().as_ref(),
+            quote_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = Pubkey::from_str(PUMP_FUN_PROGRAM_ID).unwrap(),
+    )]
+    pub authority: Signer<'info>, // Owner of the deposited tokens (ali_pump_fun PoolState)
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            AmmPool::PREFIX_SEED,
+            base_mint.key().as_ref(),
+            quote_mint.key().as_ref(),
+        ],
+        bump,
+        space = 8 + AmmPool::MAX_SIZE
+    )]
+    pub amm_pool: Box<Account<'info, AmmPool>>, // (New) AmmPool account
+
+    #[account(
+        constraint = base_mint.key() != quote_mint.key() @ AmmError::InvalidTokenPair
+    )]
+    pub base_mint: Box<Account<'info, Mint>>, // Base token mint account
+    pub quote_mint: Box<Account<'info, Mint>>, // Quote token mint account
+    #[account(
+        init,
+        payer = payer,
+        seeds = [AmmPool::LP_MINT_SEED, amm_pool.key().as_ref()],
+        bump,
+        mint::decimals = LP_DECIMALS,
+        mint::authority = amm_pool,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>, // (New) LP token mint account
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = base_mint,
+        associated_token::authority = amm_pool,
+    )]
+    pub base_vault: Box<Account<'info, TokenAccount>>, // AmmPool's base token ATA
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = amm_pool,
+    )]
+    pub quote_vault: Box<Account<'info, TokenAccount>>, // AmmPool's quote token ATA
+
+    #[account(
+        mut,
+        token::mint = base_mint,
+        token::authority = authority,
+    )]
+    pub source_base_ata: Box<Account<'info, TokenAccount>>, // Depositor's base token account
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = authority,
+    )]
+    pub source_quote_ata: Box<Account<'info, TokenAccount>>, // Depositor's quote token account
+
+    /// CHECK: any address is allowed to own the initial LP tokens
+    pub lp_recipient: AccountInfo<'info>, // Owner of the minted LP tokens
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = lp_mint,
+        associated_token::authority = lp_recipient,
+    )]
+    pub recipient_lp_ata: Box<Account<'info, TokenAccount>>, // Recipient's LP token ATA
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
@@ -0,0 +1,5 @@
+pub mod initialize_pool;
+pub use initialize_pool::*;
+
+pub mod swap;
+pub use swap::*;
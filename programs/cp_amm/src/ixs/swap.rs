@@ -0,0 +1,120 @@
+use crate::{
+    error::AmmError,
+    utils::calculate_swap_output,
+    AmmPool, SwapEvent,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount, Transfer},
+};
+
+// This function swaps tokens on the constant product pool
+// Params
+//   ctx - Swap context
+//   amount_in - Amount of input tokens
+//   min_amount_out - Minimum amount of output tokens to receive
+//   base_to_quote - true to swap base tokens for quote tokens, false for the opposite direction
+// Return
+//   Ok on success, ErrorCode on failure
+pub fn swap(ctx: Context<ASwap>, amount_in: u64, min_amount_out: u64, base_to_quote: bool) -> Result<()> {
+    require!(amount_in.gt(&0), AmmError::WrongInputAmount);
+
+    let amm_pool = &ctx.accounts.amm_pool;
+    let user = ctx.accounts.user.to_account_info();
+    let token_program = ctx.accounts.token_program.to_account_info();
+
+    let (user_in_ata, user_out_ata, vault_in, vault_out) = if base_to_quote {
+        (&ctx.accounts.user_base_ata, &ctx.accounts.user_quote_ata, &ctx.accounts.base_vault, &ctx.accounts.quote_vault)
+    } else {
+        (&ctx.accounts.user_quote_ata, &ctx.accounts.user_base_ata, &ctx.accounts.quote_vault, &ctx.accounts.base_vault)
+    };
+
+    let amount_out = calculate_swap_output(amm_pool.fee, amount_in, vault_in.amount, vault_out.amount);
+    require!(amount_out.gt(&0) && amount_out >= min_amount_out, AmmError::TooFewOutputTokens);
+
+    // Transfer input tokens from user to pool
+    let input_transfer_cpi_accounts = Transfer {
+        from: user_in_ata.to_account_info(),
+        to: vault_in.to_account_info(),
+        authority: user.clone(),
+    };
+    token::transfer(CpiContext::new(token_program.clone(), input_transfer_cpi_accounts), amount_in)?;
+
+    // Transfer output tokens from pool to user
+    let output_transfer_cpi_accounts = Transfer {
+        from: vault_out.to_account_info(),
+        to: user_out_ata.to_account_info(),
+        authority: amm_pool.to_account_info(),
+    };
+    token::transfer(CpiContext::new_with_signer(token_program.clone(), output_transfer_cpi_accounts, &[&[
+        AmmPool::PREFIX_SEED,
+        amm_pool.base_mint.as_ref(),
+        amm_pool.quote_mint.as_ref(),
+        &[ctx.bumps["amm_pool"]]
+    ]]), amount_out)?;
+
+    ctx.accounts.base_vault.reload()?;
+    ctx.accounts.quote_vault.reload()?;
+
+    emit!(SwapEvent {
+        user: user.key(),
+        pool: ctx.accounts.amm_pool.key(),
+        amount_in,
+        amount_out,
+        base_to_quote,
+        base_reserves: ctx.accounts.base_vault.amount,
+        quote_reserves: ctx.accounts.quote_vault.amount,
+        timestamp: Clock::get()?.unix_timestamp
+    });
+
+    Ok(())
+}
+
+// Swap context
+#[derive(Accounts)]
+pub struct ASwap<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>, // Trader
+
+    #[account(
+        seeds = [
+            AmmPool::PREFIX_SEED,
+            base_mint.key().as_ref(),
+            quote_mint.key().as_ref(),
+        ],
+        bump,
+        has_one = base_mint,
+        has_one = quote_mint,
+        has_one = base_vault,
+        has_one = quote_vault,
+    )]
+    pub amm_pool: Box<Account<'info, AmmPool>>, // AmmPool account
+
+    pub base_mint: Box<Account<'info, Mint>>, // Base token mint account
+    pub quote_mint: Box<Account<'info, Mint>>, // Quote token mint account
+
+    #[account(mut)]
+    pub base_vault: Box<Account<'info, TokenAccount>>, // AmmPool's base token ATA
+    #[account(mut)]
+    pub quote_vault: Box<Account<'info, TokenAccount>>, // AmmPool's quote token ATA
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = base_mint,
+        associated_token::authority = user,
+    )]
+    pub user_base_ata: Box<Account<'info, TokenAccount>>, // Trader's base token ATA
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = quote_mint,
+        associated_token::authority = user,
+    )]
+    pub user_quote_ata: Box<Account<'info, TokenAccount>>, // Trader's quote token ATA
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
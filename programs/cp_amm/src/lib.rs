@@ -0,0 +1,30 @@
+#![allow(unused)]
+
+use anchor_lang::prelude::*;
+
+pub mod ixs;
+pub mod state;
+pub mod event;
+
+pub mod constants;
+pub mod error;
+pub mod utils;
+
+use ixs::*;
+use state::*;
+use event::*;
+
+declare_id!("9v3nY7JNeKDFtxzT4jWmQbLJVgK3wZpcx2MsXiqNT8X7");
+
+#[program]
+pub mod cp_amm {
+    use super::*;
+
+    pub fn initialize_pool(ctx: Context<AInitializePool>, base_amount: u64, quote_amount: u64) -> Result<()> {
+        ixs::initialize_pool(ctx, base_amount, quote_amount)
+    }
+
+    pub fn swap(ctx: Context<ASwap>, amount_in: u64, min_amount_out: u64, base_to_quote: bool) -> Result<()> {
+        ixs::swap(ctx, amount_in, min_amount_out, base_to_quote)
+    }
+}
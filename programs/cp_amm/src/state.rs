@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+// Constant product pool struct
+#[account]
+pub struct AmmPool {
+    pub base_mint: Pubkey,      // Base token mint address
+    pub quote_mint: Pubkey,     // Quote token mint address
+    pub lp_mint: Pubkey,        // LP token mint address
+    pub base_vault: Pubkey,     // AmmPool's base token ATA
+    pub quote_vault: Pubkey,    // AmmPool's quote token ATA
+    pub fee: u64,               // Swap fee (in FEE_PER_DIV units)
+}
+
+impl AmmPool {
+    pub const MAX_SIZE: usize = std::mem::size_of::<Self>();    // Size of AmmPool
+    pub const PREFIX_SEED: &'static [u8] = b"amm_pool";         // Seed of AmmPool
+    pub const LP_MINT_SEED: &'static [u8] = b"lp_mint";         // Seed of LP token mint
+}
@@ -0,0 +1,41 @@
+use crate::constants::FEE_PER_DIV;
+
+// This function calculates integer square root (rounded down)
+// Params
+//   value - Input value
+// Return
+//   floor(sqrt(value))
+pub fn integer_sqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = x / 2 + 1;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+// This function calculates swap output amount by using constant product formula
+// Params
+//   fee - Swap fee
+//   input_amount - Input amount
+//   input_reserve - Input reserve
+//   output_reserve - Output reserve
+// Return
+//   output_amount - Output amount
+//     output_amount = output_reserve * input_after_fee / (input_reserve + input_after_fee)
+pub fn calculate_swap_output(fee: u64, input_amount: u64, input_reserve: u64, output_reserve: u64) -> u64 {
+    let input_after_fee = (input_amount as u128)
+        .checked_mul(FEE_PER_DIV * 100 - fee as u128)
+        .unwrap()
+        .checked_div(FEE_PER_DIV * 100)
+        .unwrap();
+    (output_reserve as u128)
+        .checked_mul(input_after_fee)
+        .unwrap()
+        .checked_div((input_reserve as u128) + input_after_fee)
+        .unwrap() as u64
+}
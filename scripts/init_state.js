@@ -61,6 +61,22 @@ async function main() {
 	};
 	const tx1 = await program.methods.initMainState().accounts(ctx).instruction();
 	transaction.add(tx1);
+	if(programName === "ali_pump_fun") {
+		// whitelist the quote token with MainState's reserves/threshold/fee (transaction 1a)
+		const [quoteConfigKey] = await asyncGetPda([Buffer.from("quote_config"), quoteMint.toBuffer()], programIdPubKey);
+		const tx1a = await program.methods.addQuoteMint({
+			initVirtQuoteReserves: null,
+			realQuoteThreshold: null,
+			tradingFee: null,
+		}).accounts({ // struct AAddQuoteMint<'info>
+			owner: deployerKey.publicKey,
+			mainState: new PublicKey(mainStateKey),
+			quoteMint: quoteMint,
+			quoteConfig: quoteConfigKey,
+			systemProgram: SystemProgram.programId,
+		}).instruction();
+		transaction.add(tx1a);
+	}
 	// priority fee (transaction 2)
 	transaction.add(ComputeBudgetProgram.setComputeUnitPrice({microLamports: 100_000}));
 
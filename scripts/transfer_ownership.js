@@ -18,7 +18,11 @@ const jsonRpcEndpoint = load_json_rpc_endpoint_from_env();
 const programIdPubKey = load_program_id_param();
 const programName = load_program_name_param();
 const idl = require(`../target/idl/${programName}.json`);
-const newOwnerPubKey = function() {
+// --accept: run by the proposed owner to accept the ownership
+// --cancel: run by the current owner to cancel the pending proposal
+const acceptOwnership = process.argv.includes("--accept");
+const cancelProposal = process.argv.includes("--cancel");
+const newOwnerPubKey = acceptOwnership || cancelProposal? null: function() {
 	// Find the index of --new_owner
 	const newOwnerIndex = process.argv.indexOf("--new_owner");
 
@@ -52,15 +56,33 @@ async function main() {
 	const program = new anchor.Program(idl, programIdPubKey, provider);
 	const [mainStateKey] = await asyncGetPda([Buffer.from(MAIN_STATE_PREFIX_SEED)], programIdPubKey);
 	console.log("mainStateKey: %s", mainStateKey);
-	const ctx = { // struct ATransferOwnership<'info>
+	if(acceptOwnership) {
+		const ctx = { // struct AAcceptOwnership<'info>
+			accounts: {
+				pendingOwner: deployerKey.publicKey,
+				mainState: new PublicKey(mainStateKey),
+			},
+			signers: [deployerKey],
+		};
+		const tx = await program.rpc.acceptOwnership(ctx);
+		console.log("Ownership accepted by %s: %o", deployerKey.publicKey, tx);
+		return;
+	}
+
+	const ctx = { // struct AProposeOwner<'info>
 		accounts: {
 			owner: deployerKey.publicKey,
 			mainState: new PublicKey(mainStateKey),
 		},
 		signers: [deployerKey],
 	};
-	const tx = await program.rpc.transferOwnership(newOwnerPubKey, ctx);
-	console.log("Ownership transferred to %s: %o", newOwnerPubKey, tx);
+	if(cancelProposal) {
+		const tx = await program.rpc.cancelOwnershipProposal(ctx);
+		console.log("Ownership proposal cancelled: %o", tx);
+		return;
+	}
+	const tx = await program.rpc.proposeOwner(newOwnerPubKey, ctx);
+	console.log("Ownership proposed to %s (must be accepted with --accept): %o", newOwnerPubKey, tx);
 }
 
 const asyncGetPda = async(seeds, programId) => {
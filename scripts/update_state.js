@@ -18,7 +18,11 @@ const jsonRpcEndpoint = load_json_rpc_endpoint_from_env();
 const programIdPubKey = load_program_id_param();
 const programName = load_program_name_param();
 const idl = require(`../target/idl/${programName}.json`);
-const withdrawalManagerPubKey = function() {
+// --execute: apply the queued update once its ETA has passed
+// --cancel: drop the queued update
+const executeUpdate = process.argv.includes("--execute");
+const cancelUpdate = process.argv.includes("--cancel");
+const withdrawalManagerPubKey = executeUpdate || cancelUpdate? null: function() {
 	// Find the index of --withdrawal_manager
 	const withdrawalManagerIndex = process.argv.indexOf("--withdrawal_manager");
 
@@ -32,7 +36,7 @@ const withdrawalManagerPubKey = function() {
 	console.log("Withdrawal Manager: %o", withdrawalManager);
 	return new PublicKey(withdrawalManager);
 }();
-const feeRecipientPubKey = function() {
+const feeRecipientPubKey = executeUpdate || cancelUpdate? null: function() {
 	// Find the index of --fee_recipient
 	const feeRecipientIndex = process.argv.indexOf("--fee_recipient");
 
@@ -59,12 +63,15 @@ async function main() {
 		{commitment: "processed"}
 	);
 	const MAIN_STATE_PREFIX_SEED = "main";
+	const PENDING_MAIN_UPDATE_PREFIX_SEED = "pending_main_update";
 	anchor.setProvider(provider);
 
 	// Create the program interface
 	const program = new anchor.Program(idl, programIdPubKey, provider);
 	const [mainStateKey] = await asyncGetPda([Buffer.from(MAIN_STATE_PREFIX_SEED)], programIdPubKey);
 	console.log("mainStateKey: %s", mainStateKey);
+	const [pendingUpdateKey] = await asyncGetPda([Buffer.from(PENDING_MAIN_UPDATE_PREFIX_SEED)], programIdPubKey);
+	console.log("pendingUpdateKey: %s", pendingUpdateKey);
 
 	// prepare and send bundled transaction with the priority fee
 	const {blockhash} = await connection.getLatestBlockhash();
@@ -78,21 +85,46 @@ async function main() {
 	console.log("quoteMint: %s", quoteMint);
 
 	// transaction payload (transaction 1)
-	const updateMainStateInput = Object.assign({
-		withdrawer: withdrawalManagerPubKey,
-		feeRecipient: feeRecipientPubKey,
-	}, get_main_state_defaults(programName));
-	const ctx = { // struct AInitMainState<'info>
-		owner: deployerKey.publicKey,
-		mainState: new PublicKey(mainStateKey),
-		quoteMint: quoteMint,
-		feeRecipient: feeRecipientPubKey,
-		feeQuoteAta: getAssociatedTokenAddressSync(quoteMint, feeRecipientPubKey),
-		associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-		tokenProgram: TOKEN_PROGRAM_ID,
-		systemProgram: SystemProgram.programId,
-	};
-	const tx1 = await program.methods.updateMainState(updateMainStateInput).accounts(ctx).instruction();
+	let ctx, updateMainStateInput, tx1;
+	if(executeUpdate) {
+		const pendingUpdate = await program.account.pendingMainStateUpdate.fetch(pendingUpdateKey);
+		updateMainStateInput = pendingUpdate.input;
+		console.log("queued update ETA: %o", new Date(pendingUpdate.eta.toNumber() * 1000));
+		ctx = { // struct AExecuteMainStateUpdate<'info>
+			owner: deployerKey.publicKey,
+			mainState: new PublicKey(mainStateKey),
+			pendingUpdate: new PublicKey(pendingUpdateKey),
+			quoteConfig: (await asyncGetPda([Buffer.from("quote_config"), quoteMint.toBuffer()], programIdPubKey))[0],
+			quoteMint: quoteMint,
+			feeRecipient: updateMainStateInput.feeRecipient,
+			feeQuoteAta: getAssociatedTokenAddressSync(quoteMint, updateMainStateInput.feeRecipient),
+			associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+			tokenProgram: TOKEN_PROGRAM_ID,
+			systemProgram: SystemProgram.programId,
+		};
+		tx1 = await program.methods.executeMainStateUpdate().accounts(ctx).instruction();
+	}
+	else if(cancelUpdate) {
+		ctx = { // struct ACancelMainStateUpdate<'info>
+			owner: deployerKey.publicKey,
+			mainState: new PublicKey(mainStateKey),
+			pendingUpdate: new PublicKey(pendingUpdateKey),
+		};
+		tx1 = await program.methods.cancelMainStateUpdate().accounts(ctx).instruction();
+	}
+	else {
+		updateMainStateInput = Object.assign({
+			withdrawer: withdrawalManagerPubKey,
+			feeRecipient: feeRecipientPubKey,
+		}, get_main_state_defaults(programName));
+		ctx = { // struct AQueueMainStateUpdate<'info>
+			owner: deployerKey.publicKey,
+			mainState: new PublicKey(mainStateKey),
+			pendingUpdate: new PublicKey(pendingUpdateKey),
+			systemProgram: SystemProgram.programId,
+		};
+		tx1 = await program.methods.queueMainStateUpdate(updateMainStateInput).accounts(ctx).instruction();
+	}
 	transaction.add(tx1);
 	// priority fee (transaction 2)
 	transaction.add(ComputeBudgetProgram.setComputeUnitPrice({microLamports: 100_000}));
@@ -100,8 +132,8 @@ async function main() {
 	// execute the bundle and wait for confirmation
 	const signature = await connection.sendTransaction(transaction, [deployerKey]);
 	await connection.confirmTransaction(signature, "confirmed");
-	console.log("update main state complete: %o", signature);
-	console.log(print_obj(ctx), print_obj(updateMainStateInput));
+	console.log("%s main state update complete: %o", executeUpdate? "execute": cancelUpdate? "cancel": "queue", signature);
+	console.log(print_obj(ctx), updateMainStateInput? print_obj(updateMainStateInput): "");
 }
 
 const asyncGetPda = async(seeds, programId) => {
@@ -94,6 +94,19 @@ function get_main_state_defaults(program_name) {
 			initVirtBaseReserves: new anchor.BN(66_666_666_000_000), // ~6.6666666% of total supply
 			initVirtQuoteReserves: new anchor.BN(100_000_00_000_000), // 100k ALI
 			realQuoteThreshold: new anchor.BN(300_000_00_000_000), // +300k ALI
+			updateDelay: null, // keep MainState update delay unchanged
+			allowedCurves: null, // keep allowed curve types unchanged
+			creatorFeeShare: null, // keep creator share of trading fee unchanged
+			referralFeeShare: null, // keep referrer share of trading fee unchanged
+			antiSnipeWindow: null, // keep anti-snipe window unchanged
+			antiSnipeWindowUnit: null, // keep anti-snipe window unit unchanged
+			maxBuyPerWallet: null, // keep per-wallet buy limit unchanged
+			withdrawalPolicy: null, // keep withdrawal policy unchanged
+			unsoldBaseAction: null, // keep unsold base tokens action unchanged
+			vestingDuration: null, // keep locked unsold base tokens vesting duration unchanged
+			creatorCompletionReward: null, // keep creator completion reward unchanged
+			trackPositions: null, // keep user position tracking unchanged
+			pauser: null, // keep pauser unchanged
 		};
 		case "pump_fun": return {
 			tradingFee: new anchor.BN(1000), // 1%
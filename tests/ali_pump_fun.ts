@@ -2,18 +2,23 @@ import * as anchor from "@coral-xyz/anchor";
 import { web3 } from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { AliPumpFun } from "../target/types/ali_pump_fun";
+import { CpAmm } from "../target/types/cp_amm";
 import { Connectivity } from "./connectivity/ali_index";
-import { createToken, transferToken } from "./helper";
-import { sleep } from "./connectivity/utils";
+import { createToken, createTokenAndPool, getTokenBalance, transferToken } from "./helper";
+import { buildPresaleMerkleTree, sleep } from "./connectivity/utils";
 import { assert } from "chai";
+import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID, createTransferInstruction, getAssociatedTokenAddressSync, getMint } from "@solana/spl-token";
+import BN from "bn.js";
 
 const log = console.log
 let quoteToken: any = null
+let fastQuoteToken: any = null // Quote token with low threshold, pools quoted in it complete quickly
 
 describe("ali_pump_fun", () => {
   const provider = anchor.AnchorProvider.env()
   anchor.setProvider(provider);
   const program = anchor.workspace.AliPumpFun as Program<AliPumpFun>;
+  const ammProgram = anchor.workspace.CpAmm as Program<CpAmm>;
   const connectivity = new Connectivity({ programId: program.programId, rpcEndPoint: provider.connection.rpcEndpoint, walletInfo: provider })
   
   const creatorAuthority = web3.Keypair.generate()
@@ -56,6 +61,14 @@ describe("ali_pump_fun", () => {
     if (!mainStateInfo) {
       await connectivity.initMainState({ quoteToken })
     }
+    await connectivity.addQuoteMint({ quoteToken })
+
+    const fastQuoteTokenTxInfo = await createToken({ decimals: 8, supply: 1_000_000 }, creatorProvider)
+    fastQuoteToken = fastQuoteTokenTxInfo.mint.toBase58()
+    await sleep(3000)
+    await transferToken({ to: user.toBase58(), mint: fastQuoteToken, amount: 400_000 }, creatorProvider)
+    // 30k virtual reserves, complete at +1k
+    await connectivity.addQuoteMint({ quoteToken: fastQuoteToken, initVirtQuoteReserves: 30_000_00_000_000, realQuoteThreshold: 1_000_00_000_000 })
     
     const createTokenTxInfo = await createToken({ decimals: 6, supply: 1_000_000_000 }, creatorProvider)
     commonState.mint = createTokenTxInfo.mint.toBase58()
@@ -66,6 +79,17 @@ describe("ali_pump_fun", () => {
     // })
   })
 
+  // Creates pool quoted in fastQuoteToken and buys it up to the threshold
+  async function createCompletePool() {
+    const { baseToken, res } = await createTokenAndPool({ quoteToken: fastQuoteToken }, creatorConnectivity, creatorProvider)
+    if (!res.Ok) throw "tx failed"
+    const poolId = res.Ok.poolId
+    const buyRes = await userConnectivity.buy({ poolId, amount: 2_000 }) // clipped to the threshold, completes the curve
+    if (!buyRes.Ok) throw "buy failed"
+    await sleep(1_000)
+    return { baseToken, poolId }
+  }
+
   it("create pool", async () => {
     const baseToken = commonState.mint
     if (!baseToken) throw "Base token not found"
@@ -81,6 +105,483 @@ describe("ali_pump_fun", () => {
     commonState.poolId = poolId
   });
 
+  it("launch token", async () => {
+    const res = await creatorConnectivity.launchToken({ name: "Launch Token", symbol: "LAUNCH", uri: "https://example.com/launch.json", decimals: 6, quoteToken })
+    if (!res.Ok) throw "tx failed"
+    await sleep(1_000)
+    const mintInfo = await getMint(connection, new web3.PublicKey(res.Ok.baseToken))
+    assert.isNull(mintInfo.mintAuthority, "mint authority must be revoked")
+    assert.isNull(mintInfo.freezeAuthority, "freeze authority must not be set")
+    const poolInfo = await program.account.poolState.fetch(res.Ok.poolId)
+    assert.equal(mintInfo.supply.toString(), poolInfo.realBaseReserves.toString(), "total supply must be in the pool")
+    assert.isNotNull(await connection.getAccountInfo(creatorConnectivity.pdas.getMetadataAccount(mintInfo.address)), "metadata must be created")
+  });
+
+  it("buy with referrer", async () => {
+    const poolId = commonState.poolId
+    if (!poolId) throw "pool id not found"
+    const referrer = web3.Keypair.generate().publicKey
+    await transferToken({ to: referrer.toBase58(), mint: quoteToken, amount: 1 }, creatorProvider) // creates referrer's quote ATA
+    await sleep(1_000)
+    const quote = await userConnectivity.quoteTrade({ kind: 'buyExactQuote', amount: new BN(1_000_00_000_000), poolId })
+    if (!quote.Ok) throw "quote failed"
+    const buyRes = await userConnectivity.buy({ poolId, amount: 1_000, referrer: referrer.toBase58() })
+    if (!buyRes.Ok) throw "buy failed"
+    await sleep(1_000)
+
+    const mainStateInfo = await program.account.mainState.fetch(connectivity.pdas.mainState)
+    const referrerStatsInfo = await program.account.referrerStats.fetch(userConnectivity.pdas.getReferrerStatsAccount(referrer))
+    const { fee, inputAmount } = quote.Ok.tradeQuote
+    assert.equal(referrerStatsInfo.tradeCount.toNumber(), 1)
+    assert.equal(referrerStatsInfo.volume.toString(), inputAmount.toString(), "volume must include trading fee")
+    assert.equal(referrerStatsInfo.earnings.toString(), fee.mul(mainStateInfo.referralFeeShare).divn(100 * 1000).toString(), "referrer must earn its share of trading fee")
+
+    const selfReferralRes = await userConnectivity.buy({ poolId, amount: 1_000, referrer: user.toBase58() })
+    assert.isDefined(selfReferralRes.Err, "trader must not refer itself")
+  });
+
+  it("quote trade previews", async () => {
+    const poolId = commonState.poolId
+    if (!poolId) throw "pool id not found"
+    const baseAta = getAssociatedTokenAddressSync(new web3.PublicKey(commonState.mint as string), user)
+    const quoteAta = getAssociatedTokenAddressSync(new web3.PublicKey(quoteToken), user)
+
+    const buyQuote = await userConnectivity.quoteTrade({ kind: 'buyExactQuote', amount: new BN(500_00_000_000), poolId })
+    if (!buyQuote.Ok) throw "quote failed"
+    const baseBefore = await getTokenBalance(connection, baseAta)
+    if (!(await userConnectivity.buy({ poolId, amount: 500 })).Ok) throw "buy failed"
+    await sleep(1_000)
+    assert.equal((await getTokenBalance(connection, baseAta)).sub(baseBefore).toString(), buyQuote.Ok.tradeQuote.outputAmount.toString(), "buy must match its preview")
+
+    const sellAmount = buyQuote.Ok.tradeQuote.outputAmount.divn(1_000_000).muln(1_000_000) // Whole tokens
+    const sellQuote = await userConnectivity.quoteTrade({ kind: 'sell', amount: sellAmount, poolId })
+    if (!sellQuote.Ok) throw "quote failed"
+    const quoteBefore = await getTokenBalance(connection, quoteAta)
+    if (!(await userConnectivity.sell({ poolId, amount: sellAmount.toNumber() / 1_000_000 })).Ok) throw "sell failed"
+    await sleep(1_000)
+    assert.equal((await getTokenBalance(connection, quoteAta)).sub(quoteBefore).toString(), sellQuote.Ok.tradeQuote.outputAmount.toString(), "sell must match its preview")
+
+    const zeroQuote = await userConnectivity.quoteTrade({ kind: 'buyExactQuote', amount: new BN(0), poolId })
+    assert.isDefined(zeroQuote.Err, "preview must reject zero amount like the trade does")
+  });
+
+  it("claim creator fees", async () => {
+    const { res } = await createTokenAndPool({ quoteToken }, creatorConnectivity, creatorProvider)
+    if (!res.Ok) throw "tx failed"
+    const poolId = res.Ok.poolId
+    const creatorFeeVault = creatorConnectivity.pdas.getCreatorFeeVaultAccount(new web3.PublicKey(poolId))
+    const creatorQuoteAta = getAssociatedTokenAddressSync(new web3.PublicKey(quoteToken), creator)
+
+    // creator_fee_share is 0 by default, whole trading fee goes to fee recipient
+    if (!(await userConnectivity.buy({ poolId, amount: 1_000 })).Ok) throw "buy failed"
+    await sleep(1_000)
+    assert.isTrue((await getTokenBalance(connection, creatorFeeVault)).isZero(), "creator must earn nothing without creator fee share")
+    const emptyClaimRes = await creatorConnectivity.claimCreatorFees({ poolId })
+    assert.isDefined(emptyClaimRes.Err, "claim must fail with nothing to claim")
+
+    // Fund the vault directly as the timelocked creator fee share can't be raised within tests
+    const fees = new BN(10_00_000_000)
+    await creatorProvider.sendAndConfirm(new web3.Transaction().add(
+      createTransferInstruction(creatorQuoteAta, creatorFeeVault, creator, BigInt(fees.toString()))
+    ))
+    const userClaimRes = await userConnectivity.claimCreatorFees({ poolId })
+    assert.isDefined(userClaimRes.Err, "only pool creator can claim creator fees")
+    const creatorQuoteBefore = await getTokenBalance(connection, creatorQuoteAta)
+    if (!(await creatorConnectivity.claimCreatorFees({ poolId })).Ok) throw "claim creator fees failed"
+    await sleep(1_000)
+    assert.equal((await getTokenBalance(connection, creatorQuoteAta)).sub(creatorQuoteBefore).toString(), fees.toString(), "creator must receive vault balance")
+    assert.isTrue((await getTokenBalance(connection, creatorFeeVault)).isZero(), "vault must be drained")
+  });
+
+  it("pause trading", async () => {
+    const poolId = commonState.poolId
+    if (!poolId) throw "pool id not found"
+    const userPauseRes = await userConnectivity.setGlobalPause(true)
+    assert.isDefined(userPauseRes.Err, "only pauser can pause the program")
+
+    if (!(await connectivity.setGlobalPause(true)).Ok) throw "global pause failed"
+    const globalPausedBuyRes = await userConnectivity.buy({ poolId, amount: 100 })
+    assert.isDefined(globalPausedBuyRes.Err, "buy must fail while program is paused")
+    if (!(await connectivity.setGlobalPause(false)).Ok) throw "global unpause failed"
+
+    const userPoolPauseRes = await userConnectivity.setPoolPause({ poolId, paused: true })
+    assert.isDefined(userPoolPauseRes.Err, "only pauser can pause the pool")
+    if (!(await connectivity.setPoolPause({ poolId, paused: true })).Ok) throw "pool pause failed"
+    await sleep(1_000)
+    assert.isTrue((await program.account.poolState.fetch(poolId)).paused, "pool must be paused")
+    const poolPausedSellRes = await userConnectivity.sell({ poolId, amount: 1 })
+    assert.isDefined(poolPausedSellRes.Err, "sell must fail while pool is paused")
+    if (!(await connectivity.setPoolPause({ poolId, paused: false })).Ok) throw "pool unpause failed"
+
+    if (!(await userConnectivity.buy({ poolId, amount: 100 })).Ok) throw "buy must succeed once unpaused"
+  });
+
+  it("timelocked main state update", async () => {
+    const pendingUpdate = connectivity.pdas.pendingMainStateUpdate
+    const mainStateBefore = await program.account.mainState.fetch(connectivity.pdas.mainState)
+    const userQueueRes = await userConnectivity.queueMainStateUpdate({ newTradingFee: 2, quoteToken })
+    assert.isDefined(userQueueRes.Err, "only owner can queue update")
+
+    const nowTs = Math.trunc(Date.now() / 1000)
+    if (!(await connectivity.queueMainStateUpdate({ newTradingFee: 2, quoteToken })).Ok) throw "queue update failed"
+    await sleep(1_000)
+    const pendingUpdateInfo = await program.account.pendingMainStateUpdate.fetch(pendingUpdate)
+    assert.isTrue(pendingUpdateInfo.eta.gten(nowTs + mainStateBefore.updateDelay.toNumber() - 60), "update must wait for update delay")
+    const secondQueueRes = await connectivity.queueMainStateUpdate({ newTradingFee: 3, quoteToken })
+    assert.isDefined(secondQueueRes.Err, "only one update can be queued at a time")
+
+    const executeRes = await connectivity.executeMainStateUpdate({ quoteToken })
+    assert.isDefined(executeRes.Err, "update must not execute before its eta")
+    const mainStateAfter = await program.account.mainState.fetch(connectivity.pdas.mainState)
+    assert.equal(mainStateAfter.tradingFee.toString(), mainStateBefore.tradingFee.toString(), "trading fee must not change before eta")
+
+    const userCancelRes = await userConnectivity.cancelMainStateUpdate()
+    assert.isDefined(userCancelRes.Err, "only owner can cancel update")
+    if (!(await connectivity.cancelMainStateUpdate()).Ok) throw "cancel update failed"
+    await sleep(1_000)
+    assert.isNull(await connection.getAccountInfo(pendingUpdate), "queued update must be removed")
+  });
+
+  it("whitelist quote mints", async () => {
+    const newQuoteTokenTxInfo = await createToken({ decimals: 6, supply: 1_000_000 }, creatorProvider)
+    const newQuoteToken = newQuoteTokenTxInfo.mint.toBase58()
+    const createBaseToken = async () => (await createToken({ decimals: 6, supply: 1_000_000_000 }, creatorProvider)).mint.toBase58()
+    const baseToken = await createBaseToken()
+    await sleep(3_000)
+    const unlistedRes = await creatorConnectivity.createPool({ baseToken, quoteToken: newQuoteToken })
+    assert.isDefined(unlistedRes.Err, "pool must not be created against unlisted quote mint")
+    const userAddRes = await userConnectivity.addQuoteMint({ quoteToken: newQuoteToken })
+    assert.isDefined(userAddRes.Err, "only owner can whitelist quote mint")
+
+    if (!(await connectivity.addQuoteMint({ quoteToken: newQuoteToken, initVirtQuoteReserves: 5_000_000_000, realQuoteThreshold: 20_000_000_000 })).Ok) throw "add quote mint failed"
+    await sleep(1_000)
+    const res = await creatorConnectivity.createPool({ baseToken, quoteToken: newQuoteToken })
+    if (!res.Ok) throw "tx failed"
+    await sleep(1_000)
+    const poolInfo = await program.account.poolState.fetch(res.Ok.poolId)
+    assert.equal(poolInfo.virtQuoteReserves.toNumber(), 5_000_000_000, "pool must take reserves of the quote config")
+    assert.equal(poolInfo.realQuoteThreshold.toNumber(), 20_000_000_000, "pool must take threshold of the quote config")
+
+    if (!(await connectivity.removeQuoteMint({ quoteToken: newQuoteToken })).Ok) throw "remove quote mint failed"
+    await sleep(1_000)
+    const removedRes = await creatorConnectivity.createPool({ baseToken: await createBaseToken(), quoteToken: newQuoteToken })
+    assert.isDefined(removedRes.Err, "pool must not be created against removed quote mint")
+  });
+
+  it("anti-snipe settings", async () => {
+    const slotBefore = await connection.getSlot()
+    const { res } = await createTokenAndPool({ quoteToken }, creatorConnectivity, creatorProvider)
+    if (!res.Ok) throw "tx failed"
+    await sleep(1_000)
+    const poolInfo = await program.account.poolState.fetch(res.Ok.poolId)
+    assert.isTrue(poolInfo.createdSlot.gten(slotBefore) && poolInfo.createdAt.gtn(0), "pool must store its creation slot and time")
+
+    // Window must come with a buy limit and stay bounded, settings are checked on queueing
+    const noLimitRes = await connectivity.queueMainStateUpdate({ newAntiSnipeWindow: 10, newMaxBuyPerWallet: 0, quoteToken })
+    assert.isDefined(noLimitRes.Err, "anti-snipe window must have a buy limit")
+    const unboundedRes = await connectivity.queueMainStateUpdate({ newAntiSnipeWindow: 1_000_000_000, newAntiSnipeWindowUnit: 'seconds', newMaxBuyPerWallet: 1_000_000, quoteToken })
+    assert.isDefined(unboundedRes.Err, "anti-snipe window must be bounded")
+
+    if (!(await connectivity.queueMainStateUpdate({ newAntiSnipeWindow: 10, newAntiSnipeWindowUnit: 'slots', newMaxBuyPerWallet: 1_000_000, quoteToken })).Ok) throw "queue update failed"
+    await sleep(1_000)
+    const pendingUpdateInfo = await program.account.pendingMainStateUpdate.fetch(connectivity.pdas.pendingMainStateUpdate)
+    assert.equal(pendingUpdateInfo.input.antiSnipeWindow?.toNumber(), 10)
+    assert.equal(pendingUpdateInfo.input.maxBuyPerWallet?.toNumber(), 1_000_000)
+    if (!(await connectivity.cancelMainStateUpdate()).Ok) throw "cancel update failed"
+  });
+
+  it("track user position", async () => {
+    const { res: createRes } = await createTokenAndPool({ quoteToken }, creatorConnectivity, creatorProvider)
+    if (!createRes.Ok) throw "tx failed"
+    const poolId = createRes.Ok.poolId
+    const userPosition = userConnectivity.pdas.getUserPositionAccount(new web3.PublicKey(poolId), user)
+
+    const buyQuote = await userConnectivity.quoteTrade({ kind: 'buyExactQuote', amount: new BN(1_000_00_000_000), poolId })
+    if (!buyQuote.Ok) throw "quote failed"
+    if (!(await userConnectivity.buy({ poolId, amount: 1_000 })).Ok) throw "buy failed"
+    await sleep(1_000)
+    const boughtPositionInfo = await program.account.userPosition.fetch(userPosition)
+    const { outputAmount, inputAmount, fee } = buyQuote.Ok.tradeQuote
+    assert.equal(boughtPositionInfo.baseBought.toString(), outputAmount.toString(), "bought base must be recorded")
+    assert.equal(boughtPositionInfo.quoteSpent.toString(), inputAmount.toString(), "spent quote must include trading fee")
+    assert.equal(boughtPositionInfo.feesPaid.toString(), fee.toString())
+    assert.equal(boughtPositionInfo.tradeCount.toNumber(), 1)
+
+    const sellAmount = outputAmount.divn(2_000_000).muln(1_000_000) // Half of bought tokens in whole units
+    if (!(await userConnectivity.sell({ poolId, amount: sellAmount.toNumber() / 1_000_000 })).Ok) throw "sell failed"
+    await sleep(1_000)
+    const soldPositionInfo = await program.account.userPosition.fetch(userPosition)
+    assert.equal(soldPositionInfo.baseSold.toString(), sellAmount.toString(), "sold base must be recorded")
+    assert.isTrue(soldPositionInfo.quoteReceived.gtn(0) && soldPositionInfo.feesPaid.gt(fee), "sell must add received quote and fee")
+    assert.equal(soldPositionInfo.tradeCount.toNumber(), 2)
+    assert.isTrue(soldPositionInfo.lastTradeTs.gte(soldPositionInfo.firstTradeTs))
+
+    const creatorCloseRes = await creatorConnectivity.closeUserPosition({ poolId })
+    assert.isDefined(creatorCloseRes.Err, "wallet without position must not close it")
+    const userLamportsBefore = await connection.getBalance(user)
+    if (!(await userConnectivity.closeUserPosition({ poolId })).Ok) throw "close user position failed"
+    await sleep(1_000)
+    assert.isNull(await connection.getAccountInfo(userPosition), "UserPosition must be closed")
+    assert.isTrue((await connection.getBalance(user)) > userLamportsBefore, "user must get the rent back")
+  });
+
+  it("create pool with initial buy", async () => {
+    const { res } = await createTokenAndPool({ quoteToken, initialBuyQuoteAmount: 1_000 }, creatorConnectivity, creatorProvider)
+    if (!res.Ok) throw "tx failed"
+    await sleep(1_000)
+    const poolInfo = await program.account.poolState.fetch(res.Ok.poolId)
+    assert.isTrue(poolInfo.realQuoteReserves.gtn(0), "initial buy must add quote reserves")
+    assert.isTrue(poolInfo.realBaseReserves.lt(poolInfo.initialBaseReserves), "initial buy must take base reserves")
+    const positionInfo = await program.account.userPosition.fetch(creatorConnectivity.pdas.getUserPositionAccount(new web3.PublicKey(res.Ok.poolId), creator))
+    assert.equal(positionInfo.baseBought.toString(), poolInfo.initialBaseReserves.sub(poolInfo.realBaseReserves).toString(), "initial buy must be recorded in creator's position")
+    assert.equal(positionInfo.tradeCount.toNumber(), 1)
+  });
+
+  it("create scheduled pool (TradingNotStarted: Fail)", async () => {
+    const tradingStartTs = Math.trunc(Date.now() / 1000) + 3600
+    const { res } = await createTokenAndPool({ quoteToken, tradingStartTs }, creatorConnectivity, creatorProvider)
+    if (!res.Ok) throw "tx failed"
+    await sleep(1_000)
+    const poolInfo = await program.account.poolState.fetch(res.Ok.poolId)
+    assert.equal(poolInfo.tradingStartTs?.toNumber(), tradingStartTs, "trading start time must be stored")
+    const buyRes = await userConnectivity.buy({ poolId: res.Ok.poolId, amount: 1_000 })
+    assert.isDefined(buyRes.Err, "buy must fail before trading start time")
+  });
+
+  it("create scheduled pool with initial buy (TradingNotStarted: Fail)", async () => {
+    const tradingStartTs = Math.trunc(Date.now() / 1000) + 3600
+    const { res } = await createTokenAndPool({ quoteToken, tradingStartTs, initialBuyQuoteAmount: 1_000 }, creatorConnectivity, creatorProvider)
+    assert.isDefined(res.Err, "initial buy must fail before trading start time")
+  });
+
+  it("presale buy", async () => {
+    const { res } = await createTokenAndPool({ quoteToken }, creatorConnectivity, creatorProvider)
+    if (!res.Ok) throw "tx failed"
+    const poolId = res.Ok.poolId
+    const allocation = new BN(100_000_000_000_000) // 100M tokens
+    const { root, proofs } = buildPresaleMerkleTree([{ user, allocation }, { user: creator, allocation }, { user: withdrawer, allocation }])
+    const setRes = await creatorConnectivity.setPresale({ poolId, merkleRoot: root, presaleEndTs: Math.trunc(Date.now() / 1000) + 3600 })
+    if (!setRes.Ok) throw "tx failed"
+    await sleep(1_000)
+
+    const publicBuyRes = await userConnectivity.buy({ poolId, amount: 1_000 })
+    assert.isDefined(publicBuyRes.Err, "public buy must fail during presale")
+    const publicBuyQuote = await userConnectivity.quoteTrade({ kind: 'buyExactQuote', amount: new BN(1_000_00_000_000), poolId })
+    assert.isDefined(publicBuyQuote.Err, "public buy quote must fail during presale like the buy")
+    const invalidProofRes = await userConnectivity.presaleBuy({ poolId, amount: 1_000, allocation: allocation.muln(2), proof: proofs[0] })
+    assert.isDefined(invalidProofRes.Err, "presale buy must fail with wrong allocation")
+    const presaleBuyRes = await userConnectivity.presaleBuy({ poolId, amount: 1_000, allocation, proof: proofs[0] })
+    if (!presaleBuyRes.Ok) throw "presale buy failed"
+    await sleep(1_000)
+    const claimInfo = await program.account.presaleClaim.fetch(userConnectivity.pdas.getPresaleClaimAccount(new web3.PublicKey(poolId), user))
+    assert.isTrue(claimInfo.baseBought.gtn(0), "presale claim must record bought amount")
+  });
+
+  it("refund after deadline", async () => {
+    const { res } = await createTokenAndPool({ quoteToken, deadline: Math.trunc(Date.now() / 1000) + 15 }, creatorConnectivity, creatorProvider)
+    if (!res.Ok) throw "tx failed"
+    const poolId = res.Ok.poolId
+    const buyRes = await userConnectivity.buy({ poolId, amount: 1_000 })
+    if (!buyRes.Ok) throw "buy failed"
+    const earlyRefundRes = await userConnectivity.refund({ poolId, amount: 1 })
+    assert.isDefined(earlyRefundRes.Err, "refund must fail before deadline")
+    await sleep(15_000)
+
+    const lateBuyRes = await userConnectivity.buy({ poolId, amount: 1_000 })
+    assert.isDefined(lateBuyRes.Err, "buy must fail after deadline")
+    const poolInfo = await program.account.poolState.fetch(poolId)
+    const boughtBase = poolInfo.initialBaseReserves.sub(poolInfo.realBaseReserves)
+    const refundRes = await userConnectivity.refund({ poolId, amount: boughtBase.toNumber() / 1_000_000 })
+    if (!refundRes.Ok) throw "refund failed"
+    const reclaimRes = await creatorConnectivity.reclaimUnsold({ poolId })
+    if (!reclaimRes.Ok) throw "reclaim failed"
+    await sleep(1_000)
+    const refundedPoolInfo = await program.account.poolState.fetch(poolId)
+    assert.isTrue(refundedPoolInfo.realQuoteReserves.isZero(), "all quote reserves must be refunded to the only holder")
+  });
+
+  it("close refunded pool", async () => {
+    const { res } = await createTokenAndPool({ quoteToken, deadline: Math.trunc(Date.now() / 1000) + 15 }, creatorConnectivity, creatorProvider)
+    if (!res.Ok) throw "tx failed"
+    const poolId = res.Ok.poolId
+    const poolState = new web3.PublicKey(poolId)
+    if (!(await userConnectivity.buy({ poolId, amount: 1_000 })).Ok) throw "buy failed"
+    const earlyCloseRes = await connectivity.closeRefundedPool({ poolId })
+    assert.isDefined(earlyCloseRes.Err, "pool must be in refund state before closing")
+    await sleep(15_000)
+
+    const unrefundedCloseRes = await connectivity.closeRefundedPool({ poolId })
+    assert.isDefined(unrefundedCloseRes.Err, "quote reserves must be fully refunded before closing")
+    const poolInfo = await program.account.poolState.fetch(poolId)
+    const boughtBase = poolInfo.initialBaseReserves.sub(poolInfo.realBaseReserves)
+    if (!(await userConnectivity.refund({ poolId, amount: boughtBase.toNumber() / 1_000_000 })).Ok) throw "refund failed"
+    if (!(await creatorConnectivity.reclaimUnsold({ poolId })).Ok) throw "reclaim failed"
+    await sleep(1_000)
+
+    const unauthorisedCloseRes = await userConnectivity.closeRefundedPool({ poolId })
+    assert.isDefined(unauthorisedCloseRes.Err, "only withdrawer or creator can close the pool")
+    const creatorFeeVault = creatorConnectivity.pdas.getCreatorFeeVaultAccount(poolState)
+    const reserverQuoteAta = getAssociatedTokenAddressSync(poolInfo.quoteMint, poolState, true)
+    let rent = 0
+    for (const account of [poolState, reserverQuoteAta, creatorFeeVault]) rent += await connection.getBalance(account)
+    const creatorLamportsBefore = await connection.getBalance(creator)
+    // Closed by withdrawer so that creator's balance changes by rent only
+    if (!(await connectivity.closeRefundedPool({ poolId })).Ok) throw "close refunded pool failed"
+    await sleep(1_000)
+    for (const account of [poolState, reserverQuoteAta, creatorFeeVault]) assert.isNull(await connection.getAccountInfo(account), "pool accounts must be closed")
+    assert.equal(await connection.getBalance(creator), creatorLamportsBefore + rent, "creator must get all the rent back")
+  });
+
+  it("price history candles", async () => {
+    const { res } = await createTokenAndPool({ quoteToken }, creatorConnectivity, creatorProvider)
+    if (!res.Ok) throw "tx failed"
+    const poolId = res.Ok.poolId
+    const initRes = await creatorConnectivity.initPoolObservations({ poolId })
+    if (!initRes.Ok) throw "init pool observations failed"
+
+    const startTs = Math.trunc(Date.now() / 1000) - 60
+    if (!(await userConnectivity.buy({ poolId, amount: 1_000 })).Ok) throw "buy failed"
+    if (!(await userConnectivity.sell({ poolId, amount: 1_000 })).Ok) throw "sell failed"
+    if (!(await userConnectivity.buy({ poolId, amount: 2_000 })).Ok) throw "buy failed"
+    await sleep(1_000)
+
+    const candlesRes = await userConnectivity.getCandles({ poolId, startTs, interval: 3_600 })
+    if (!candlesRes.Ok) throw "get candles failed"
+    const [candle] = candlesRes.Ok.candles
+    assert.equal(candlesRes.Ok.candles.length, 1, "all trades must fall into one candle")
+    assert.equal(candle.tradeCount, 3)
+    assert.isTrue(candle.high.gte(candle.close) && candle.low.lte(candle.open), "high/low must bound sampled prices")
+  });
+
+  it("time-weighted average price", async () => {
+    const { res } = await createTokenAndPool({ quoteToken }, creatorConnectivity, creatorProvider)
+    if (!res.Ok) throw "tx failed"
+    const poolId = res.Ok.poolId
+    // Spot price of the constant product curve (Q64.64)
+    const spotPrice = (poolInfo: any) => poolInfo.virtQuoteReserves.add(poolInfo.realQuoteReserves).shln(64)
+      .div(poolInfo.virtBaseReserves.add(poolInfo.realBaseReserves))
+
+    await sleep(2_000)
+    const earlierRes = await userConnectivity.observePrice({ poolId })
+    if (!earlierRes.Ok) throw "observe price failed"
+    const prices = [spotPrice(await program.account.poolState.fetch(poolId))]
+    const tradeTimestamps = []
+    for (const amount of [10_000, 20_000]) {
+      if (!(await userConnectivity.buy({ poolId, amount })).Ok) throw "buy failed"
+      const poolInfo = await program.account.poolState.fetch(poolId)
+      prices.push(spotPrice(poolInfo))
+      tradeTimestamps.push(poolInfo.lastPriceUpdateTs)
+      await sleep(3_000)
+    }
+    const laterRes = await userConnectivity.observePrice({ poolId })
+    if (!laterRes.Ok) throw "observe price failed"
+    assert.isTrue(prices[1].gt(prices[0]) && prices[2].gt(prices[1]), "buys must raise spot price")
+
+    // Each price is weighted by the seconds it was held
+    const earlier = earlierRes.Ok.observation
+    const later = laterRes.Ok.observation
+    const timestamps = [earlier.timestamp, ...tradeTimestamps, later.timestamp]
+    assert.isTrue(timestamps[2].gt(timestamps[1]) && timestamps[3].gt(timestamps[2]), "trades must happen at different times between observations")
+    let expectedDelta = new BN(0)
+    prices.forEach((price, i) => { expectedDelta = expectedDelta.add(price.mul(timestamps[i + 1].sub(timestamps[i]))) })
+    assert.equal(later.priceCumulative.sub(earlier.priceCumulative).toString(), expectedDelta.toString(), "cumulative price must weight prices by time")
+    const twap = expectedDelta.div(later.timestamp.sub(earlier.timestamp))
+    assert.isTrue(twap.gt(prices[0]) && twap.lt(prices[2]), "TWAP must lie between spot prices")
+  });
+
+  it("migrate pool to cp_amm", async () => {
+    const { baseToken, poolId } = await createCompletePool()
+    const baseMint = new web3.PublicKey(baseToken)
+    const quoteMint = new web3.PublicKey(fastQuoteToken)
+
+    // Only migrate_pool can create the AMM pool of the pair
+    const ammPool = userConnectivity.pdas.getAmmPoolAccount({ baseMint, quoteMint })
+    const lpMint = userConnectivity.pdas.getLpMintAccount(ammPool)
+    const frontRunTxSignature = await ammProgram.methods.initializePool(new BN(1_000_000), new BN(1_000_000)).accounts({
+      payer: user, authority: user,
+      ammPool, baseMint, quoteMint, lpMint,
+      baseVault: getAssociatedTokenAddressSync(baseMint, ammPool, true),
+      quoteVault: getAssociatedTokenAddressSync(quoteMint, ammPool, true),
+      sourceBaseAta: getAssociatedTokenAddressSync(baseMint, user),
+      sourceQuoteAta: getAssociatedTokenAddressSync(quoteMint, user),
+      lpRecipient: user,
+      recipientLpAta: getAssociatedTokenAddressSync(lpMint, user),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: web3.SystemProgram.programId,
+      rent: web3.SYSVAR_RENT_PUBKEY,
+    }).signers([userAuthority]).rpc().catch(() => null)
+    assert.isNull(frontRunTxSignature, "AMM pool must not be created ahead of migration")
+
+    if (!(await connectivity.setPoolPause({ poolId, paused: true })).Ok) throw "pause failed"
+    const pausedMigrateRes = await userConnectivity.migratePool({ poolId })
+    assert.isDefined(pausedMigrateRes.Err, "migrate must fail while pool is paused")
+    if (!(await connectivity.setPoolPause({ poolId, paused: false })).Ok) throw "unpause failed"
+
+    const res = await userConnectivity.migratePool({ poolId })
+    if (!res.Ok) throw "migrate failed"
+    await sleep(1_000)
+    const ammPoolInfo = await ammProgram.account.ammPool.fetch(res.Ok.ammPoolId)
+    assert.equal(ammPoolInfo.baseMint.toBase58(), baseToken)
+    const poolInfo = await program.account.poolState.fetch(poolId)
+    assert.isTrue(poolInfo.realBaseReserves.isZero() && poolInfo.realQuoteReserves.isZero(), "reserves must move to the AMM pool")
+  });
+
+  it("partial withdrawals", async () => {
+    const { poolId } = await createCompletePool()
+    const mainStateInfo = await program.account.mainState.fetch(connectivity.pdas.mainState)
+    const poolInfo = await program.account.poolState.fetch(poolId)
+    const quoteMint = new web3.PublicKey(fastQuoteToken)
+    const treasuryQuoteAta = getAssociatedTokenAddressSync(quoteMint, mainStateInfo.withdrawalPolicy.treasury)
+
+    const unauthorisedRes = await withdrawerConnectivity.withdraw({ poolId })
+    assert.isDefined(unauthorisedRes.Err, "only withdrawer can withdraw")
+    const excessRes = await connectivity.withdraw({ poolId, baseAmount: new BN(0), quoteAmount: poolInfo.realQuoteReserves.addn(1) })
+    assert.isDefined(excessRes.Err, "withdraw must not exceed reserves")
+
+    // Default policy routes whole quote proceeds to treasury
+    const quoteAmount = poolInfo.realQuoteReserves.divn(2)
+    const treasuryBefore = await getTokenBalance(connection, treasuryQuoteAta)
+    if (!(await connectivity.withdraw({ poolId, baseAmount: new BN(0), quoteAmount })).Ok) throw "withdraw failed"
+    await sleep(1_000)
+    assert.equal((await getTokenBalance(connection, treasuryQuoteAta)).sub(treasuryBefore).toString(), quoteAmount.toString(), "treasury must receive withdrawn quote")
+    const partialPoolInfo = await program.account.poolState.fetch(poolId)
+    assert.equal(partialPoolInfo.realQuoteReserves.toString(), poolInfo.realQuoteReserves.sub(quoteAmount).toString())
+    assert.equal(partialPoolInfo.realBaseReserves.toString(), poolInfo.realBaseReserves.toString(), "base must stay until withdrawn separately")
+
+    if (!(await connectivity.withdraw({ poolId, baseAmount: partialPoolInfo.realBaseReserves, quoteAmount: new BN(0) })).Ok) throw "withdraw failed"
+    if (!(await connectivity.withdraw({ poolId })).Ok) throw "withdraw failed"
+    await sleep(1_000)
+    const drainedPoolInfo = await program.account.poolState.fetch(poolId)
+    assert.isTrue(drainedPoolInfo.realBaseReserves.isZero() && drainedPoolInfo.realQuoteReserves.isZero(), "pool must be drained by separate withdrawals")
+
+    const invalidPolicyRes = await connectivity.queueMainStateUpdate({
+      newWithdrawalPolicy: { treasury: provider.publicKey.toBase58(), creatorShare: 60, migrationEscrow: provider.publicKey.toBase58(), migrationEscrowShare: 60 },
+      quoteToken,
+    })
+    assert.isDefined(invalidPolicyRes.Err, "recipient shares must not exceed 100%")
+  });
+
+  it("close drained pool", async () => {
+    const { poolId } = await createCompletePool()
+    const earlyCloseRes = await creatorConnectivity.closePool({ poolId })
+    assert.isDefined(earlyCloseRes.Err, "pool must be withdrawn or migrated before closing")
+    const withdrawRes = await connectivity.withdraw({ poolId })
+    if (!withdrawRes.Ok) throw "withdraw failed"
+    await sleep(1_000)
+
+    const unauthorisedCloseRes = await userConnectivity.closePool({ poolId })
+    assert.isDefined(unauthorisedCloseRes.Err, "only withdrawer or creator can close the pool")
+    const creatorFeeVault = creatorConnectivity.pdas.getCreatorFeeVaultAccount(new web3.PublicKey(poolId))
+    const creatorLamportsBefore = await connection.getBalance(creator)
+    const res = await creatorConnectivity.closePool({ poolId })
+    if (!res.Ok) throw "close pool failed"
+    await sleep(1_000)
+    assert.isNull(await connection.getAccountInfo(new web3.PublicKey(poolId)), "PoolState must be closed")
+    assert.isNull(await connection.getAccountInfo(creatorFeeVault), "creator's fee vault must be closed")
+    assert.isTrue((await connection.getBalance(creator)) > creatorLamportsBefore, "creator must get the rent back")
+  });
+
   // it("buy", async () => {
   //   const poolId = commonState.poolId
   //   if (!poolId) throw "pool id not found"
@@ -115,7 +616,7 @@ describe("ali_pump_fun", () => {
   // })
 
   // it("update mainState", async () => {
-  //   const res = await connectivity.updateMainState({ newWithdrawer: withdrawer.toString(), quoteToken, newTradingFee: 2 })
+  //   const res = await connectivity.queueMainStateUpdate({ newWithdrawer: withdrawer.toString(), quoteToken, newTradingFee: 2 })
   //   if (res.Err) {
   //     log(`Error: ${res.Err}`)
   //     throw "updateMainState fail"
@@ -232,7 +733,7 @@ describe("ali_pump_fun", () => {
 
   // it("transfer ownership", async () => {
   //   await sleep(3_000)
-  //   const res = await connectivity.transferOwnership(user)
+  //   const res = await connectivity.proposeOwner(user)
   //   if (res.Err) {
   //     log(`Error: ${res.Err}`)
   //     throw "TransferOwnership Tx Error"
@@ -2,7 +2,7 @@ import { AnchorProvider, Program, Wallet, web3 } from '@coral-xyz/anchor'
 import { AliPumpFun, IDL as PumpFunIDL } from '../../target/types/ali_pump_fun'
 import { Result, TxPassResult } from './types'
 import { PumpFunError } from './error';
-import { FEE_PRE_DIV, PROGRAMS, debug } from './constants';
+import { CurveType, FEE_PRE_DIV, PROGRAMS, UnsoldBaseAction, WindowUnit, curveTypeMask, debug } from './constants';
 import { Pdas } from './pdas';
 import BN from 'bn.js';
 import { calculateOutputAmount, calculateInputAmount, getMultipleAccountsInfo, getPubkeyFromStr, sleep } from './utils';
@@ -10,7 +10,7 @@ import { MintLayout, NATIVE_MINT, getAssociatedTokenAddressSync, getMint, mintTo
 import { calcDecimalValue, calcNonDecimalValue } from './base/utils';
 import { toBufferBE, toBigIntBE } from 'bigint-buffer'
 
-const { systemProgram, tokenProgram, associatedTokenProgram } = PROGRAMS
+const { systemProgram, tokenProgram, associatedTokenProgram, ammProgram, metadataProgram } = PROGRAMS
 const todo = null as any;
 
 export type MainStateInfo = {
@@ -71,37 +71,77 @@ export class Connectivity {
         return { Ok: { txSignature } }
     }
 
-    async transferOwnership(newOwner: web3.PublicKey): Promise<Result<TxPassResult>> {
+    async proposeOwner(newOwner: web3.PublicKey): Promise<Result<TxPassResult>> {
         const owner = this.provider.publicKey
         if (!owner) return { Err: PumpFunError.WALLET_NOT_FOUND }
 
-        const mainState = this.pdas.mainState
-        const mainStateInfo = await this.program.account.mainState.fetch(mainState)
-            .catch((fetchMainStateInfoError) => { debug({ fetchMainStateInfoError }); return null })
-        if (!mainStateInfo) return { Err: PumpFunError.FAILED_TO_FETCH_DATA }
+        const txSignature = await this.program.methods.proposeOwner(newOwner)
+        .accounts({
+            owner, 
+            mainState: this.pdas.mainState,
+        }).rpc().catch(proposeOwnerError => {
+            debug({ proposeOwnerError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    async acceptOwnership(): Promise<Result<TxPassResult>> {
+        const pendingOwner = this.provider.publicKey
+        if (!pendingOwner) return { Err: PumpFunError.WALLET_NOT_FOUND }
 
-        const txSignature = await this.program.methods.transferOwnership(newOwner
-        )
+        const txSignature = await this.program.methods.acceptOwnership()
+        .accounts({
+            pendingOwner, 
+            mainState: this.pdas.mainState,
+        }).rpc().catch(acceptOwnershipError => {
+            debug({ acceptOwnershipError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    async cancelOwnershipProposal(): Promise<Result<TxPassResult>> {
+        const owner = this.provider.publicKey
+        if (!owner) return { Err: PumpFunError.WALLET_NOT_FOUND }
+
+        const txSignature = await this.program.methods.cancelOwnershipProposal()
         .accounts({
             owner, 
             mainState: this.pdas.mainState,
-        }).rpc().catch(transferOwnershipError => {
-            debug({ transferOwnershipError })
+        }).rpc().catch(cancelOwnershipProposalError => {
+            debug({ cancelOwnershipProposalError })
             return null
         })
         if (!txSignature) return { Err: PumpFunError.TX_FAILED }
         return { Ok: { txSignature } }
     }
 
-    async updateMainState(input: {
+    // Queues MainState update, it's applied by executeMainStateUpdate once MainState.updateDelay has passed
+    async queueMainStateUpdate(input: {
         newWithdrawer?: string,
+        newPauser?: string,
         newFeeRecipient?: string,
         quoteToken?: string,
         newTradingFee?: number,
         newTotalSupply?: number,
         newInitVirtBaseReserves?: number,
         newInitVirtQuoteReserves?: number,
-        newRealQuoteThreshold?: number
+        newRealQuoteThreshold?: number,
+        newUpdateDelay?: number,
+        newAllowedCurves?: CurveType[],
+        newCreatorFeeShare?: number,
+        newReferralFeeShare?: number,
+        newAntiSnipeWindow?: number,
+        newAntiSnipeWindowUnit?: WindowUnit,
+        newMaxBuyPerWallet?: number,
+        newWithdrawalPolicy?: { treasury: string, creatorShare: number, migrationEscrow: string, migrationEscrowShare: number },
+        newUnsoldBaseAction?: UnsoldBaseAction,
+        newVestingDuration?: number,
+        newCreatorCompletionReward?: { fixed: number } | { thresholdShare: number },
+        newTrackPositions?: boolean
     }): Promise<Result<TxPassResult>> {
         const owner = this.provider.publicKey
         if (!owner) return { Err: PumpFunError.WALLET_NOT_FOUND }
@@ -115,12 +155,25 @@ export class Connectivity {
         if (!mainStateInfo) return { Err: PumpFunError.FAILED_TO_FETCH_DATA }
 
         let newWithdrawer: null | web3.PublicKey = null
+        let newPauser: null | web3.PublicKey = null
         let newFeeRecipient: null | web3.PublicKey = null
         let newTradingFee: null | BN = null
         let newTotalSupply: null | BN = null
         let newInitVirtBaseReserves: null | BN = null
         let newInitVirtQuoteReserves: null | BN = null
         let newRealQuoteThreshold: null | BN = null
+        let newUpdateDelay: null | BN = null
+        let newAllowedCurves: null | number = null
+        let newCreatorFeeShare: null | BN = null
+        let newReferralFeeShare: null | BN = null
+        let newAntiSnipeWindow: null | BN = null
+        let newAntiSnipeWindowUnit: any = null
+        let newMaxBuyPerWallet: null | BN = null
+        let newWithdrawalPolicy: null | { treasury: web3.PublicKey, creatorShare: BN, migrationEscrow: web3.PublicKey, migrationEscrowShare: BN } = null
+        let newUnsoldBaseAction: any = null
+        let newVestingDuration: null | BN = null
+        let newCreatorCompletionReward: any = null
+        let newTrackPositions: null | boolean = null
         
         if (input.newWithdrawer) {
             const address = getPubkeyFromStr(input.newWithdrawer)
@@ -130,6 +183,12 @@ export class Connectivity {
             newWithdrawer = mainStateInfo.withdrawer
         }
 
+        if (input.newPauser) {
+            const address = getPubkeyFromStr(input.newPauser)
+            if (!address) return { Err: PumpFunError.INVALID_INPUT }
+            newPauser = address
+        }
+
         if (input.newFeeRecipient) {
             const address = getPubkeyFromStr(input.newFeeRecipient)
             if (!address) return { Err: PumpFunError.INVALID_INPUT }
@@ -165,50 +224,283 @@ export class Connectivity {
             newRealQuoteThreshold = new BN(tmpRealQuoteThreshold)
         }
 
-        const feeQuoteAta = getAssociatedTokenAddressSync(quoteMint, newFeeRecipient)
-        
-        const txSignature = await this.program.methods.updateMainState({ 
+        if (input.newUpdateDelay != undefined) {
+            newUpdateDelay = new BN(input.newUpdateDelay)
+        }
+
+        if (input.newAllowedCurves) {
+            newAllowedCurves = input.newAllowedCurves.reduce((mask, curveType) => mask | curveTypeMask(curveType), 0)
+        }
+
+        if (input.newCreatorFeeShare != undefined) {
+            newCreatorFeeShare = new BN(Math.trunc(input.newCreatorFeeShare * FEE_PRE_DIV))
+        }
+
+        if (input.newReferralFeeShare != undefined) {
+            newReferralFeeShare = new BN(Math.trunc(input.newReferralFeeShare * FEE_PRE_DIV))
+        }
+
+        if (input.newAntiSnipeWindow != undefined) {
+            newAntiSnipeWindow = new BN(input.newAntiSnipeWindow)
+        }
+
+        if (input.newAntiSnipeWindowUnit) {
+            newAntiSnipeWindowUnit = { [input.newAntiSnipeWindowUnit]: {} }
+        }
+
+        if (input.newMaxBuyPerWallet != undefined) {
+            newMaxBuyPerWallet = new BN(input.newMaxBuyPerWallet)
+        }
+
+        if (input.newWithdrawalPolicy) {
+            const treasury = getPubkeyFromStr(input.newWithdrawalPolicy.treasury)
+            const migrationEscrow = getPubkeyFromStr(input.newWithdrawalPolicy.migrationEscrow)
+            if (!treasury || !migrationEscrow) return { Err: PumpFunError.INVALID_INPUT }
+            newWithdrawalPolicy = {
+                treasury,
+                creatorShare: new BN(Math.trunc(input.newWithdrawalPolicy.creatorShare * FEE_PRE_DIV)),
+                migrationEscrow,
+                migrationEscrowShare: new BN(Math.trunc(input.newWithdrawalPolicy.migrationEscrowShare * FEE_PRE_DIV)),
+            }
+        }
+
+        if (input.newUnsoldBaseAction) {
+            newUnsoldBaseAction = { [input.newUnsoldBaseAction]: {} }
+        }
+
+        if (input.newVestingDuration != undefined) {
+            newVestingDuration = new BN(input.newVestingDuration)
+        }
+
+        if (input.newCreatorCompletionReward) {
+            // Fixed amount is in raw quote units, threshold share is in percent
+            newCreatorCompletionReward = ('fixed' in input.newCreatorCompletionReward)
+                ? { fixed: { 0: new BN(input.newCreatorCompletionReward.fixed) } }
+                : { thresholdShare: { 0: new BN(Math.trunc(input.newCreatorCompletionReward.thresholdShare * FEE_PRE_DIV)) } }
+        }
+
+        if (input.newTrackPositions != undefined) {
+            newTrackPositions = input.newTrackPositions
+        }
+
+        const txSignature = await this.program.methods.queueMainStateUpdate({ 
             withdrawer: newWithdrawer, 
+            pauser: newPauser, 
             feeRecipient: newFeeRecipient, 
             tradingFee: newTradingFee, 
             totalSupply: newTotalSupply, 
             initVirtBaseReserves: newInitVirtBaseReserves, 
             initVirtQuoteReserves: newInitVirtQuoteReserves,
-            realQuoteThreshold: newRealQuoteThreshold
+            realQuoteThreshold: newRealQuoteThreshold,
+            updateDelay: newUpdateDelay,
+            allowedCurves: newAllowedCurves,
+            creatorFeeShare: newCreatorFeeShare,
+            referralFeeShare: newReferralFeeShare,
+            antiSnipeWindow: newAntiSnipeWindow,
+            antiSnipeWindowUnit: newAntiSnipeWindowUnit,
+            maxBuyPerWallet: newMaxBuyPerWallet,
+            withdrawalPolicy: newWithdrawalPolicy,
+            unsoldBaseAction: newUnsoldBaseAction,
+            vestingDuration: newVestingDuration,
+            creatorCompletionReward: newCreatorCompletionReward,
+            trackPositions: newTrackPositions
         })
         .accounts({
             owner, 
             mainState: this.pdas.mainState,
+            pendingUpdate: this.pdas.pendingMainStateUpdate,
+            systemProgram,
+        }).rpc().catch(queueMainStateUpdateError => {
+            debug({ queueMainStateUpdateError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    async executeMainStateUpdate(input: { quoteToken?: string }): Promise<Result<TxPassResult>> {
+        const owner = this.provider.publicKey
+        if (!owner) return { Err: PumpFunError.WALLET_NOT_FOUND }
+
+        const quoteMint = (input?.quoteToken) ? getPubkeyFromStr(input?.quoteToken) : NATIVE_MINT
+        if (!quoteMint) return { Err: PumpFunError.INVALID_INPUT }
+
+        const pendingUpdate = this.pdas.pendingMainStateUpdate
+        const pendingUpdateInfo = await this.program.account.pendingMainStateUpdate.fetch(pendingUpdate)
+            .catch((fetchPendingUpdateInfoError) => { debug({ fetchPendingUpdateInfoError }); return null })
+        if (!pendingUpdateInfo) return { Err: PumpFunError.FAILED_TO_FETCH_DATA }
+        const feeRecipient = pendingUpdateInfo.input.feeRecipient
+        const feeQuoteAta = getAssociatedTokenAddressSync(quoteMint, feeRecipient)
+
+        const txSignature = await this.program.methods.executeMainStateUpdate().accounts({
+            owner, 
+            mainState: this.pdas.mainState,
+            pendingUpdate,
+            quoteConfig: this.pdas.getQuoteConfigAccount(quoteMint),
             quoteMint, 
-            feeRecipient: newFeeRecipient, 
-            feeQuoteAta
-        }).rpc().catch(updateMainStateError => {
-            debug({ updateMainStateError })
+            feeRecipient, 
+            feeQuoteAta,
+            associatedTokenProgram, tokenProgram, systemProgram,
+        }).rpc().catch(executeMainStateUpdateError => {
+            debug({ executeMainStateUpdateError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    async cancelMainStateUpdate(): Promise<Result<TxPassResult>> {
+        const owner = this.provider.publicKey
+        if (!owner) return { Err: PumpFunError.WALLET_NOT_FOUND }
+
+        const txSignature = await this.program.methods.cancelMainStateUpdate().accounts({
+            owner, 
+            mainState: this.pdas.mainState,
+            pendingUpdate: this.pdas.pendingMainStateUpdate,
+        }).rpc().catch(cancelMainStateUpdateError => {
+            debug({ cancelMainStateUpdateError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    // Whitelists quote token, reserves/threshold default to MainState's and trading fee to no override
+    async addQuoteMint(input: { quoteToken: string, initVirtQuoteReserves?: number, realQuoteThreshold?: number, tradingFee?: number }): Promise<Result<TxPassResult>> {
+        const owner = this.provider.publicKey
+        if (!owner) return { Err: PumpFunError.WALLET_NOT_FOUND }
+        const quoteMint = getPubkeyFromStr(input.quoteToken)
+        if (!quoteMint) return { Err: PumpFunError.INVALID_INPUT }
+
+        const txSignature = await this.program.methods.addQuoteMint({
+            initVirtQuoteReserves: input.initVirtQuoteReserves ? new BN(input.initVirtQuoteReserves) : null,
+            realQuoteThreshold: input.realQuoteThreshold ? new BN(input.realQuoteThreshold) : null,
+            tradingFee: input.tradingFee != undefined ? new BN(Math.trunc(input.tradingFee * FEE_PRE_DIV)) : null,
+        }).accounts({
+            owner,
+            mainState: this.pdas.mainState,
+            quoteMint,
+            quoteConfig: this.pdas.getQuoteConfigAccount(quoteMint),
+            systemProgram,
+        }).rpc().catch(addQuoteMintError => {
+            debug({ addQuoteMintError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    async removeQuoteMint(input: { quoteToken: string }): Promise<Result<TxPassResult>> {
+        const owner = this.provider.publicKey
+        if (!owner) return { Err: PumpFunError.WALLET_NOT_FOUND }
+        const quoteMint = getPubkeyFromStr(input.quoteToken)
+        if (!quoteMint) return { Err: PumpFunError.INVALID_INPUT }
+
+        const txSignature = await this.program.methods.removeQuoteMint().accounts({
+            owner,
+            mainState: this.pdas.mainState,
+            quoteConfig: this.pdas.getQuoteConfigAccount(quoteMint),
+        }).rpc().catch(removeQuoteMintError => {
+            debug({ removeQuoteMintError })
             return null
         })
         if (!txSignature) return { Err: PumpFunError.TX_FAILED }
         return { Ok: { txSignature } }
     }
 
-    async createPool(input: { baseToken: string, quoteToken: string }): Promise<Result<TxPassResult & { poolId: string }>> {
+    async setGlobalPause(paused: boolean): Promise<Result<TxPassResult>> {
+        const pauser = this.provider.publicKey
+        if (!pauser) return { Err: PumpFunError.WALLET_NOT_FOUND }
+        const txSignature = await this.program.methods.setGlobalPause(paused).accounts({
+            pauser,
+            mainState: this.pdas.mainState,
+        }).rpc().catch(setGlobalPauseError => {
+            debug({ setGlobalPauseError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    async setPoolPause(input: { poolId: string, paused: boolean }): Promise<Result<TxPassResult>> {
+        const pauser = this.provider.publicKey
+        if (!pauser) return { Err: PumpFunError.WALLET_NOT_FOUND }
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+        const txSignature = await this.program.methods.setPoolPause(input.paused).accounts({
+            pauser,
+            mainState: this.pdas.mainState,
+            poolState,
+        }).rpc().catch(setPoolPauseError => {
+            debug({ setPoolPauseError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    async setPresale(input: { poolId: string, merkleRoot: Buffer, presaleEndTs: number }): Promise<Result<TxPassResult>> {
+        const creator = this.provider.publicKey
+        if (!creator) return { Err: PumpFunError.WALLET_NOT_FOUND }
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+        const txSignature = await this.program.methods.setPresale([...input.merkleRoot], new BN(input.presaleEndTs)).accounts({
+            creator,
+            mainState: this.pdas.mainState,
+            poolState,
+        }).rpc().catch(setPresaleError => {
+            debug({ setPresaleError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    async createPool(input: { baseToken: string, quoteToken: string, curveType?: CurveType, initialBuyQuoteAmount?: number, tradingStartTs?: number, deadline?: number }): Promise<Result<TxPassResult & { poolId: string }>> {
         const creator = this.provider.publicKey
         if (!creator) return { Err: PumpFunError.WALLET_NOT_FOUND }
         const baseMint = getPubkeyFromStr(input.baseToken)
         const quoteMint = getPubkeyFromStr(input.quoteToken)
         if (!baseMint || !quoteMint) return { Err: PumpFunError.INVALID_INPUT }
-        const creatorBaseAta = getAssociatedTokenAddressSync(baseMint, creator)
+        const baseTokenProgram = await this.getTokenProgram(baseMint)
+        const creatorBaseAta = getAssociatedTokenAddressSync(baseMint, creator, false, baseTokenProgram)
         const poolState = this.pdas.getPoolStateAccount({ baseMint, quoteMint, owner: creator })
-        const reserverBaseAta = getAssociatedTokenAddressSync(baseMint, poolState, true)
+        const reserverBaseAta = getAssociatedTokenAddressSync(baseMint, poolState, true, baseTokenProgram)
         const reserverQuoteAta = getAssociatedTokenAddressSync(quoteMint, poolState, true)
-        const txSignature = await this.program.methods.createPool().accounts({
+        const curveType = input.curveType ?? 'constantProduct'
+        let initialBuyQuoteAmount: BN | null = null
+        let initialBuyAccounts: { feeRecipient: web3.PublicKey | null, feeQuoteAta: web3.PublicKey | null, creatorQuoteAta: web3.PublicKey | null, userPosition: web3.PublicKey | null } = { feeRecipient: null, feeQuoteAta: null, creatorQuoteAta: null, userPosition: null }
+        if (input.initialBuyQuoteAmount) {
+            const mainStateInfo = await this.program.account.mainState.fetch(this.pdas.mainState)
+                .catch((fetchMainStateInfoError) => { debug({ fetchMainStateInfoError }); return null })
+            if (!mainStateInfo) return { Err: PumpFunError.FAILED_TO_FETCH_DATA }
+            const accountInfoes = await getMultipleAccountsInfo(this.provider.connection, [quoteMint])
+            if (!accountInfoes) return { Err: PumpFunError.FAILED_TO_FETCH_DATA }
+            const [quoteMintAccountInfo] = accountInfoes;
+            if (!quoteMintAccountInfo) return { Err: PumpFunError.TOKEN_NOT_FOUND }
+            const quoteMintDecimals = MintLayout.decode(quoteMintAccountInfo.data).decimals
+            initialBuyQuoteAmount = new BN(toBufferBE(BigInt(calcNonDecimalValue(input.initialBuyQuoteAmount, quoteMintDecimals).toString()), 8))
+            initialBuyAccounts = {
+                feeRecipient: mainStateInfo.feeRecipient,
+                feeQuoteAta: getAssociatedTokenAddressSync(quoteMint, mainStateInfo.feeRecipient),
+                creatorQuoteAta: getAssociatedTokenAddressSync(quoteMint, creator),
+                userPosition: this.getUserPositionAccount(mainStateInfo, poolState, creator),
+            }
+        }
+        const tradingStartTs = input.tradingStartTs != undefined ? new BN(input.tradingStartTs) : null
+        const deadline = input.deadline != undefined ? new BN(input.deadline) : null
+        const txSignature = await this.program.methods.createPool({ [curveType]: {} } as any, initialBuyQuoteAmount, tradingStartTs, deadline).accounts({
             creator: creator,
             mainState: this.pdas.mainState,
             poolState,
             baseMint, quoteMint,
+            quoteConfig: this.pdas.getQuoteConfigAccount(quoteMint),
             creatorBaseAta,
             reserverBaseAta, reserverQuoteAta,
+            creatorFeeVault: this.pdas.getCreatorFeeVaultAccount(poolState),
+            ...initialBuyAccounts,
             associatedTokenProgram,
-            tokenProgram,
+            tokenProgram, baseTokenProgram,
             systemProgram
         }).preInstructions([web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 300_000 })]).rpc().catch(createPoolError => {
             debug({ createPoolError })
@@ -218,7 +510,39 @@ export class Connectivity {
         return { Ok: { txSignature, poolId: poolState.toBase58() } }
     }
 
-    async buy(input: { amount: number, poolId: string }) {
+    async launchToken(input: { name: string, symbol: string, uri: string, decimals: number, quoteToken: string, curveType?: CurveType }): Promise<Result<TxPassResult & { poolId: string, baseToken: string }>> {
+        const creator = this.provider.publicKey
+        if (!creator) return { Err: PumpFunError.WALLET_NOT_FOUND }
+        const quoteMint = getPubkeyFromStr(input.quoteToken)
+        if (!quoteMint) return { Err: PumpFunError.INVALID_INPUT }
+        const baseMintKeypair = web3.Keypair.generate()
+        const baseMint = baseMintKeypair.publicKey
+        const poolState = this.pdas.getPoolStateAccount({ baseMint, quoteMint, owner: creator })
+        const reserverBaseAta = getAssociatedTokenAddressSync(baseMint, poolState, true)
+        const reserverQuoteAta = getAssociatedTokenAddressSync(quoteMint, poolState, true)
+        const curveType = input.curveType ?? 'constantProduct'
+        const txSignature = await this.program.methods.launchToken(input.name, input.symbol, input.uri, input.decimals, { [curveType]: {} } as any).accounts({
+            creator,
+            mainState: this.pdas.mainState,
+            poolState,
+            baseMint, quoteMint,
+            quoteConfig: this.pdas.getQuoteConfigAccount(quoteMint),
+            reserverBaseAta, reserverQuoteAta,
+            creatorFeeVault: this.pdas.getCreatorFeeVaultAccount(poolState),
+            metadata: this.pdas.getMetadataAccount(baseMint),
+            metadataProgram,
+            associatedTokenProgram,
+            tokenProgram,
+            systemProgram
+        }).signers([baseMintKeypair]).preInstructions([web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })]).rpc().catch(launchTokenError => {
+            debug({ launchTokenError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature, poolId: poolState.toBase58(), baseToken: baseMint.toBase58() } }
+    }
+
+    async buy(input: { amount: number, poolId: string, referrer?: string }) {
         const buyer = this.provider.publicKey
         if (!buyer) return { Err: PumpFunError.WALLET_NOT_FOUND }
         const poolState = getPubkeyFromStr(input.poolId)
@@ -230,27 +554,33 @@ export class Connectivity {
             .catch((fetchPoolInfoError) => { debug({ fetchPoolInfoError }); return null })
         if (!poolInfo) return { Err: PumpFunError.POOL_NOT_FOUND }
         const { baseMint, quoteMint } = poolInfo
+        const baseTokenProgram = await this.getTokenProgram(baseMint)
         const accountInfoes = await getMultipleAccountsInfo(this.provider.connection, [quoteMint])
         if (!accountInfoes) return { Err: PumpFunError.FAILED_TO_FETCH_DATA }
         const [quoteMintAccountInfo] = accountInfoes;
         if (!quoteMintAccountInfo) return { Err: PumpFunError.TOKEN_NOT_FOUND }
         const quoteMintDecimals = MintLayout.decode(quoteMintAccountInfo.data).decimals
         const amount = new BN(toBufferBE(BigInt(calcNonDecimalValue(input.amount, quoteMintDecimals).toString()), 8))
-        const buyerBaseAta = getAssociatedTokenAddressSync(baseMint, buyer)
+        const buyerBaseAta = getAssociatedTokenAddressSync(baseMint, buyer, false, baseTokenProgram)
         const buyerQuoteAta = getAssociatedTokenAddressSync(quoteMint, buyer)
         const feeQuoteAta = getAssociatedTokenAddressSync(quoteMint, mainStateInfo.feeRecipient)
-        const reserverBaseAta = getAssociatedTokenAddressSync(baseMint, poolState, true)
+        const reserverBaseAta = getAssociatedTokenAddressSync(baseMint, poolState, true, baseTokenProgram)
         const reserverQuoteAta = getAssociatedTokenAddressSync(quoteMint, poolState, true)
 
         const txSignature = await this.program.methods.buyTokensFromExactQuote(amount, new BN(0)).accounts({
             baseMint, quoteMint,
             buyer, buyerBaseAta, buyerQuoteAta,
             poolState,
+            creatorFeeVault: this.pdas.getCreatorFeeVaultAccount(poolState),
+            ...this.getReferrerAccounts(input.referrer, quoteMint),
+            userPosition: this.getUserPositionAccount(mainStateInfo, poolState, buyer),
+            poolObservations: this.getPoolObservationsAccount(poolInfo, poolState),
+            presaleClaim: null,
             mainState: this.pdas.mainState,
             feeRecipient: mainStateInfo.feeRecipient,
             feeQuoteAta,
             reserverBaseAta, reserverQuoteAta,
-            tokenProgram, systemProgram,
+            tokenProgram, baseTokenProgram, systemProgram,
             associatedTokenProgram,
         }).preInstructions([web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 300_000 })]).rpc().catch(buyTxError => {
             debug({ buyTxError })
@@ -260,7 +590,7 @@ export class Connectivity {
         return { Ok: { txSignature } }
     }
 
-    async buy2(input: { amount: number, poolId: string }) {
+    async buy2(input: { amount: number, poolId: string, referrer?: string }) {
         const buyer = this.provider.publicKey
         if (!buyer) return { Err: PumpFunError.WALLET_NOT_FOUND }
         const poolState = getPubkeyFromStr(input.poolId)
@@ -272,16 +602,17 @@ export class Connectivity {
             .catch((fetchPoolInfoError) => { debug({ fetchPoolInfoError }); return null })
         if (!poolInfo) return { Err: PumpFunError.POOL_NOT_FOUND }
         const { baseMint, quoteMint } = poolInfo
+        const baseTokenProgram = await this.getTokenProgram(baseMint)
         const accountInfoes = await getMultipleAccountsInfo(this.provider.connection, [quoteMint])
         if (!accountInfoes) return { Err: PumpFunError.FAILED_TO_FETCH_DATA }
         const [quoteMintAccountInfo] = accountInfoes;
         if (!quoteMintAccountInfo) return { Err: PumpFunError.TOKEN_NOT_FOUND }
         const quoteMintDecimals = MintLayout.decode(quoteMintAccountInfo.data).decimals
         const amount = new BN(toBufferBE(BigInt(calcNonDecimalValue(input.amount, 6).toString()), 8))
-        const buyerBaseAta = getAssociatedTokenAddressSync(baseMint, buyer)
+        const buyerBaseAta = getAssociatedTokenAddressSync(baseMint, buyer, false, baseTokenProgram)
         const buyerQuoteAta = getAssociatedTokenAddressSync(quoteMint, buyer)
         const feeQuoteAta = getAssociatedTokenAddressSync(quoteMint, mainStateInfo.feeRecipient)
-        const reserverBaseAta = getAssociatedTokenAddressSync(baseMint, poolState, true)
+        const reserverBaseAta = getAssociatedTokenAddressSync(baseMint, poolState, true, baseTokenProgram)
         const reserverQuoteAta = getAssociatedTokenAddressSync(quoteMint, poolState, true)
         const result = await this.getInputAmountOnBuy({ outputAmount: input.amount, poolId: input.poolId })
         if (!result) return { Err: PumpFunError.INVALID_INPUT }
@@ -292,11 +623,16 @@ export class Connectivity {
             baseMint, quoteMint,
             buyer, buyerBaseAta, buyerQuoteAta,
             poolState,
+            creatorFeeVault: this.pdas.getCreatorFeeVaultAccount(poolState),
+            ...this.getReferrerAccounts(input.referrer, quoteMint),
+            userPosition: this.getUserPositionAccount(mainStateInfo, poolState, buyer),
+            poolObservations: this.getPoolObservationsAccount(poolInfo, poolState),
+            presaleClaim: null,
             mainState: this.pdas.mainState,
             feeRecipient: mainStateInfo.feeRecipient,
             feeQuoteAta,
             reserverBaseAta, reserverQuoteAta,
-            tokenProgram, systemProgram,
+            tokenProgram, baseTokenProgram, systemProgram,
             associatedTokenProgram,
         }).preInstructions([web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 300_000 })]).rpc().catch(buyTxError => {
             debug({ buyTxError })
@@ -306,7 +642,57 @@ export class Connectivity {
         return { Ok: { txSignature } }
     }
 
-    async sell(input: { amount: number, poolId: string }) {
+    // Buys during presale, allocation (raw base token amount) and proof come from the allowlist Merkle tree
+    async presaleBuy(input: { amount: number, poolId: string, allocation: BN, proof: Buffer[], referrer?: string }) {
+        const buyer = this.provider.publicKey
+        if (!buyer) return { Err: PumpFunError.WALLET_NOT_FOUND }
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+        const mainStateInfo = await this.program.account.mainState.fetch(this.pdas.mainState)
+            .catch((fetchMainStateInfoError) => { debug({ fetchMainStateInfoError }); return null })
+        if (!mainStateInfo) return { Err: PumpFunError.FAILED_TO_FETCH_DATA }
+        const poolInfo = await this.program.account.poolState.fetch(poolState)
+            .catch((fetchPoolInfoError) => { debug({ fetchPoolInfoError }); return null })
+        if (!poolInfo) return { Err: PumpFunError.POOL_NOT_FOUND }
+        const { baseMint, quoteMint } = poolInfo
+        const baseTokenProgram = await this.getTokenProgram(baseMint)
+        const accountInfoes = await getMultipleAccountsInfo(this.provider.connection, [quoteMint])
+        if (!accountInfoes) return { Err: PumpFunError.FAILED_TO_FETCH_DATA }
+        const [quoteMintAccountInfo] = accountInfoes;
+        if (!quoteMintAccountInfo) return { Err: PumpFunError.TOKEN_NOT_FOUND }
+        const quoteMintDecimals = MintLayout.decode(quoteMintAccountInfo.data).decimals
+        const amount = new BN(toBufferBE(BigInt(calcNonDecimalValue(input.amount, quoteMintDecimals).toString()), 8))
+        const buyerBaseAta = getAssociatedTokenAddressSync(baseMint, buyer, false, baseTokenProgram)
+        const buyerQuoteAta = getAssociatedTokenAddressSync(quoteMint, buyer)
+        const feeQuoteAta = getAssociatedTokenAddressSync(quoteMint, mainStateInfo.feeRecipient)
+        const reserverBaseAta = getAssociatedTokenAddressSync(baseMint, poolState, true, baseTokenProgram)
+        const reserverQuoteAta = getAssociatedTokenAddressSync(quoteMint, poolState, true)
+        const proof = input.proof.map((node) => [...node])
+
+        const txSignature = await this.program.methods.presaleBuy(amount, new BN(0), input.allocation, proof).accounts({
+            baseMint, quoteMint,
+            buyer, buyerBaseAta, buyerQuoteAta,
+            poolState,
+            creatorFeeVault: this.pdas.getCreatorFeeVaultAccount(poolState),
+            ...this.getReferrerAccounts(input.referrer, quoteMint),
+            userPosition: this.getUserPositionAccount(mainStateInfo, poolState, buyer),
+            poolObservations: this.getPoolObservationsAccount(poolInfo, poolState),
+            presaleClaim: this.pdas.getPresaleClaimAccount(poolState, buyer),
+            mainState: this.pdas.mainState,
+            feeRecipient: mainStateInfo.feeRecipient,
+            feeQuoteAta,
+            reserverBaseAta, reserverQuoteAta,
+            tokenProgram, baseTokenProgram, systemProgram,
+            associatedTokenProgram,
+        }).preInstructions([web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 300_000 })]).rpc().catch(presaleBuyTxError => {
+            debug({ presaleBuyTxError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    async sell(input: { amount: number, poolId: string, referrer?: string }) {
         const seller = this.provider.publicKey
         if (!seller) return { Err: PumpFunError.WALLET_NOT_FOUND }
         const poolState = getPubkeyFromStr(input.poolId)
@@ -319,11 +705,12 @@ export class Connectivity {
         if (!poolInfo) return { Err: PumpFunError.POOL_NOT_FOUND }
 
         const { baseMint, quoteMint } = poolInfo;
+        const baseTokenProgram = await this.getTokenProgram(baseMint)
         const baseMintDecimals = 6
         const sellAmount = new BN(toBufferBE(BigInt(calcNonDecimalValue(input.amount, baseMintDecimals).toString()), 8))
-        const sellerBaseAta = getAssociatedTokenAddressSync(baseMint, seller)
+        const sellerBaseAta = getAssociatedTokenAddressSync(baseMint, seller, false, baseTokenProgram)
         const sellerQuoteAta = getAssociatedTokenAddressSync(quoteMint, seller)
-        const reserverBaseAta = getAssociatedTokenAddressSync(baseMint, poolState, true)
+        const reserverBaseAta = getAssociatedTokenAddressSync(baseMint, poolState, true, baseTokenProgram)
         const reserverQuoteAta = getAssociatedTokenAddressSync(quoteMint, poolState, true)
         const feeQuoteAta = getAssociatedTokenAddressSync(quoteMint, mainStateInfo.feeRecipient)
 
@@ -333,7 +720,59 @@ export class Connectivity {
             feeRecipient: mainStateInfo.feeRecipient,
             feeQuoteAta,
             poolState, reserverBaseAta, reserverQuoteAta,
-            systemProgram, tokenProgram,
+            creatorFeeVault: this.pdas.getCreatorFeeVaultAccount(poolState),
+            ...this.getReferrerAccounts(input.referrer, quoteMint),
+            userPosition: this.getUserPositionAccount(mainStateInfo, poolState, seller, false),
+            poolObservations: this.getPoolObservationsAccount(poolInfo, poolState),
+            systemProgram, tokenProgram, baseTokenProgram,
+            associatedTokenProgram,
+        }).preInstructions([web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 300_000 })]).rpc().catch(sellTxError => {
+            debug({ sellTxError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    async sellForExactQuote(input: { quoteAmount: number, maxBaseAmount: number, poolId: string, referrer?: string }) {
+        const seller = this.provider.publicKey
+        if (!seller) return { Err: PumpFunError.WALLET_NOT_FOUND }
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+        const mainStateInfo = await this.program.account.mainState.fetch(this.pdas.mainState)
+            .catch((fetchMainStateInfoError) => { debug({ fetchMainStateInfoError }); return null })
+        if (!mainStateInfo) return { Err: PumpFunError.FAILED_TO_FETCH_DATA }
+        const poolInfo = await this.program.account.poolState.fetch(poolState)
+            .catch((fetchPoolInfoError) => { debug({ fetchPoolInfoError }); return null })
+        if (!poolInfo) return { Err: PumpFunError.POOL_NOT_FOUND }
+
+        const { baseMint, quoteMint } = poolInfo;
+        const baseTokenProgram = await this.getTokenProgram(baseMint)
+        const accountInfoes = await getMultipleAccountsInfo(this.provider.connection, [quoteMint])
+        if (!accountInfoes) return { Err: PumpFunError.FAILED_TO_FETCH_DATA }
+        const [quoteMintAccountInfo] = accountInfoes;
+        if (!quoteMintAccountInfo) return { Err: PumpFunError.TOKEN_NOT_FOUND }
+        const quoteMintDecimals = MintLayout.decode(quoteMintAccountInfo.data).decimals
+        const baseMintDecimals = 6
+        const quoteAmount = new BN(toBufferBE(BigInt(calcNonDecimalValue(input.quoteAmount, quoteMintDecimals).toString()), 8))
+        const maxBaseAmount = new BN(toBufferBE(BigInt(calcNonDecimalValue(input.maxBaseAmount, baseMintDecimals).toString()), 8))
+        const sellerBaseAta = getAssociatedTokenAddressSync(baseMint, seller, false, baseTokenProgram)
+        const sellerQuoteAta = getAssociatedTokenAddressSync(quoteMint, seller)
+        const reserverBaseAta = getAssociatedTokenAddressSync(baseMint, poolState, true, baseTokenProgram)
+        const reserverQuoteAta = getAssociatedTokenAddressSync(quoteMint, poolState, true)
+        const feeQuoteAta = getAssociatedTokenAddressSync(quoteMint, mainStateInfo.feeRecipient)
+
+        const txSignature = await this.program.methods.sellTokensForExactQuote(quoteAmount, maxBaseAmount).accounts({
+            seller, sellerBaseAta, sellerQuoteAta,
+            mainState: this.pdas.mainState, baseMint, quoteMint,
+            feeRecipient: mainStateInfo.feeRecipient,
+            feeQuoteAta,
+            poolState, reserverBaseAta, reserverQuoteAta,
+            creatorFeeVault: this.pdas.getCreatorFeeVaultAccount(poolState),
+            ...this.getReferrerAccounts(input.referrer, quoteMint),
+            userPosition: this.getUserPositionAccount(mainStateInfo, poolState, seller, false),
+            poolObservations: this.getPoolObservationsAccount(poolInfo, poolState),
+            systemProgram, tokenProgram, baseTokenProgram,
             associatedTokenProgram,
         }).preInstructions([web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 300_000 })]).rpc().catch(sellTxError => {
             debug({ sellTxError })
@@ -343,7 +782,237 @@ export class Connectivity {
         return { Ok: { txSignature } }
     }
 
-    async withdraw(input: { poolId: string }): Promise<Result<TxPassResult>> {
+    // Creates price history ring buffer of the pool (creator only), every later trade records a sample in it
+    async initPoolObservations(input: { poolId: string }): Promise<Result<TxPassResult>> {
+        const creator = this.provider.publicKey
+        if (!creator) return { Err: PumpFunError.WALLET_NOT_FOUND }
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+
+        const txSignature = await this.program.methods.initPoolObservations().accounts({
+            creator,
+            poolState,
+            poolObservations: this.pdas.getPoolObservationsAccount(poolState),
+            systemProgram,
+        }).rpc().catch(initPoolObservationsError => {
+            debug({ initPoolObservationsError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    // Builds OHLC candles from the pool's price history (mirrors PoolObservations::candles), prices are Q64.64
+    async getCandles(input: { poolId: string, startTs: number, interval: number }) {
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState || input.interval <= 0) return { Err: PumpFunError.INVALID_INPUT }
+        const observationsInfo = await this.program.account.poolObservations.fetch(this.pdas.getPoolObservationsAccount(poolState))
+            .catch((fetchPoolObservationsError) => { debug({ fetchPoolObservationsError }); return null })
+        if (!observationsInfo) return { Err: PumpFunError.FAILED_TO_FETCH_DATA }
+
+        const capacity = observationsInfo.observations.length
+        const count = observationsInfo.count.toNumber()
+        const start = (observationsInfo.head.toNumber() + capacity - count) % capacity
+        const candles: { openTs: number, open: BN, high: BN, low: BN, close: BN, volume: BN, tradeCount: number }[] = []
+        // Cumulative volume before the oldest sample is known only if nothing was overwritten
+        let prevCumulativeVolume: BN | null = count < capacity ? new BN(0) : null
+        for (let i = 0; i < count; i++) {
+            const sample = observationsInfo.observations[(start + i) % capacity]
+            const volume = prevCumulativeVolume ? sample.cumulativeVolume.sub(prevCumulativeVolume) : new BN(0)
+            prevCumulativeVolume = sample.cumulativeVolume
+            const timestamp = sample.timestamp.toNumber()
+            if (timestamp < input.startTs) continue
+
+            const openTs = input.startTs + Math.floor((timestamp - input.startTs) / input.interval) * input.interval
+            const candle = candles[candles.length - 1]
+            if (candle && candle.openTs == openTs) {
+                candle.high = BN.max(candle.high, sample.price)
+                candle.low = BN.min(candle.low, sample.price)
+                candle.close = sample.price
+                candle.volume = candle.volume.add(volume)
+                candle.tradeCount += 1
+            } else {
+                candles.push({ openTs, open: sample.price, high: sample.price, low: sample.price, close: sample.price, volume, tradeCount: 1 })
+            }
+        }
+        return { Ok: { candles } }
+    }
+
+    // Closes the user's position account of a pool, rent goes back to the user
+    async closeUserPosition(input: { poolId: string }): Promise<Result<TxPassResult>> {
+        const user = this.provider.publicKey
+        if (!user) return { Err: PumpFunError.WALLET_NOT_FOUND }
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+
+        const txSignature = await this.program.methods.closeUserPosition().accounts({
+            user,
+            mainState: this.pdas.mainState,
+            poolState,
+            userPosition: this.pdas.getUserPositionAccount(poolState, user),
+        }).rpc().catch(closeUserPositionError => {
+            debug({ closeUserPositionError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    // Simulates a trade without sending it, amounts are raw (non-decimal) values
+    async quoteTrade(input: { kind: 'buyExactQuote' | 'buyExactBase' | 'sell', amount: BN, poolId: string }) {
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+        const poolInfo = await this.program.account.poolState.fetch(poolState)
+            .catch((fetchPoolInfoError) => { debug({ fetchPoolInfoError }); return null })
+        if (!poolInfo) return { Err: PumpFunError.POOL_NOT_FOUND }
+        const method = input.kind == 'buyExactQuote' ? this.program.methods.quoteBuyExactQuote(input.amount)
+            : input.kind == 'buyExactBase' ? this.program.methods.quoteBuyExactBase(input.amount)
+                : this.program.methods.quoteSell(input.amount)
+        // Buyer's position counts towards buy limit of anti-snipe window like on the buy
+        const user = this.provider.publicKey
+        const userPosition = user && input.kind != 'sell' ? this.pdas.getUserPositionAccount(poolState, user) : null
+        const userPositionExists = userPosition && await this.provider.connection.getAccountInfo(userPosition)
+        const tradeQuote = await method.accounts({
+            mainState: this.pdas.mainState, poolState, baseMint: poolInfo.baseMint,
+            userPosition: userPositionExists ? userPosition : null,
+        }).view().catch(quoteTradeError => {
+            debug({ quoteTradeError })
+            return null
+        })
+        if (!tradeQuote) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { tradeQuote } }
+    }
+
+    // Observes cumulative price of the pool (Q64.64 price * seconds), TWAP = delta of priceCumulative / delta of timestamp
+    async observePrice(input: { poolId: string }) {
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+        const poolInfo = await this.program.account.poolState.fetch(poolState)
+            .catch((fetchPoolInfoError) => { debug({ fetchPoolInfoError }); return null })
+        if (!poolInfo) return { Err: PumpFunError.POOL_NOT_FOUND }
+        const observation = await this.program.methods.observePrice().accounts({
+            mainState: this.pdas.mainState, poolState, baseMint: poolInfo.baseMint,
+            userPosition: null,
+        }).view().catch(observePriceError => {
+            debug({ observePriceError })
+            return null
+        })
+        if (!observation) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { observation } }
+    }
+
+    async claimCreatorFees(input: { poolId: string }): Promise<Result<TxPassResult>> {
+        const creator = this.provider.publicKey
+        if (!creator) return { Err: PumpFunError.WALLET_NOT_FOUND }
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+        const poolInfo = await this.program.account.poolState.fetch(poolState)
+            .catch((fetchPoolInfoError) => { debug({ fetchPoolInfoError }); return null })
+        if (!poolInfo) return { Err: PumpFunError.POOL_NOT_FOUND }
+        const { baseMint, quoteMint } = poolInfo
+        const baseTokenProgram = await this.getTokenProgram(baseMint)
+        const creatorQuoteAta = getAssociatedTokenAddressSync(quoteMint, creator)
+
+        const txSignature = await this.program.methods.claimCreatorFees().accounts({
+            creator, poolState,
+            creatorFeeVault: this.pdas.getCreatorFeeVaultAccount(poolState),
+            baseMint, quoteMint,
+            creatorQuoteAta,
+            associatedTokenProgram, tokenProgram, baseTokenProgram, systemProgram,
+        }).rpc().catch(claimCreatorFeesError => {
+            debug({ claimCreatorFeesError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    // Claims creator completion reward reserved when the pool became complete
+    async claimCompletionReward(input: { poolId: string }): Promise<Result<TxPassResult>> {
+        const creator = this.provider.publicKey
+        if (!creator) return { Err: PumpFunError.WALLET_NOT_FOUND }
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+        const poolInfo = await this.program.account.poolState.fetch(poolState)
+            .catch((fetchPoolInfoError) => { debug({ fetchPoolInfoError }); return null })
+        if (!poolInfo) return { Err: PumpFunError.POOL_NOT_FOUND }
+        const { baseMint, quoteMint } = poolInfo
+
+        const txSignature = await this.program.methods.claimCompletionReward().accounts({
+            creator, poolState,
+            baseMint, quoteMint,
+            reserverQuoteAta: getAssociatedTokenAddressSync(quoteMint, poolState, true),
+            creatorQuoteAta: getAssociatedTokenAddressSync(quoteMint, creator),
+            associatedTokenProgram, tokenProgram, systemProgram,
+        }).rpc().catch(claimCompletionRewardError => {
+            debug({ claimCompletionRewardError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    // Returns base tokens for pro-rata share of quote reserves once the pool deadline has passed without completing
+    async refund(input: { amount: number, poolId: string }): Promise<Result<TxPassResult>> {
+        const holder = this.provider.publicKey
+        if (!holder) return { Err: PumpFunError.WALLET_NOT_FOUND }
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+        const poolInfo = await this.program.account.poolState.fetch(poolState)
+            .catch((fetchPoolInfoError) => { debug({ fetchPoolInfoError }); return null })
+        if (!poolInfo) return { Err: PumpFunError.POOL_NOT_FOUND }
+        const { baseMint, quoteMint } = poolInfo
+        const baseTokenProgram = await this.getTokenProgram(baseMint)
+        const baseMintInfo = await getMint(this.provider.connection, baseMint, undefined, baseTokenProgram)
+        const amount = new BN(toBufferBE(BigInt(calcNonDecimalValue(input.amount, baseMintInfo.decimals).toString()), 8))
+
+        const txSignature = await this.program.methods.refund(amount).accounts({
+            holder,
+            mainState: this.pdas.mainState,
+            poolState,
+            baseMint, quoteMint,
+            holderBaseAta: getAssociatedTokenAddressSync(baseMint, holder, false, baseTokenProgram),
+            holderQuoteAta: getAssociatedTokenAddressSync(quoteMint, holder),
+            reserverQuoteAta: getAssociatedTokenAddressSync(quoteMint, poolState, true),
+            associatedTokenProgram, tokenProgram, baseTokenProgram, systemProgram,
+        }).rpc().catch(refundError => {
+            debug({ refundError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    // Creator reclaims unsold base tokens once the pool is in refund state
+    async reclaimUnsold(input: { poolId: string }): Promise<Result<TxPassResult>> {
+        const creator = this.provider.publicKey
+        if (!creator) return { Err: PumpFunError.WALLET_NOT_FOUND }
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+        const poolInfo = await this.program.account.poolState.fetch(poolState)
+            .catch((fetchPoolInfoError) => { debug({ fetchPoolInfoError }); return null })
+        if (!poolInfo) return { Err: PumpFunError.POOL_NOT_FOUND }
+        const { baseMint, quoteMint } = poolInfo
+        const baseTokenProgram = await this.getTokenProgram(baseMint)
+
+        const txSignature = await this.program.methods.reclaimUnsold().accounts({
+            creator,
+            poolState,
+            baseMint, quoteMint,
+            reserverBaseAta: getAssociatedTokenAddressSync(baseMint, poolState, true, baseTokenProgram),
+            creatorBaseAta: getAssociatedTokenAddressSync(baseMint, creator, false, baseTokenProgram),
+            associatedTokenProgram, baseTokenProgram, systemProgram,
+        }).rpc().catch(reclaimUnsoldError => {
+            debug({ reclaimUnsoldError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    // Withdraws from complete pool, amounts are raw token amounts (whole remaining reserves if omitted)
+    // Quote proceeds are split by MainState.withdrawalPolicy, unsold base tokens follow the pool's unsoldBaseAction
+    async withdraw(input: { poolId: string, baseAmount?: BN, quoteAmount?: BN }): Promise<Result<TxPassResult>> {
         const withdrawer = this.provider.publicKey
         if (!withdrawer) return { Err: PumpFunError.WALLET_NOT_FOUND }
 
@@ -357,27 +1026,216 @@ export class Connectivity {
         const poolInfo = await this.program.account.poolState.fetch(poolState)
             .catch((fetchPoolInfoError) => { debug({ fetchPoolInfoError }); return null })
         if (!poolInfo) return { Err: PumpFunError.POOL_NOT_FOUND }
-        const { baseMint, quoteMint } = poolInfo
+        const { baseMint, quoteMint, owner: creator } = poolInfo
+        const { treasury, migrationEscrow } = mainStateInfo.withdrawalPolicy
+        const baseTokenProgram = await this.getTokenProgram(baseMint)
+        const baseAmount = input.baseAmount ?? poolInfo.realBaseReserves
+        const quoteAmount = input.quoteAmount ?? poolInfo.realQuoteReserves
 
-        const reserverBaseAta = getAssociatedTokenAddressSync(baseMint, poolState, true)
+        const reserverBaseAta = getAssociatedTokenAddressSync(baseMint, poolState, true, baseTokenProgram)
         const reserverQuoteAta = getAssociatedTokenAddressSync(quoteMint, poolState, true)
+        const baseVesting = ('lock' in poolInfo.unsoldBaseAction) ? this.pdas.getBaseVestingAccount(poolState) : null
+        const vestingBaseAta = baseVesting ? getAssociatedTokenAddressSync(baseMint, baseVesting, true, baseTokenProgram) : null
 
-        const withdrawerBaseAta = getAssociatedTokenAddressSync(baseMint, withdrawer)
-        const withdrawerQuoteAta = getAssociatedTokenAddressSync(quoteMint, withdrawer)
-
-        const txSignature = await this.program.methods.withdraw().accounts({
+        const txSignature = await this.program.methods.withdraw(baseAmount, quoteAmount).accounts({
             withdrawer, 
             mainState, poolState, 
             baseMint, quoteMint, 
             reserverBaseAta, reserverQuoteAta, 
-            withdrawerBaseAta, withdrawerQuoteAta, 
-            systemProgram, tokenProgram,
+            treasury,
+            treasuryQuoteAta: getAssociatedTokenAddressSync(quoteMint, treasury),
+            creator,
+            creatorQuoteAta: getAssociatedTokenAddressSync(quoteMint, creator),
+            migrationEscrow,
+            migrationEscrowBaseAta: getAssociatedTokenAddressSync(baseMint, migrationEscrow, false, baseTokenProgram),
+            migrationEscrowQuoteAta: getAssociatedTokenAddressSync(quoteMint, migrationEscrow),
+            baseVesting, vestingBaseAta,
+            systemProgram, tokenProgram, baseTokenProgram,
             associatedTokenProgram,
-        }).rpc().catch((collectTradingFeeError) => debug({ collectTradingFeeError }));
+        }).preInstructions([web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })]).rpc().catch((withdrawError) => debug({ withdrawError }));
         if (!txSignature) return { Err: PumpFunError.TX_FAILED }
         return { Ok: { txSignature } }
     }
 
+    // Releases vested part of locked unsold base tokens to beneficiary (treasury)
+    async releaseVestedBase(input: { poolId: string }): Promise<Result<TxPassResult>> {
+        const beneficiary = this.provider.publicKey
+        if (!beneficiary) return { Err: PumpFunError.WALLET_NOT_FOUND }
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+        // BaseVesting keeps its base mint, so it can be released after the pool is closed
+        const baseVesting = this.pdas.getBaseVestingAccount(poolState)
+        const baseVestingInfo = await this.program.account.baseVesting.fetch(baseVesting)
+            .catch((fetchBaseVestingInfoError) => { debug({ fetchBaseVestingInfoError }); return null })
+        if (!baseVestingInfo) return { Err: PumpFunError.FAILED_TO_FETCH_DATA }
+        const { baseMint } = baseVestingInfo
+        const baseTokenProgram = await this.getTokenProgram(baseMint)
+
+        const txSignature = await this.program.methods.releaseVestedBase().accounts({
+            beneficiary,
+            baseVesting,
+            baseMint,
+            vestingBaseAta: getAssociatedTokenAddressSync(baseMint, baseVesting, true, baseTokenProgram),
+            beneficiaryBaseAta: getAssociatedTokenAddressSync(baseMint, beneficiary, false, baseTokenProgram),
+            associatedTokenProgram, baseTokenProgram, systemProgram,
+        }).rpc().catch(releaseVestedBaseError => {
+            debug({ releaseVestedBaseError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    // Closes withdrawn or migrated pool (withdrawer or creator), rent goes back to creator
+    async closePool(input: { poolId: string }): Promise<Result<TxPassResult>> {
+        const closer = this.provider.publicKey
+        if (!closer) return { Err: PumpFunError.WALLET_NOT_FOUND }
+
+        const mainState = this.pdas.mainState
+        const mainStateInfo = await this.program.account.mainState.fetch(mainState)
+            .catch((fetchMainStateInfoError) => { debug({ fetchMainStateInfoError }); return null })
+        if (!mainStateInfo) return { Err: PumpFunError.FAILED_TO_FETCH_DATA }
+
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+        const poolInfo = await this.program.account.poolState.fetch(poolState)
+            .catch((fetchPoolInfoError) => { debug({ fetchPoolInfoError }); return null })
+        if (!poolInfo) return { Err: PumpFunError.POOL_NOT_FOUND }
+        const { baseMint, quoteMint, owner: creator } = poolInfo
+        const { withdrawer } = mainStateInfo
+        const baseTokenProgram = await this.getTokenProgram(baseMint)
+
+        const txSignature = await this.program.methods.closePool().accounts({
+            closer,
+            mainState, poolState,
+            creator, withdrawer,
+            baseMint, quoteMint,
+            reserverBaseAta: getAssociatedTokenAddressSync(baseMint, poolState, true, baseTokenProgram),
+            reserverQuoteAta: getAssociatedTokenAddressSync(quoteMint, poolState, true),
+            creatorFeeVault: this.pdas.getCreatorFeeVaultAccount(poolState),
+            creatorQuoteAta: getAssociatedTokenAddressSync(quoteMint, creator),
+            withdrawerQuoteAta: getAssociatedTokenAddressSync(quoteMint, withdrawer),
+            associatedTokenProgram, tokenProgram, baseTokenProgram, systemProgram,
+        }).rpc().catch(closePoolError => {
+            debug({ closePoolError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    async closeRefundedPool(input: { poolId: string }): Promise<Result<TxPassResult>> {
+        const closer = this.provider.publicKey
+        if (!closer) return { Err: PumpFunError.WALLET_NOT_FOUND }
+
+        const mainState = this.pdas.mainState
+        const mainStateInfo = await this.program.account.mainState.fetch(mainState)
+            .catch((fetchMainStateInfoError) => { debug({ fetchMainStateInfoError }); return null })
+        if (!mainStateInfo) return { Err: PumpFunError.FAILED_TO_FETCH_DATA }
+
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+        const poolInfo = await this.program.account.poolState.fetch(poolState)
+            .catch((fetchPoolInfoError) => { debug({ fetchPoolInfoError }); return null })
+        if (!poolInfo) return { Err: PumpFunError.POOL_NOT_FOUND }
+        const { baseMint, quoteMint, owner: creator } = poolInfo
+        const { withdrawer } = mainStateInfo
+        const baseTokenProgram = await this.getTokenProgram(baseMint)
+
+        const txSignature = await this.program.methods.closeRefundedPool().accounts({
+            closer,
+            mainState, poolState,
+            creator, withdrawer,
+            baseMint, quoteMint,
+            reserverBaseAta: getAssociatedTokenAddressSync(baseMint, poolState, true, baseTokenProgram),
+            reserverQuoteAta: getAssociatedTokenAddressSync(quoteMint, poolState, true),
+            creatorFeeVault: this.pdas.getCreatorFeeVaultAccount(poolState),
+            creatorQuoteAta: getAssociatedTokenAddressSync(quoteMint, creator),
+            withdrawerQuoteAta: getAssociatedTokenAddressSync(quoteMint, withdrawer),
+            associatedTokenProgram, tokenProgram, baseTokenProgram, systemProgram,
+        }).rpc().catch(closeRefundedPoolError => {
+            debug({ closeRefundedPoolError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature } }
+    }
+
+    async migratePool(input: { poolId: string }): Promise<Result<TxPassResult & { ammPoolId: string }>> {
+        const payer = this.provider.publicKey
+        if (!payer) return { Err: PumpFunError.WALLET_NOT_FOUND }
+
+        const poolState = getPubkeyFromStr(input.poolId)
+        if (!poolState) return { Err: PumpFunError.INVALID_INPUT }
+        const poolInfo = await this.program.account.poolState.fetch(poolState)
+            .catch((fetchPoolInfoError) => { debug({ fetchPoolInfoError }); return null })
+        if (!poolInfo) return { Err: PumpFunError.POOL_NOT_FOUND }
+        const { baseMint, quoteMint } = poolInfo
+        const baseTokenProgram = await this.getTokenProgram(baseMint)
+
+        const reserverBaseAta = getAssociatedTokenAddressSync(baseMint, poolState, true, baseTokenProgram)
+        const reserverQuoteAta = getAssociatedTokenAddressSync(quoteMint, poolState, true)
+
+        const lpLocker = this.pdas.getLpLockerAccount(poolState)
+        const ammPool = this.pdas.getAmmPoolAccount({ baseMint, quoteMint })
+        const lpMint = this.pdas.getLpMintAccount(ammPool)
+        const ammBaseVault = getAssociatedTokenAddressSync(baseMint, ammPool, true)
+        const ammQuoteVault = getAssociatedTokenAddressSync(quoteMint, ammPool, true)
+        const lockerLpAta = getAssociatedTokenAddressSync(lpMint, lpLocker, true)
+
+        const txSignature = await this.program.methods.migratePool().accounts({
+            payer,
+            mainState: this.pdas.mainState,
+            poolState,
+            baseMint, quoteMint,
+            reserverBaseAta, reserverQuoteAta,
+            lpLocker,
+            ammPool, lpMint,
+            ammBaseVault, ammQuoteVault,
+            lockerLpAta,
+            ammProgram,
+            associatedTokenProgram,
+            tokenProgram,
+            baseTokenProgram,
+            systemProgram,
+            rent: web3.SYSVAR_RENT_PUBKEY,
+        }).preInstructions([web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })]).rpc().catch(migratePoolError => {
+            debug({ migratePoolError })
+            return null
+        })
+        if (!txSignature) return { Err: PumpFunError.TX_FAILED }
+        return { Ok: { txSignature, ammPoolId: ammPool.toBase58() } }
+    }
+
+    // Optional referrer accounts of buy/sell instructions (all null if no referrer)
+    // UserPosition is only needed while positions are tracked, or on buying while anti-snipe window is enabled
+    getUserPositionAccount(mainStateInfo: { antiSnipeWindow: BN, trackPositions: boolean }, poolState: web3.PublicKey, user: web3.PublicKey, isBuy = true) {
+        if (!mainStateInfo.trackPositions && (!isBuy || mainStateInfo.antiSnipeWindow.isZero())) return null
+        return this.pdas.getUserPositionAccount(poolState, user)
+    }
+
+    // PoolObservations is only needed once enabled on the pool
+    getPoolObservationsAccount(poolInfo: { observationsEnabled: boolean }, poolState: web3.PublicKey) {
+        if (!poolInfo.observationsEnabled) return null
+        return this.pdas.getPoolObservationsAccount(poolState)
+    }
+
+    getReferrerAccounts(referrerStr: string | undefined, quoteMint: web3.PublicKey) {
+        const referrer = referrerStr ? getPubkeyFromStr(referrerStr) : null
+        if (!referrer) return { referrer: null, referrerQuoteAta: null, referrerStats: null }
+        return {
+            referrer,
+            referrerQuoteAta: getAssociatedTokenAddressSync(quoteMint, referrer),
+            referrerStats: this.pdas.getReferrerStatsAccount(referrer),
+        }
+    }
+
+    // Token program owning the mint (SPL Token or Token-2022)
+    async getTokenProgram(mint: web3.PublicKey): Promise<web3.PublicKey> {
+        const mintAccountInfo = await this.provider.connection.getAccountInfo(mint)
+        return mintAccountInfo?.owner ?? tokenProgram
+    }
+
     async getMainStateInfo(): Promise<MainStateInfo | null> {
         const mainState = this.pdas.mainState
         const mainStateInfo = await this.program.account.mainState.fetch(mainState).catch(fetchMainStateError => {
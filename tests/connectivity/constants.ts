@@ -4,14 +4,41 @@ import { Logger, ILogObj } from 'tslog'
 
 export const Seeds = {
     main: Buffer.from('main'),
-    pool: Buffer.from('pool')
+    pool: Buffer.from('pool'),
+    quoteConfig: Buffer.from('quote_config'),
+    pendingMainUpdate: Buffer.from('pending_main_update'),
+    lpLocker: Buffer.from('lp_locker'),
+    ammPool: Buffer.from('amm_pool'),
+    lpMint: Buffer.from('lp_mint'),
+    creatorFeeVault: Buffer.from('creator_fee_vault'),
+    referrerStats: Buffer.from('referrer_stats'),
+    userPosition: Buffer.from('user_position'),
+    presaleClaim: Buffer.from('presale_claim'),
+    baseVesting: Buffer.from('base_vesting'),
+    poolObservations: Buffer.from('pool_observations'),
+    metadata: Buffer.from('metadata'),
 }
 
+export const AMM_PROGRAM_ID = new web3.PublicKey('9v3nY7JNeKDFtxzT4jWmQbLJVgK3wZpcx2MsXiqNT8X7')
+export const METADATA_PROGRAM_ID = new web3.PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bWg2YKB4s6s')
+
 export const FEE_PRE_DIV = 1000
+
+export type WindowUnit = 'slots' | 'seconds'
+
+export type UnsoldBaseAction = 'burn' | 'lock' | 'pair'
+
+export const CURVE_TYPES = ['constantProduct', 'linear', 'exponential', 'stepped'] as const
+export type CurveType = typeof CURVE_TYPES[number]
+export function curveTypeMask(curveType: CurveType) {
+    return 1 << CURVE_TYPES.indexOf(curveType)
+}
 export const PROGRAMS = {
     systemProgram: web3.SystemProgram.programId,
     tokenProgram: TOKEN_PROGRAM_ID,
     associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+    ammProgram: AMM_PROGRAM_ID,
+    metadataProgram: METADATA_PROGRAM_ID,
 }
 
 export const log: Logger<ILogObj> = new Logger();
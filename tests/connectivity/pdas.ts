@@ -1,12 +1,21 @@
 import { web3 } from "@coral-xyz/anchor";
-import { Seeds } from './constants'
+import { AMM_PROGRAM_ID, METADATA_PROGRAM_ID, Seeds } from './constants'
 
 export class Pdas {
     programId: web3.PublicKey
     mainState: web3.PublicKey
+    pendingMainStateUpdate: web3.PublicKey
     constructor(programId: web3.PublicKey) {
         this.programId = programId
         this.mainState = web3.PublicKey.findProgramAddressSync([Seeds.main], this.programId)[0]
+        this.pendingMainStateUpdate = web3.PublicKey.findProgramAddressSync([Seeds.pendingMainUpdate], this.programId)[0]
+    }
+
+    getQuoteConfigAccount(quoteMint: web3.PublicKey) {
+        return web3.PublicKey.findProgramAddressSync([
+            Seeds.quoteConfig,
+            quoteMint.toBuffer(),
+        ], this.programId)[0]
     }
 
     getPoolStateAccount({ baseMint, quoteMint, owner }: { baseMint: web3.PublicKey, quoteMint: web3.PublicKey, owner: web3.PublicKey }) {
@@ -16,4 +25,78 @@ export class Pdas {
             quoteMint.toBuffer(),
         ], this.programId)[0]
     }
+
+    getLpLockerAccount(poolState: web3.PublicKey) {
+        return web3.PublicKey.findProgramAddressSync([
+            Seeds.lpLocker,
+            poolState.toBuffer(),
+        ], this.programId)[0]
+    }
+
+    getCreatorFeeVaultAccount(poolState: web3.PublicKey) {
+        return web3.PublicKey.findProgramAddressSync([
+            Seeds.creatorFeeVault,
+            poolState.toBuffer(),
+        ], this.programId)[0]
+    }
+
+    getReferrerStatsAccount(referrer: web3.PublicKey) {
+        return web3.PublicKey.findProgramAddressSync([
+            Seeds.referrerStats,
+            referrer.toBuffer(),
+        ], this.programId)[0]
+    }
+
+    getUserPositionAccount(poolState: web3.PublicKey, user: web3.PublicKey) {
+        return web3.PublicKey.findProgramAddressSync([
+            Seeds.userPosition,
+            poolState.toBuffer(),
+            user.toBuffer(),
+        ], this.programId)[0]
+    }
+
+    getPresaleClaimAccount(poolState: web3.PublicKey, user: web3.PublicKey) {
+        return web3.PublicKey.findProgramAddressSync([
+            Seeds.presaleClaim,
+            poolState.toBuffer(),
+            user.toBuffer(),
+        ], this.programId)[0]
+    }
+
+    getBaseVestingAccount(poolState: web3.PublicKey) {
+        return web3.PublicKey.findProgramAddressSync([
+            Seeds.baseVesting,
+            poolState.toBuffer(),
+        ], this.programId)[0]
+    }
+
+    getPoolObservationsAccount(poolState: web3.PublicKey) {
+        return web3.PublicKey.findProgramAddressSync([
+            Seeds.poolObservations,
+            poolState.toBuffer(),
+        ], this.programId)[0]
+    }
+
+    getMetadataAccount(mint: web3.PublicKey) {
+        return web3.PublicKey.findProgramAddressSync([
+            Seeds.metadata,
+            METADATA_PROGRAM_ID.toBuffer(),
+            mint.toBuffer(),
+        ], METADATA_PROGRAM_ID)[0]
+    }
+
+    getAmmPoolAccount({ baseMint, quoteMint }: { baseMint: web3.PublicKey, quoteMint: web3.PublicKey }) {
+        return web3.PublicKey.findProgramAddressSync([
+            Seeds.ammPool,
+            baseMint.toBuffer(),
+            quoteMint.toBuffer(),
+        ], AMM_PROGRAM_ID)[0]
+    }
+
+    getLpMintAccount(ammPool: web3.PublicKey) {
+        return web3.PublicKey.findProgramAddressSync([
+            Seeds.lpMint,
+            ammPool.toBuffer(),
+        ], AMM_PROGRAM_ID)[0]
+    }
 }
\ No newline at end of file
@@ -1,5 +1,7 @@
 import { web3 } from "@coral-xyz/anchor";
 import { debug } from "./constants";
+import { createHash } from "crypto";
+import BN from "bn.js";
 
 export async function sleep(ms: number) {
     return new Promise(resolve => setTimeout(resolve, ms));
@@ -50,3 +52,34 @@ export function calculateInputAmount({ outputAmount, inputReserve, outputReserve
     const divider = outputReserve - outputAmount
     return Math.trunc(amount / divider)
 }
+
+// Presale allowlist leaf: sha256(0x00 || user || allocation as little-endian u64)
+export function presaleLeaf(user: web3.PublicKey, allocation: BN) {
+    return createHash('sha256').update(Buffer.concat([Buffer.from([0x00]), user.toBuffer(), allocation.toArrayLike(Buffer, 'le', 8)])).digest()
+}
+
+// Builds presale Merkle tree (sibling pairs are sorted and hashed as sha256(0x01 || left || right), odd node is promoted)
+// Returns the root and the proof of each entry
+export function buildPresaleMerkleTree(entries: { user: web3.PublicKey, allocation: BN }[]) {
+    let level = entries.map(({ user, allocation }) => presaleLeaf(user, allocation))
+    let indices = entries.map((_, i) => i)
+    const proofs: Buffer[][] = entries.map(() => [])
+    while (level.length > 1) {
+        const next: Buffer[] = []
+        for (let i = 0; i < level.length; i += 2) {
+            if (i + 1 == level.length) {
+                next.push(level[i])
+                continue
+            }
+            const [left, right] = Buffer.compare(level[i], level[i + 1]) <= 0 ? [level[i], level[i + 1]] : [level[i + 1], level[i]]
+            next.push(createHash('sha256').update(Buffer.concat([Buffer.from([0x01]), left, right])).digest())
+        }
+        indices = indices.map((index, entry) => {
+            const sibling = index ^ 1
+            if (sibling < level.length) proofs[entry].push(level[sibling])
+            return index >> 1
+        })
+        level = next
+    }
+    return { root: level[0], proofs }
+}
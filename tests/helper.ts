@@ -1,5 +1,13 @@
 import { AnchorProvider, web3 } from "@coral-xyz/anchor";
 import { BaseSpl } from "./connectivity/base/baseSpl";
+import { Connectivity } from "./connectivity/ali_index";
+import { sleep } from "./connectivity/utils";
+import {
+    AuthorityType, ExtensionType, TOKEN_2022_PROGRAM_ID,
+    createAssociatedTokenAccountInstruction, createInitializeMintInstruction, createInitializeTransferFeeConfigInstruction,
+    createMintToInstruction, createSetAuthorityInstruction, getAssociatedTokenAddressSync, getMintLen,
+} from "@solana/spl-token";
+import BN from "bn.js";
 
 export async function createToken({ decimals, supply }: { decimals: number, supply: number }, provider: AnchorProvider) {
     const connection = provider.connection
@@ -28,3 +36,44 @@ export async function transferToken({ to, mint, amount }: { to: string, mint: st
     const txSignatrue = await provider.sendAndConfirm(tx)
     return txSignatrue
 }
+
+// Returns raw token balance of a token account (0 if the account doesn't exist)
+export async function getTokenBalance(connection: web3.Connection, tokenAccount: web3.PublicKey) {
+    const balance = await connection.getTokenAccountBalance(tokenAccount).catch(() => null)
+    return new BN(balance?.value.amount ?? "0")
+}
+
+// Creates base token (1B supply, 6 decimals) with the provider and creates its pool with the connectivity
+//   createPool result is returned as is so that expected failures can be asserted
+export async function createTokenAndPool(input: Omit<Parameters<Connectivity['createPool']>[0], 'baseToken'>, connectivity: Connectivity, provider: AnchorProvider) {
+    const createTokenTxInfo = await createToken({ decimals: 6, supply: 1_000_000_000 }, provider)
+    const baseToken = createTokenTxInfo.mint.toBase58()
+    await sleep(3_000)
+    const res = await connectivity.createPool({ baseToken, ...input })
+    return { baseToken, res }
+}
+
+// Creates Token-2022 mint with transfer fee extension, mints supply to the provider and revokes mint authority
+//   Transfer fee authorities are not set unless feeAuthority is given
+export async function createToken2022WithTransferFee({ decimals, supply, feeBasisPoints, maxFee, feeAuthority = null }: { decimals: number, supply: number, feeBasisPoints: number, maxFee: bigint, feeAuthority?: web3.PublicKey | null }, provider: AnchorProvider) {
+    const connection = provider.connection
+    const owner = provider.publicKey
+    const mintKeypair = web3.Keypair.generate()
+    const mint = mintKeypair.publicKey
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig])
+    const lamports = await connection.getMinimumBalanceForRentExemption(mintLen)
+    const ata = getAssociatedTokenAddressSync(mint, owner, false, TOKEN_2022_PROGRAM_ID)
+    const tx = new web3.Transaction().add(
+        web3.SystemProgram.createAccount({ fromPubkey: owner, newAccountPubkey: mint, space: mintLen, lamports, programId: TOKEN_2022_PROGRAM_ID }),
+        createInitializeTransferFeeConfigInstruction(mint, feeAuthority, feeAuthority, feeBasisPoints, maxFee, TOKEN_2022_PROGRAM_ID),
+        createInitializeMintInstruction(mint, decimals, owner, null, TOKEN_2022_PROGRAM_ID),
+        createAssociatedTokenAccountInstruction(owner, ata, owner, mint, TOKEN_2022_PROGRAM_ID),
+        createMintToInstruction(mint, ata, owner, BigInt(supply) * BigInt(10 ** decimals), [], TOKEN_2022_PROGRAM_ID),
+        createSetAuthorityInstruction(mint, owner, AuthorityType.MintTokens, null, [], TOKEN_2022_PROGRAM_ID),
+    )
+    const txSignature = await provider.sendAndConfirm(tx, [mintKeypair])
+    return {
+        txSignature,
+        mint
+    }
+}
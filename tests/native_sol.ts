@@ -0,0 +1,132 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { AliPumpFun } from "../target/types/ali_pump_fun";
+import { Connectivity } from "./connectivity/ali_index";
+import { createToken } from "./helper";
+import { sleep } from "./connectivity/utils";
+import { NATIVE_MINT, getAssociatedTokenAddressSync, createAssociatedTokenAccountIdempotentInstruction, createSyncNativeInstruction } from "@solana/spl-token";
+import { assert } from "chai";
+
+const log = console.log
+
+// Pools quoted in wrapped SOL: traders pay and receive lamports, WSOL accounts are wrapped/closed by the program
+describe("ali_pump_fun (native SOL)", () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider);
+  const program = anchor.workspace.AliPumpFun as Program<AliPumpFun>;
+  const connectivity = new Connectivity({ programId: program.programId, rpcEndPoint: provider.connection.rpcEndpoint, walletInfo: provider })
+
+  const creatorAuthority = web3.Keypair.generate()
+  const creatorProvider = new anchor.AnchorProvider(provider.connection, new anchor.Wallet(creatorAuthority), {})
+  const creatorConnectivity = new Connectivity({ programId: program.programId, rpcEndPoint: provider.connection.rpcEndpoint, walletInfo: creatorProvider })
+  const creator = creatorAuthority.publicKey
+
+  const userAuthority = web3.Keypair.generate()
+  const userProvider = new anchor.AnchorProvider(provider.connection, new anchor.Wallet(userAuthority), {})
+  const userConnectivity = new Connectivity({ programId: program.programId, rpcEndPoint: provider.connection.rpcEndpoint, walletInfo: userProvider })
+  const user = userAuthority.publicKey
+
+  const connection = provider.connection;
+  const quoteToken = NATIVE_MINT.toBase58()
+  const userWsolAta = getAssociatedTokenAddressSync(NATIVE_MINT, user)
+  const commonState: { mint?: string, poolId?: string } = {}
+
+  before(async () => {
+    await connection.requestAirdrop(creator, 1_000_000_000) // 1 SOL
+    await connection.requestAirdrop(user, 5_000_000_000) // 5 SOL
+    await sleep(3000)
+
+    const mainStateInfo = await connection.getAccountInfo(connectivity.pdas.mainState)
+    if (!mainStateInfo) {
+      await connectivity.initMainState({ quoteToken })
+    }
+    const quoteConfigInfo = await connection.getAccountInfo(connectivity.pdas.getQuoteConfigAccount(NATIVE_MINT))
+    if (!quoteConfigInfo) {
+      // 30 SOL virtual reserves, complete at +1 SOL
+      const res = await connectivity.addQuoteMint({ quoteToken, initVirtQuoteReserves: 30_000_000_000, realQuoteThreshold: 1_000_000_000 })
+      if (!res.Ok) throw "addQuoteMint failed"
+    }
+
+    const createTokenTxInfo = await createToken({ decimals: 6, supply: 1_000_000_000 }, creatorProvider)
+    commonState.mint = createTokenTxInfo.mint.toBase58()
+    log('baseToken:', commonState.mint)
+  })
+
+  it("create pool", async () => {
+    const baseToken = commonState.mint
+    if (!baseToken) throw "Base token not found"
+    const res = await creatorConnectivity.createPool({ baseToken, quoteToken })
+    if (!res.Ok) throw "create pool failed"
+    commonState.poolId = res.Ok.poolId
+  })
+
+  it("buy with lamports", async () => {
+    const poolId = commonState.poolId
+    if (!poolId) throw "pool id not found"
+    const lamportsBefore = await connection.getBalance(user)
+    const res = await userConnectivity.buy({ poolId, amount: 0.1 })
+    if (!res.Ok) throw "buy failed"
+    await sleep(1000)
+    const lamportsAfter = await connection.getBalance(user)
+    assert.isTrue(lamportsBefore - lamportsAfter >= 100_000_000, "lamports must be spent")
+    assert.isNull(await connection.getAccountInfo(userWsolAta), "WSOL account must be closed")
+  })
+
+  it("sell for lamports", async () => {
+    const poolId = commonState.poolId
+    if (!poolId) throw "pool id not found"
+    const baseMint = new web3.PublicKey(commonState.mint as string)
+    const baseBalance = await connection.getTokenAccountBalance(getAssociatedTokenAddressSync(baseMint, user))
+    const lamportsBefore = await connection.getBalance(user)
+    const res = await userConnectivity.sell({ poolId, amount: Number(baseBalance.value.uiAmount) })
+    if (!res.Ok) throw "sell failed"
+    await sleep(1000)
+    const lamportsAfter = await connection.getBalance(user)
+    assert.isTrue(lamportsAfter > lamportsBefore, "lamports must be received")
+    assert.isNull(await connection.getAccountInfo(userWsolAta), "WSOL account must be closed")
+  })
+
+  it("keep pre-existing WSOL wrapped", async () => {
+    const poolId = commonState.poolId
+    if (!poolId) throw "pool id not found"
+    // User holds 0.5 WSOL before trading
+    const wrapTx = new web3.Transaction().add(
+      createAssociatedTokenAccountIdempotentInstruction(user, userWsolAta, user, NATIVE_MINT),
+      web3.SystemProgram.transfer({ fromPubkey: user, toPubkey: userWsolAta, lamports: 500_000_000 }),
+      createSyncNativeInstruction(userWsolAta),
+    )
+    await userProvider.sendAndConfirm(wrapTx)
+
+    const buyRes = await userConnectivity.buy({ poolId, amount: 0.1 }) // Paid from WSOL held
+    if (!buyRes.Ok) throw "buy failed"
+    await sleep(1000)
+    const wsolAfterBuy = Number((await connection.getTokenAccountBalance(userWsolAta)).value.amount)
+    assert.isTrue(wsolAfterBuy > 0 && wsolAfterBuy <= 400_000_000, "remaining WSOL must stay wrapped")
+
+    const baseMint = new web3.PublicKey(commonState.mint as string)
+    const baseBalance = await connection.getTokenAccountBalance(getAssociatedTokenAddressSync(baseMint, user))
+    const sellRes = await userConnectivity.sell({ poolId, amount: Number(baseBalance.value.uiAmount) })
+    if (!sellRes.Ok) throw "sell failed"
+    await sleep(1000)
+    const wsolAfterSell = Number((await connection.getTokenAccountBalance(userWsolAta)).value.amount)
+    assert.isTrue(wsolAfterSell > wsolAfterBuy, "sold WSOL must be received into the kept account")
+  })
+
+  it("withdraw lamports", async () => {
+    const poolId = commonState.poolId
+    if (!poolId) throw "pool id not found"
+    const buyRes = await userConnectivity.buy({ poolId, amount: 2 }) // clipped to the threshold, completes the curve
+    if (!buyRes.Ok) throw "buy failed"
+    await sleep(1000)
+
+    const withdrawer = provider.publicKey
+    const lamportsBefore = await connection.getBalance(withdrawer)
+    const res = await connectivity.withdraw({ poolId })
+    if (!res.Ok) throw "withdraw failed"
+    await sleep(1000)
+    const lamportsAfter = await connection.getBalance(withdrawer)
+    assert.isTrue(lamportsAfter - lamportsBefore >= 900_000_000, "withdrawer must receive lamports")
+    assert.isNull(await connection.getAccountInfo(getAssociatedTokenAddressSync(NATIVE_MINT, withdrawer)), "WSOL account must be closed")
+  })
+})
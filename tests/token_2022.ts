@@ -0,0 +1,92 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { AliPumpFun } from "../target/types/ali_pump_fun";
+import { Connectivity } from "./connectivity/ali_index";
+import { createToken2022WithTransferFee } from "./helper";
+import { sleep } from "./connectivity/utils";
+import { NATIVE_MINT, TOKEN_2022_PROGRAM_ID, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { assert } from "chai";
+
+const log = console.log
+
+// Token-2022 base token with 1% transfer fee: curve reserves only count tokens the pool actually holds
+describe("ali_pump_fun (Token-2022)", () => {
+  const provider = anchor.AnchorProvider.env()
+  anchor.setProvider(provider);
+  const program = anchor.workspace.AliPumpFun as Program<AliPumpFun>;
+  const connectivity = new Connectivity({ programId: program.programId, rpcEndPoint: provider.connection.rpcEndpoint, walletInfo: provider })
+
+  const creatorAuthority = web3.Keypair.generate()
+  const creatorProvider = new anchor.AnchorProvider(provider.connection, new anchor.Wallet(creatorAuthority), {})
+  const creatorConnectivity = new Connectivity({ programId: program.programId, rpcEndPoint: provider.connection.rpcEndpoint, walletInfo: creatorProvider })
+  const creator = creatorAuthority.publicKey
+
+  const userAuthority = web3.Keypair.generate()
+  const userProvider = new anchor.AnchorProvider(provider.connection, new anchor.Wallet(userAuthority), {})
+  const userConnectivity = new Connectivity({ programId: program.programId, rpcEndPoint: provider.connection.rpcEndpoint, walletInfo: userProvider })
+  const user = userAuthority.publicKey
+
+  const connection = provider.connection;
+  const quoteToken = NATIVE_MINT.toBase58()
+  const commonState: { mint?: string, poolId?: string } = {}
+
+  before(async () => {
+    await connection.requestAirdrop(creator, 1_000_000_000) // 1 SOL
+    await connection.requestAirdrop(user, 5_000_000_000) // 5 SOL
+    await sleep(3000)
+
+    const mainStateInfo = await connection.getAccountInfo(connectivity.pdas.mainState)
+    if (!mainStateInfo) {
+      await connectivity.initMainState({ quoteToken })
+    }
+    const quoteConfigInfo = await connection.getAccountInfo(connectivity.pdas.getQuoteConfigAccount(NATIVE_MINT))
+    if (!quoteConfigInfo) {
+      const res = await connectivity.addQuoteMint({ quoteToken, initVirtQuoteReserves: 30_000_000_000, realQuoteThreshold: 1_000_000_000 })
+      if (!res.Ok) throw "addQuoteMint failed"
+    }
+
+    const createTokenTxInfo = await createToken2022WithTransferFee({ decimals: 6, supply: 1_000_000_000, feeBasisPoints: 100, maxFee: BigInt(1_000_000_000_000) }, creatorProvider)
+    commonState.mint = createTokenTxInfo.mint.toBase58()
+    log('baseToken:', commonState.mint)
+  })
+
+  it("create pool", async () => {
+    const baseToken = commonState.mint
+    if (!baseToken) throw "Base token not found"
+    const res = await creatorConnectivity.createPool({ baseToken, quoteToken })
+    if (!res.Ok) throw "create pool failed"
+    commonState.poolId = res.Ok.poolId
+    await sleep(1000)
+
+    const poolInfo = await program.account.poolState.fetch(res.Ok.poolId)
+    const reserverBaseAta = getAssociatedTokenAddressSync(poolInfo.baseMint, new web3.PublicKey(res.Ok.poolId), true, TOKEN_2022_PROGRAM_ID)
+    const reserverBalance = await connection.getTokenAccountBalance(reserverBaseAta)
+    assert.equal(poolInfo.realBaseReserves.toString(), reserverBalance.value.amount, "reserves must exclude withheld transfer fee")
+  })
+
+  it("create pool (BaseTokenTransferFeeMustNotBeManaged: Fail)", async () => {
+    const createTokenTxInfo = await createToken2022WithTransferFee({ decimals: 6, supply: 1_000_000_000, feeBasisPoints: 100, maxFee: BigInt(1_000_000_000_000), feeAuthority: creator }, creatorProvider)
+    const res = await creatorConnectivity.createPool({ baseToken: createTokenTxInfo.mint.toBase58(), quoteToken })
+    assert.isDefined(res.Err, "transfer fee must not be updatable by its authority")
+  })
+
+  it("buy and sell", async () => {
+    const poolId = commonState.poolId
+    if (!poolId) throw "pool id not found"
+    const baseMint = new web3.PublicKey(commonState.mint as string)
+    const quote = await userConnectivity.quoteTrade({ kind: 'buyExactQuote', amount: new anchor.BN(100_000_000), poolId })
+    if (!quote.Ok) throw "quote failed"
+    assert.isTrue(quote.Ok.tradeQuote.transferFee.gtn(0), "transfer fee must be withheld")
+
+    const buyRes = await userConnectivity.buy({ poolId, amount: 0.1 })
+    if (!buyRes.Ok) throw "buy failed"
+    await sleep(1000)
+    const userBaseAta = getAssociatedTokenAddressSync(baseMint, user, false, TOKEN_2022_PROGRAM_ID)
+    const baseBalance = await connection.getTokenAccountBalance(userBaseAta)
+    assert.equal(baseBalance.value.amount, quote.Ok.tradeQuote.outputAmount.toString(), "buyer must receive output after transfer fee")
+
+    const sellRes = await userConnectivity.sell({ poolId, amount: Number(baseBalance.value.uiAmount) })
+    if (!sellRes.Ok) throw "sell failed"
+  })
+})